@@ -0,0 +1,41 @@
+fn classify(inputs: &[f32]) -> usize {
+    fn sanitize(value: f32) -> f32 {
+        if value.is_nan() { 0.0 } else { value.clamp(f32::MIN, f32::MAX) }
+    }
+    fn clamp(r: &mut [f32]) {
+        for value in r.iter_mut() { *value = value.clamp(-4.0, 4.0); }
+    }
+    #[allow(unused_variables)]
+    fn add(a: f32, b: f32) -> f32 {
+        a + b
+    }
+    #[allow(unused_variables)]
+    fn divide(a: f32, b: f32) -> f32 {
+        if b == 0.0 { a } else { a / b }
+    }
+    #[allow(unused_variables)]
+    fn multiply(a: f32, b: f32) -> f32 {
+        a * b
+    }
+    let input = |index: usize| inputs[index % inputs.len()];
+    let mut r: [f32; 3] = [0.0, 0.5, 1.0];
+    r[0] = sanitize(add(r[0], input(5)));
+    clamp(&mut r);
+    if r[0] < r[2] {
+        r[1] = sanitize(divide(r[1], r[0]));
+        clamp(&mut r);
+    }
+    r[0] = sanitize(multiply(r[0], input(2)));
+    clamp(&mut r);
+    if r[1] < r[0] && r[2] < r[0] {
+        r[1] = sanitize(add(r[1], r[2]));
+        clamp(&mut r);
+    }
+    let outputs = &r[..2];
+    let max = outputs.iter().copied().fold(f32::NAN, f32::max);
+    let mut winners = (0..outputs.len()).filter(|&i| outputs[i] == max);
+    match (winners.next(), winners.next()) {
+        (Some(class), None) => class,
+        _ => usize::MAX,
+    }
+}
@@ -1,7 +1,10 @@
 use gym_rs::{envs::classical_control::cartpole::CartPoleEnv, utils::renderer::RenderMode};
 use lgp::{
     core::{
-        algorithm::{EventHooks, GeneticAlgorithm, HyperParameters},
+        algorithm::{
+            CrossoverMethod, EventHooks, GeneticAlgorithm, HyperParameters, ParentSelection,
+            ReplacementStrategy,
+        },
         instruction::InstructionGeneratorParameters,
         program::ProgramGeneratorParameters,
     },
@@ -19,6 +22,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         population_size: 1,
         gap: 0.5,
         n_crossovers: 0.5,
+        adaptive_mutation: None,
+        fitness_weighted_fill: false,
+        n_random_immigrants: 0,
+        dedup_population: false,
+        multi_objective: false,
+        replacement: ReplacementStrategy::Generational,
+        crossover: CrossoverMethod::TwoPoint,
+        parent_selection: ParentSelection::Uniform,
+        surrogate_cutoff: None,
+        memory_budget: None,
+        snapshot_path: None,
+        time_budget: None,
         n_mutations: 0.5,
         max_generations: 1,
         fitness_parameters: ReinforcementLearningParameters::new(5, 500, input),
@@ -40,7 +55,10 @@ mod tests {
     use gym_rs::{envs::classical_control::cartpole::CartPoleEnv, utils::renderer::RenderMode};
     use lgp::{
         core::{
-            algorithm::{EventHooks, GeneticAlgorithm, HyperParameters},
+            algorithm::{
+                CrossoverMethod, EventHooks, GeneticAlgorithm, HyperParameters, ParentSelection,
+                ReplacementStrategy,
+            },
             instruction::InstructionGeneratorParameters,
             program::ProgramGeneratorParameters,
         },
@@ -60,6 +78,18 @@ mod tests {
             population_size: 10,
             gap: 0.5,
             n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
             n_mutations: 0.5,
             max_generations: 100,
             fitness_parameters: ReinforcementLearningParameters::new(5, 500, input),
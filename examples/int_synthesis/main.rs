@@ -0,0 +1,156 @@
+use std::error;
+
+use lgp::{
+    core::{
+        algorithm::{EventHooks, GeneticAlgorithm, HyperParameters},
+        characteristics::Fitness,
+        inputs::{Inputs, ValidInput},
+        instruction::InstructionGeneratorParameters,
+        int_registers::I32,
+        program::{Program, ProgramGeneratorParameters},
+        registers::R32,
+    },
+    extensions::regression::{RegressionInput, RegressionParameters},
+    utils::executables::{
+        integer_add, integer_divide, integer_multiply, integer_subtract, load_one, Executables,
+    },
+};
+use serde::Serialize;
+
+/// The function being synthesized.
+fn target(x: I32) -> I32 {
+    x * (x + 3)
+}
+
+const SAMPLES: std::ops::RangeInclusive<I32> = -10..=10;
+const MAX_INSTRUCTIONS: usize = 12;
+const MAX_GENERATIONS: usize = 500;
+
+/// Integer arithmetic plus the constant 1, from which the evolved programs build their offsets.
+const EXECUTABLES: Executables = &[
+    integer_add,
+    integer_subtract,
+    integer_multiply,
+    integer_divide,
+    load_one,
+];
+
+/// A sample `x` paired with `target(x)`.
+#[derive(Clone, Debug, Serialize)]
+struct Sample(I32);
+
+impl ValidInput for Sample {
+    const N_INPUT_REGISTERS: usize = 1;
+    const N_ACTION_REGISTERS: usize = 1;
+    const AVAILABLE_EXECUTABLES: Executables = EXECUTABLES;
+
+    fn flat(&self) -> Vec<R32> {
+        vec![self.0 as R32]
+    }
+
+    fn feature_names() -> Option<Vec<&'static str>> {
+        Some(vec!["x"])
+    }
+}
+
+impl RegressionInput for Sample {
+    fn get_target(&self) -> R32 {
+        target(self.0) as R32
+    }
+}
+
+struct IntSynthesisLgp;
+
+impl GeneticAlgorithm for IntSynthesisLgp {
+    type O = Program<RegressionParameters<Sample>>;
+}
+
+fn samples() -> Inputs<Sample> {
+    SAMPLES.map(Sample).collect()
+}
+
+/// Evolves programs until one reproduces `target` on every sample, returning the best found.
+fn evolve() -> Result<Program<RegressionParameters<Sample>>, Box<dyn error::Error>> {
+    let mut hyper_params = HyperParameters::builder()
+        .with_max_generations(MAX_GENERATIONS)
+        .with_fitness_parameters(RegressionParameters::new(samples()))
+        .with_program_parameters(ProgramGeneratorParameters::new(
+            MAX_INSTRUCTIONS,
+            InstructionGeneratorParameters::from::<Sample>(2),
+        ))
+        .build()?;
+
+    let population = IntSynthesisLgp::execute(
+        &mut hyper_params,
+        EventHooks::default().with_stop_condition(&mut |population| {
+            population.first().and_then(|best| best.get_fitness()) == Some(0.)
+        }),
+    )?;
+
+    Ok(population
+        .first()
+        .ok_or("The population is empty.")?
+        .clone())
+}
+
+fn main() -> Result<(), Box<dyn error::Error>> {
+    let best = evolve()?;
+
+    println!("{}", best.to_assembly());
+    println!(
+        "mean absolute error: {}",
+        best.get_fitness()
+            .ok_or("The best program was never evaluated.")?
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use lgp::{
+        core::{
+            characteristics::Fitness,
+            instruction::{Instruction, Mode},
+            program::Program,
+            registers::Registers,
+        },
+        extensions::regression::RegressionParameters,
+        utils::executables::{integer_add, integer_multiply, load_one},
+    };
+
+    use super::{evolve, samples, Sample};
+
+    #[test]
+    fn given_a_hand_written_program_when_evaluated_then_it_matches_the_target_exactly() {
+        // R[1] = x + 3, then R[0] = x * R[1].
+        let mut program = Program::<RegressionParameters<Sample>>::new(
+            [
+                Instruction::new(0, 0, Mode::External, integer_add),
+                Instruction::new(2, 0, Mode::External, load_one),
+                Instruction::new(1, 0, Mode::External, integer_add),
+                Instruction::new(1, 2, Mode::Internal, integer_add),
+                Instruction::new(1, 2, Mode::Internal, integer_add),
+                Instruction::new(1, 2, Mode::Internal, integer_add),
+                Instruction::new(0, 1, Mode::Internal, integer_multiply),
+            ]
+            .into_iter()
+            .collect(),
+            Registers::new(3),
+            None,
+        );
+
+        assert_eq!(
+            program.eval_fitness(&mut RegressionParameters::new(samples())),
+            0.
+        );
+    }
+
+    #[test]
+    fn given_the_seeded_generator_when_evolving_then_the_target_is_synthesized() {
+        let best = evolve().unwrap();
+
+        assert_eq!(best.get_fitness(), Some(0.));
+        assert!(!best.instructions.is_empty());
+    }
+}
@@ -17,18 +17,13 @@ async fn main() -> Result<(), Box<dyn error::Error>> {
     let ContentFilePair(_, file) = get_iris_content().await?;
     let inputs = IrisLgp::load_inputs(file.path());
 
-    let mut hyper_params = HyperParameters {
-        population_size: 100,
-        max_generations: 100,
-        gap: 0.5,
-        n_mutations: 0.5,
-        n_crossovers: 0.5,
-        fitness_parameters: ClassificationParameters::new(inputs),
-        program_parameters: ProgramGeneratorParameters::new(
+    let mut hyper_params = HyperParameters::builder()
+        .with_fitness_parameters(ClassificationParameters::new(inputs))
+        .with_program_parameters(ProgramGeneratorParameters::new(
             100,
             InstructionGeneratorParameters::from::<IrisInput>(1),
-        ),
-    };
+        ))
+        .build()?;
 
     IrisLgp::execute(&mut hyper_params, EventHooks::default())?;
     Ok(())
@@ -38,7 +33,10 @@ async fn main() -> Result<(), Box<dyn error::Error>> {
 mod tests {
     use lgp::{
         core::{
-            algorithm::{EventHooks, GeneticAlgorithm, HyperParameters, Loader},
+            algorithm::{
+                BreedParameters, CrossoverMethod, EventHooks, GeneticAlgorithm, HyperParameters,
+                Loader, ParentSelection, ReplacementStrategy,
+            },
             instruction::InstructionGeneratorParameters,
             program::{Program, ProgramGeneratorParameters},
         },
@@ -67,6 +65,18 @@ mod tests {
                 gap: 0.5,
                 n_mutations: 0.5,
                 n_crossovers: 0.5,
+                adaptive_mutation: None,
+                fitness_weighted_fill: false,
+                n_random_immigrants: 0,
+                dedup_population: false,
+                multi_objective: false,
+                replacement: ReplacementStrategy::Generational,
+                crossover: CrossoverMethod::TwoPoint,
+                parent_selection: ParentSelection::Uniform,
+                surrogate_cutoff: None,
+                memory_budget: None,
+                snapshot_path: None,
+                time_budget: None,
                 fitness_parameters: ClassificationParameters::new(inputs),
                 program_parameters: ProgramGeneratorParameters::new(
                     100,
@@ -107,6 +117,18 @@ mod tests {
                 gap: 0.5,
                 n_mutations: 0.5,
                 n_crossovers: 0.,
+                adaptive_mutation: None,
+                fitness_weighted_fill: false,
+                n_random_immigrants: 0,
+                dedup_population: false,
+                multi_objective: false,
+                replacement: ReplacementStrategy::Generational,
+                crossover: CrossoverMethod::TwoPoint,
+                parent_selection: ParentSelection::Uniform,
+                surrogate_cutoff: None,
+                memory_budget: None,
+                snapshot_path: None,
+                time_budget: None,
                 fitness_parameters: ClassificationParameters::new(inputs),
                 program_parameters: ProgramGeneratorParameters::new(
                     100,
@@ -147,6 +169,18 @@ mod tests {
                 gap: 0.5,
                 n_mutations: 0.,
                 n_crossovers: 0.5,
+                adaptive_mutation: None,
+                fitness_weighted_fill: false,
+                n_random_immigrants: 0,
+                dedup_population: false,
+                multi_objective: false,
+                replacement: ReplacementStrategy::Generational,
+                crossover: CrossoverMethod::TwoPoint,
+                parent_selection: ParentSelection::Uniform,
+                surrogate_cutoff: None,
+                memory_budget: None,
+                snapshot_path: None,
+                time_budget: None,
                 fitness_parameters: ClassificationParameters::new(inputs),
                 program_parameters: ProgramGeneratorParameters::new(
                     100,
@@ -188,6 +222,18 @@ mod tests {
                 gap: 0.5,
                 n_mutations: 0.,
                 n_crossovers: 0.,
+                adaptive_mutation: None,
+                fitness_weighted_fill: false,
+                n_random_immigrants: 0,
+                dedup_population: false,
+                multi_objective: false,
+                replacement: ReplacementStrategy::Generational,
+                crossover: CrossoverMethod::TwoPoint,
+                parent_selection: ParentSelection::Uniform,
+                surrogate_cutoff: None,
+                memory_budget: None,
+                snapshot_path: None,
+                time_budget: None,
                 fitness_parameters: ClassificationParameters::new(inputs),
                 program_parameters: ProgramGeneratorParameters::new(
                     100,
@@ -245,6 +291,18 @@ mod tests {
                 gap: 0.5,
                 n_mutations: 0.,
                 n_crossovers: 0.5,
+                adaptive_mutation: None,
+                fitness_weighted_fill: false,
+                n_random_immigrants: 0,
+                dedup_population: false,
+                multi_objective: false,
+                replacement: ReplacementStrategy::Generational,
+                crossover: CrossoverMethod::TwoPoint,
+                parent_selection: ParentSelection::Uniform,
+                surrogate_cutoff: None,
+                memory_budget: None,
+                snapshot_path: None,
+                time_budget: None,
                 fitness_parameters: ClassificationParameters::new(inputs),
                 program_parameters: ProgramGeneratorParameters::new(
                     100,
@@ -263,9 +321,9 @@ mod tests {
 
         IrisLgp::breed(
             &mut population,
-            0f32,
-            0f32,
+            &BreedParameters::new(0f32, 0f32),
             &hyper_params.program_parameters,
+            None,
         );
 
         assert_eq!(population.len(), hyper_params.population_size);
@@ -287,6 +345,18 @@ mod tests {
                 gap: 0.5,
                 n_mutations: 0.,
                 n_crossovers: 0.5,
+                adaptive_mutation: None,
+                fitness_weighted_fill: false,
+                n_random_immigrants: 0,
+                dedup_population: false,
+                multi_objective: false,
+                replacement: ReplacementStrategy::Generational,
+                crossover: CrossoverMethod::TwoPoint,
+                parent_selection: ParentSelection::Uniform,
+                surrogate_cutoff: None,
+                memory_budget: None,
+                snapshot_path: None,
+                time_budget: None,
                 fitness_parameters: ClassificationParameters::new(inputs),
                 program_parameters: ProgramGeneratorParameters::new(
                     100,
@@ -322,6 +392,18 @@ mod tests {
                 gap: 0.5,
                 n_mutations: 0.,
                 n_crossovers: 0.5,
+                adaptive_mutation: None,
+                fitness_weighted_fill: false,
+                n_random_immigrants: 0,
+                dedup_population: false,
+                multi_objective: false,
+                replacement: ReplacementStrategy::Generational,
+                crossover: CrossoverMethod::TwoPoint,
+                parent_selection: ParentSelection::Uniform,
+                surrogate_cutoff: None,
+                memory_budget: None,
+                snapshot_path: None,
+                time_budget: None,
                 fitness_parameters: ClassificationParameters::new(inputs),
                 program_parameters: ProgramGeneratorParameters::new(
                     100,
@@ -373,4 +455,62 @@ mod tests {
         assert_ne!(inputs.len(), 0);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn given_best_iris_program_when_saved_and_reloaded_then_held_out_predictions_match(
+    ) -> Result<(), Box<dyn error::Error>> {
+        let ContentFilePair(_, tmp_file) = get_iris_content().await?;
+        let inputs = IrisLgp::load_inputs(tmp_file.path());
+
+        // Every fifth row is held out from training.
+        let (held_out, training): (Vec<_>, Vec<_>) = inputs
+            .into_iter()
+            .enumerate()
+            .partition(|(index, _)| index % 5 == 0);
+        let held_out: Vec<IrisInput> = held_out.into_iter().map(|(_, input)| input).collect();
+        let training: Vec<IrisInput> = training.into_iter().map(|(_, input)| input).collect();
+
+        let mut hyper_params: HyperParameters<Program<ClassificationParameters<IrisInput>>> =
+            HyperParameters {
+                population_size: 100,
+                max_generations: 20,
+                gap: 0.5,
+                n_mutations: 0.5,
+                n_crossovers: 0.5,
+                adaptive_mutation: None,
+                fitness_weighted_fill: false,
+                n_random_immigrants: 0,
+                dedup_population: false,
+                multi_objective: false,
+                replacement: ReplacementStrategy::Generational,
+                crossover: CrossoverMethod::TwoPoint,
+                parent_selection: ParentSelection::Uniform,
+                surrogate_cutoff: None,
+                memory_budget: None,
+                snapshot_path: None,
+                time_budget: None,
+                fitness_parameters: ClassificationParameters::new(training),
+                program_parameters: ProgramGeneratorParameters::new(
+                    100,
+                    InstructionGeneratorParameters::from::<IrisInput>(1),
+                ),
+            };
+
+        let mut population = IrisLgp::execute(&mut hyper_params, EventHooks::default())?;
+        IrisLgp::rank(&mut population, &mut hyper_params.fitness_parameters);
+        let best = population.first().unwrap();
+
+        let checkpoint = tempfile::NamedTempFile::new()?;
+        best.save(checkpoint.path())?;
+        let reloaded = Program::<ClassificationParameters<IrisInput>>::load(checkpoint.path())?;
+
+        for input in held_out.iter() {
+            self::assert_eq!(
+                reloaded.predict(input, &hyper_params.fitness_parameters),
+                best.predict(input, &hyper_params.fitness_parameters)
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -7,11 +7,12 @@ use strum::EnumCount;
 use lgp::{
     core::{
         algorithm::{GeneticAlgorithm, Loader},
-        inputs::ValidInput,
+        inputs::{validate_feature_counts, DatasetError, Inputs, ValidInput},
+        instruction::Mode,
         program::Program,
         registers::R32,
     },
-    extensions::classification::{ClassificationInput, ClassificationParameters},
+    extensions::classification::{validate_classes, ClassificationInput, ClassificationParameters},
 };
 
 use std::error;
@@ -92,6 +93,7 @@ impl Display for IrisInput {
 impl ValidInput for IrisInput {
     const N_INPUT_REGISTERS: usize = 4;
     const N_ACTION_REGISTERS: usize = 3;
+    const AVAILABLE_MODES: &'static [Mode] = Mode::ALL;
 
     fn flat(&self) -> Vec<R32> {
         [
@@ -102,4 +104,18 @@ impl ValidInput for IrisInput {
         ]
         .to_vec()
     }
+
+    fn feature_names() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "sepal_length",
+            "sepal_width",
+            "petal_length",
+            "petal_width",
+        ])
+    }
+
+    fn validate_dataset(inputs: &Inputs<Self>) -> Result<(), DatasetError> {
+        validate_feature_counts(inputs)?;
+        validate_classes(inputs)
+    }
 }
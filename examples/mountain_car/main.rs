@@ -15,18 +15,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let environment = MountainCarEnv::new(RenderMode::Human, None);
     let input = MountainCarInput::new(environment);
 
-    let mut hyper_params = HyperParameters {
-        population_size: 1,
-        gap: 0.5,
-        n_crossovers: 0.5,
-        n_mutations: 0.5,
-        max_generations: 1,
-        fitness_parameters: ReinforcementLearningParameters::new(5, 200, input),
-        program_parameters: ProgramGeneratorParameters::new(
+    let mut hyper_params = HyperParameters::builder()
+        .with_population_size(1)
+        .with_max_generations(1)
+        .with_fitness_parameters(ReinforcementLearningParameters::new(5, 200, input))
+        .with_program_parameters(ProgramGeneratorParameters::new(
             100,
             InstructionGeneratorParameters::from::<MountainCarInput>(1),
-        ),
-    };
+        ))
+        .build()?;
 
     MountainCarLgp::execute(&mut hyper_params, EventHooks::default())?;
 
@@ -40,7 +37,10 @@ mod tests {
     };
     use lgp::{
         core::{
-            algorithm::{EventHooks, GeneticAlgorithm, HyperParameters},
+            algorithm::{
+                CrossoverMethod, EventHooks, GeneticAlgorithm, HyperParameters, ParentSelection,
+                ReplacementStrategy,
+            },
             instruction::InstructionGeneratorParameters,
             program::ProgramGeneratorParameters,
         },
@@ -62,6 +62,18 @@ mod tests {
             population_size: 100,
             gap: 0.5,
             n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
             n_mutations: 0.5,
             max_generations: 100,
             fitness_parameters: ReinforcementLearningParameters::new(5, 200, input),
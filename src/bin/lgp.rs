@@ -0,0 +1,298 @@
+//! Runs an evolution described by a TOML config, e.g.
+//!
+//! ```toml
+//! dataset = "iris.csv"
+//! extension = "classification"
+//! n_features = 4
+//! n_classes = 3
+//! max_instructions = 100
+//!
+//! [hyper_parameters]
+//! population_size = 100
+//! max_generations = 50
+//! crossover = "Uniform"
+//! replacement = { HillClimb = { epsilon = 0.01 } }
+//! ```
+//!
+//! Usage: `lgp <config.toml>`.
+//!
+//! Datasets are header-less CSVs holding `n_features` values followed by a label per row: a
+//! class index for `classification`, or a real-valued target for `regression`, whose best
+//! program is reported with its mean absolute error. `reinforcement_learning` is not supported,
+//! as it needs an environment to interact with rather than a dataset.
+//!
+//! `[hyper_parameters]` accepts every plain-data field of `HyperParameters`, defaulting to
+//! `HyperParameters::builder`. Output register counts are compile-time constants, so
+//! `n_classes` (required for classification only) must lie within `MIN_CLASSES..=MAX_CLASSES`;
+//! the feature count is read at runtime.
+
+use std::{env, error::Error, fs, path::PathBuf, process};
+
+use serde::{Deserialize, Serialize};
+
+use lgp::{
+    core::{
+        algorithm::{EventHooks, GeneticAlgorithm, HyperParametersBuilder, Loader},
+        characteristics::Fitness,
+        inputs::{DatasetError, Inputs, ValidInput},
+        instruction::InstructionGeneratorParameters,
+        program::{Program, ProgramGeneratorParameters},
+        registers::R32,
+    },
+    extensions::{
+        classification::{ClassificationInput, ClassificationParameters},
+        regression::{RegressionInput, RegressionParameters},
+    },
+};
+
+const MIN_CLASSES: usize = 2;
+const MAX_CLASSES: usize = 16;
+/// Widest dataset accepted. Instructions only read the configured `n_features`, so this merely
+/// bounds the `N_INPUT_REGISTERS` declared by `CsvInput`.
+const MAX_FEATURES: usize = 1024;
+/// Registers given to regression programs: the output plus one scratch register.
+const REGRESSION_REGISTERS: usize = 2;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+enum Extension {
+    Classification,
+    Regression,
+    ReinforcementLearning,
+}
+
+#[derive(Deserialize, Debug)]
+struct Config {
+    dataset: PathBuf,
+    extension: Extension,
+    n_features: usize,
+    n_classes: Option<usize>,
+    #[serde(default = "default_max_instructions")]
+    max_instructions: usize,
+}
+
+/// The `[hyper_parameters]` table of a config, read once the program type (and so the
+/// `HyperParametersBuilder` standing in for `B`) is known.
+#[derive(Deserialize)]
+struct HyperParameterSection<B> {
+    #[serde(default)]
+    hyper_parameters: B,
+}
+
+fn default_max_instructions() -> usize {
+    100
+}
+
+/// A CSV row of feature values followed by the class index.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, PartialOrd)]
+struct CsvInput<const N_CLASSES: usize>(Vec<R32>);
+
+impl<const N_CLASSES: usize> ValidInput for CsvInput<N_CLASSES> {
+    const N_INPUT_REGISTERS: usize = MAX_FEATURES;
+    const N_ACTION_REGISTERS: usize = N_CLASSES;
+
+    fn flat(&self) -> Vec<R32> {
+        self.0[..self.0.len() - 1].to_vec()
+    }
+
+    /// `ClassificationParameters::try_new` checks the classes.
+    fn validate_dataset(inputs: &Inputs<Self>) -> Result<(), DatasetError> {
+        validate_row_widths(inputs.iter().map(|input| input.0.as_slice()))
+    }
+}
+
+impl<const N_CLASSES: usize> ClassificationInput for CsvInput<N_CLASSES> {
+    fn get_class(&self) -> usize {
+        self.0[self.0.len() - 1] as usize
+    }
+}
+
+/// A CSV row of feature values followed by the regression target.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, PartialOrd)]
+struct CsvTargetInput(Vec<R32>);
+
+impl ValidInput for CsvTargetInput {
+    const N_INPUT_REGISTERS: usize = MAX_FEATURES;
+    const N_ACTION_REGISTERS: usize = 1;
+
+    fn flat(&self) -> Vec<R32> {
+        self.0[..self.0.len() - 1].to_vec()
+    }
+
+    fn validate_dataset(inputs: &Inputs<Self>) -> Result<(), DatasetError> {
+        validate_row_widths(inputs.iter().map(|input| input.0.as_slice()))
+    }
+}
+
+impl RegressionInput for CsvTargetInput {
+    fn get_target(&self) -> R32 {
+        self.0[self.0.len() - 1]
+    }
+}
+
+/// Every row must hold at least one feature and a label, and be as wide as the first;
+/// `check_feature_count` checks the first against the configured feature count.
+fn validate_row_widths<'a>(rows: impl Iterator<Item = &'a [R32]>) -> Result<(), DatasetError> {
+    let mut width = None;
+
+    for (row, values) in rows.enumerate() {
+        let expected = *width.get_or_insert(values.len());
+
+        if values.len() < 2 || values.len() != expected {
+            return Err(DatasetError::FeatureCount {
+                row,
+                expected: expected.saturating_sub(1),
+                found: values.len().saturating_sub(1),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects datasets that are empty or whose rows don't hold the configured feature count.
+fn check_feature_count<T: ValidInput>(
+    config: &Config,
+    inputs: &Inputs<T>,
+) -> Result<(), Box<dyn Error>> {
+    match inputs.first().map(|input| input.flat().len()) {
+        None => Err("The dataset is empty.".into()),
+        Some(found) if found != config.n_features => Err(DatasetError::FeatureCount {
+            row: 0,
+            expected: config.n_features,
+            found,
+        }
+        .into()),
+        Some(_) => Ok(()),
+    }
+}
+
+struct CsvLgp<const N_CLASSES: usize>;
+
+impl<const N_CLASSES: usize> GeneticAlgorithm for CsvLgp<N_CLASSES> {
+    type O = Program<ClassificationParameters<CsvInput<N_CLASSES>>>;
+}
+
+impl<const N_CLASSES: usize> Loader for CsvLgp<N_CLASSES> {
+    type InputType = CsvInput<N_CLASSES>;
+}
+
+struct CsvRegressionLgp;
+
+impl GeneticAlgorithm for CsvRegressionLgp {
+    type O = Program<RegressionParameters<CsvTargetInput>>;
+}
+
+impl Loader for CsvRegressionLgp {
+    type InputType = CsvTargetInput;
+}
+
+fn run_classification<const N_CLASSES: usize>(
+    config: &Config,
+    config_source: &str,
+) -> Result<(), Box<dyn Error>> {
+    let inputs = CsvLgp::<N_CLASSES>::try_load_inputs(&config.dataset)?;
+    check_feature_count(config, &inputs)?;
+
+    let HyperParameterSection::<HyperParametersBuilder<<CsvLgp<N_CLASSES> as GeneticAlgorithm>::O>> {
+        hyper_parameters: builder,
+    } = toml::from_str(config_source)?;
+    let mut hyper_params = builder
+        .with_fitness_parameters(ClassificationParameters::try_new(inputs)?)
+        .with_program_parameters(ProgramGeneratorParameters::new(
+            config.max_instructions,
+            InstructionGeneratorParameters::new(N_CLASSES + 1, config.n_features),
+        ))
+        .build()?;
+
+    let mut population = CsvLgp::<N_CLASSES>::execute(&mut hyper_params, EventHooks::default())?;
+    population.sort();
+
+    let best = population.first().ok_or("The population is empty.")?;
+    println!("{}", best.to_assembly());
+    println!(
+        "fitness: {}",
+        best.get_fitness()
+            .ok_or("The best program was never evaluated.")?
+    );
+
+    Ok(())
+}
+
+fn run_regression(config: &Config, config_source: &str) -> Result<(), Box<dyn Error>> {
+    let inputs = CsvRegressionLgp::try_load_inputs(&config.dataset)?;
+    check_feature_count(config, &inputs)?;
+
+    let HyperParameterSection::<HyperParametersBuilder<<CsvRegressionLgp as GeneticAlgorithm>::O>> {
+        hyper_parameters: builder,
+    } = toml::from_str(config_source)?;
+    let mut hyper_params = builder
+        .with_fitness_parameters(RegressionParameters::new(inputs))
+        .with_program_parameters(ProgramGeneratorParameters::new(
+            config.max_instructions,
+            InstructionGeneratorParameters::new(REGRESSION_REGISTERS, config.n_features),
+        ))
+        .build()?;
+
+    let mut population = CsvRegressionLgp::execute(&mut hyper_params, EventHooks::default())?;
+    population.sort();
+
+    let best = population.first().ok_or("The population is empty.")?;
+    println!("{}", best.to_assembly());
+    println!(
+        "mean absolute error: {}",
+        best.get_fitness()
+            .ok_or("The best program was never evaluated.")?
+    );
+
+    Ok(())
+}
+
+/// Monomorphizes `run_classification` once per supported class count.
+macro_rules! dispatch {
+    ($config:expr, $config_source:expr, $n_classes:expr; $($supported:literal),+) => {
+        match $n_classes {
+            $($supported => run_classification::<$supported>($config, $config_source),)+
+            n_classes => Err(format!(
+                "The number of classes must be within [{}, {}], got {}.",
+                MIN_CLASSES, MAX_CLASSES, n_classes
+            )
+            .into()),
+        }
+    };
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let config_path = env::args().nth(1).ok_or("Usage: lgp <config.toml>")?;
+    let config_source = fs::read_to_string(config_path)?;
+    let config: Config = toml::from_str(&config_source)?;
+
+    if !(1..=MAX_FEATURES).contains(&config.n_features) {
+        return Err(format!(
+            "The number of features must be within [1, {}], got {}.",
+            MAX_FEATURES, config.n_features
+        )
+        .into());
+    }
+
+    match config.extension {
+        Extension::Classification => {
+            let n_classes = config
+                .n_classes
+                .ok_or("Classification configs must set n_classes.")?;
+            dispatch!(&config, &config_source, n_classes; 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16)
+        }
+        Extension::Regression => run_regression(&config, &config_source),
+        Extension::ReinforcementLearning => Err(
+            "Reinforcement learning needs an environment to interact with; see the examples."
+                .into(),
+        ),
+    }
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
@@ -1,23 +1,145 @@
 use core::fmt;
-use std::path::PathBuf;
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    io::Write,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use csv::ReaderBuilder;
+use derive_new::new;
+use itertools::Itertools;
 use more_asserts::{assert_ge, assert_le};
 use ordered_float::OrderedFloat;
-use rand::prelude::{IteratorRandom, SliceRandom};
-use serde::{de::DeserializeOwned, Serialize};
+use rand::{
+    distributions::WeightedIndex,
+    prelude::{Distribution, IteratorRandom, SliceRandom},
+    Rng,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
-    core::characteristics::{Breed, Fitness, Generate},
-    utils::random::generator,
+    core::characteristics::{Breed, Fitness, FitnessScore, Generate, Length, Lineage},
+    utils::{
+        export::{create_jsonl, write_jsonl_line},
+        random::generator,
+    },
 };
 
 use super::{
+    archive::QualityDiversityArchive,
     characteristics::Mutate,
+    fitness_cache::{CachedFitness, FitnessCache},
+    genealogy::Genealogy,
     inputs::{Inputs, ValidInput},
-    population::Population,
+    pareto::{crowding_distances, non_dominated_sort},
+    population::{ComplexityBenchmark, Population},
 };
 
+/// How the next generation is formed from the ranked population.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum ReplacementStrategy {
+    /// Truncation selection followed by crossover, mutation and cloning.
+    #[default]
+    Generational,
+    /// Each individual is a lineage whose mutated child replaces it only if the child's fitness
+    /// improves on it by at least `epsilon`, i.e. a (1+1) evolution strategy per lineage.
+    HillClimb { epsilon: f32 },
+}
+
+/// How `breed` recombines two parents into a crossover child.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum CrossoverMethod {
+    /// Swaps a randomly chosen segment of one parent with a segment of the other.
+    #[default]
+    TwoPoint,
+    /// Draws each gene independently from either parent (see `Breed::uniform_crossover`).
+    Uniform,
+    /// Swaps equal-length segments at the same positions, preserving program length.
+    Homologous,
+}
+
+/// How `breed` picks the two parents of each child from the survivors.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum ParentSelection {
+    #[default]
+    Uniform,
+    /// Draws parents with a probability proportional to their fitness (shifted to be
+    /// non-negative), falling back to uniform when every weight is zero.
+    FitnessWeighted,
+    /// Draws each parent as the fittest of this many survivors, sampled with replacement. Larger
+    /// tournaments raise the selection pressure.
+    Tournament(usize),
+}
+
+/// How `breed` refills the population from the survivors of selection.
+#[derive(Clone, Debug, PartialEq, Serialize, new)]
+pub struct BreedParameters {
+    /// Fraction of the open spots filled by mutation children.
+    pub n_mutations: f32,
+    /// Fraction of the open spots filled by crossover children.
+    pub n_crossovers: f32,
+    /// Bias the clones used to refill the population towards fitter survivors.
+    #[new(default)]
+    pub fitness_weighted_fill: bool,
+    /// Slots of the refill given to freshly generated individuals instead of clones.
+    #[new(default)]
+    pub n_random_immigrants: usize,
+    #[new(default)]
+    pub crossover: CrossoverMethod,
+    #[new(default)]
+    pub parent_selection: ParentSelection,
+}
+
+impl<O> From<&HyperParameters<O>> for BreedParameters
+where
+    O: Fitness + Mutate + Generate,
+{
+    fn from(hyper_params: &HyperParameters<O>) -> Self {
+        BreedParameters {
+            n_mutations: hyper_params.n_mutations,
+            n_crossovers: hyper_params.n_crossovers,
+            fitness_weighted_fill: hyper_params.fitness_weighted_fill,
+            n_random_immigrants: hyper_params.n_random_immigrants,
+            crossover: hyper_params.crossover,
+            parent_selection: hyper_params.parent_selection,
+        }
+    }
+}
+
+/// A deterministic k-way tournament: the fittest of `size` random candidates wins.
+#[derive(Clone, Debug, Serialize, new)]
+pub struct TournamentParameters {
+    pub size: usize,
+    /// Whether the same individual may be drawn more than once per tournament.
+    pub replacement: bool,
+}
+
+/// Raises the mutation rate while the best fitness stagnates.
+#[derive(Clone, Debug, Serialize, Deserialize, new)]
+pub struct AdaptiveMutation {
+    /// Generations without improvement tolerated before the rate is raised.
+    pub patience: usize,
+    /// Multiplier applied to the rate each stagnant generation past `patience`.
+    pub factor: f32,
+    pub max_rate: f32,
+}
+
+impl AdaptiveMutation {
+    /// The mutation rate for the next generation, given how many generations the best fitness
+    /// has gone without improving. Improvement resets the rate to `base_rate`.
+    pub fn next_rate(&self, base_rate: f32, current_rate: f32, n_stagnant: usize) -> f32 {
+        if n_stagnant == 0 {
+            base_rate
+        } else if n_stagnant >= self.patience {
+            (current_rate * self.factor).min(self.max_rate)
+        } else {
+            current_rate
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct HyperParameters<OrganismType>
 where
@@ -27,30 +149,532 @@ where
     pub gap: f32,
     pub n_mutations: f32,
     pub n_crossovers: f32,
+    /// Scales `n_mutations` up while the population stagnates.
+    pub adaptive_mutation: Option<AdaptiveMutation>,
+    /// Bias the clones used to refill the population towards fitter survivors.
+    pub fitness_weighted_fill: bool,
+    /// Slots of the refill given to freshly generated individuals instead of clones.
+    pub n_random_immigrants: usize,
+    /// Replace exact duplicates with freshly generated individuals after breeding.
+    pub dedup_population: bool,
+    /// Select survivors by Pareto rank and crowding distance over `Fitness::objectives`.
+    pub multi_objective: bool,
+    pub replacement: ReplacementStrategy,
+    pub crossover: CrossoverMethod,
+    pub parent_selection: ParentSelection,
+    /// Percentile (0 to 1) of surrogate scores below which individuals skip full evaluation.
+    pub surrogate_cutoff: Option<f32>,
+    /// Maximum number of bytes the population may occupy.
+    pub memory_budget: Option<usize>,
+    /// JSONL file receiving each generation's ranked fitness scores, one line per generation.
+    pub snapshot_path: Option<PathBuf>,
+    /// Wall-clock limit checked before each generation; the run stops early once it is spent.
+    pub time_budget: Option<Duration>,
     pub max_generations: usize,
     pub fitness_parameters: OrganismType::FitnessParameters,
     pub program_parameters: OrganismType::GeneratorParameters,
 }
 
+/// State carried across the generations of a run, kept apart from `HyperParameters` so one
+/// configuration can drive several runs. Everything is opt-in and empty by default.
+#[derive(Debug, new)]
+pub struct RunState<OrganismType>
+where
+    OrganismType: Fitness,
+{
+    /// Collects the best individual per behavioral cell across generations.
+    #[new(default)]
+    pub archive: Option<QualityDiversityArchive<OrganismType>>,
+    /// Records the parents of every bred child.
+    #[new(default)]
+    pub lineage: Option<Genealogy>,
+    /// Reuses evaluations across individuals sharing a fitness key. Takes precedence over
+    /// `HyperParameters::surrogate_cutoff`.
+    #[new(default)]
+    pub fitness_cache: Option<FitnessCache>,
+}
+
+impl<OrganismType> RunState<OrganismType>
+where
+    OrganismType: Fitness,
+{
+    pub fn with_archive(self, archive: QualityDiversityArchive<OrganismType>) -> Self {
+        Self {
+            archive: Some(archive),
+            ..self
+        }
+    }
+
+    pub fn with_lineage(self, lineage: Genealogy) -> Self {
+        Self {
+            lineage: Some(lineage),
+            ..self
+        }
+    }
+
+    pub fn with_fitness_cache(self, fitness_cache: FitnessCache) -> Self {
+        Self {
+            fitness_cache: Some(fitness_cache),
+            ..self
+        }
+    }
+}
+
+impl<OrganismType> Default for RunState<OrganismType>
+where
+    OrganismType: Fitness,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The final population of a run and its summary.
+pub type RunOutcome<O> = Result<(Population<O>, RunReport), Box<dyn std::error::Error>>;
+
+/// A hyperparameter combination rejected by `HyperParameters::validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HyperParameterError {
+    EmptyPopulation,
+    GapOutOfRange(f32),
+    MutationRateOutOfRange(f32),
+    CrossoverRateOutOfRange(f32),
+    /// `n_mutations + n_crossovers` exceeds the spots available for children.
+    BreedingRatesExceedOne {
+        n_mutations: f32,
+        n_crossovers: f32,
+    },
+    /// `HyperParametersBuilder::build` was called before a required field was set.
+    MissingField(&'static str),
+    /// `ParentSelection::Tournament` was given no candidates.
+    EmptyTournament,
+    SurrogateCutoffOutOfRange(f32),
+    /// More immigrants than the spots refilled each generation.
+    TooManyImmigrants {
+        n_random_immigrants: usize,
+        n_refilled: usize,
+    },
+    /// `AdaptiveMutation::factor` would lower the mutation rate (or is `NaN`).
+    MutationFactorBelowOne(f32),
+    /// `AdaptiveMutation::max_rate` lies outside `[n_mutations, 1]`.
+    MaxMutationRateOutOfRange(f32),
+    /// `Generate::validate_parameters` rejected the program parameters.
+    InvalidProgramParameters(String),
+    /// `Fitness::validate_fitness_parameters` rejected the fitness parameters.
+    InvalidFitnessParameters(String),
+}
+
+impl fmt::Display for HyperParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HyperParameterError::EmptyPopulation => {
+                write!(f, "The population size must be greater than 0.")
+            }
+            HyperParameterError::GapOutOfRange(gap) => {
+                write!(f, "The gap must be within [0, 1], got {}.", gap)
+            }
+            HyperParameterError::MutationRateOutOfRange(rate) => {
+                write!(f, "The mutation rate must be within [0, 1], got {}.", rate)
+            }
+            HyperParameterError::CrossoverRateOutOfRange(rate) => {
+                write!(f, "The crossover rate must be within [0, 1], got {}.", rate)
+            }
+            HyperParameterError::BreedingRatesExceedOne {
+                n_mutations,
+                n_crossovers,
+            } => write!(
+                f,
+                "The mutation and crossover rates must sum to at most 1, got {} + {}.",
+                n_mutations, n_crossovers
+            ),
+            HyperParameterError::MissingField(field) => write!(f, "`{}` must be set.", field),
+            HyperParameterError::EmptyTournament => {
+                write!(f, "Tournament parent selection needs at least 1 candidate.")
+            }
+            HyperParameterError::SurrogateCutoffOutOfRange(cutoff) => write!(
+                f,
+                "The surrogate cutoff must be within [0, 1], got {}.",
+                cutoff
+            ),
+            HyperParameterError::TooManyImmigrants {
+                n_random_immigrants,
+                n_refilled,
+            } => write!(
+                f,
+                "There must be at most {} random immigrants, one per refilled spot, got {}.",
+                n_refilled, n_random_immigrants
+            ),
+            HyperParameterError::MutationFactorBelowOne(factor) => write!(
+                f,
+                "The adaptive mutation factor must be at least 1, got {}.",
+                factor
+            ),
+            HyperParameterError::MaxMutationRateOutOfRange(max_rate) => write!(
+                f,
+                "The maximum adaptive mutation rate must be within [n_mutations, 1], got {}.",
+                max_rate
+            ),
+            HyperParameterError::InvalidProgramParameters(reason) => {
+                write!(f, "Invalid program parameters: {}", reason)
+            }
+            HyperParameterError::InvalidFitnessParameters(reason) => {
+                write!(f, "Invalid fitness parameters: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HyperParameterError {}
+
+impl<OrganismType> HyperParameters<OrganismType>
+where
+    OrganismType: Fitness + Mutate + Generate,
+{
+    /// Rejects combinations that would otherwise trip assertions mid-run.
+    pub fn validate(&self) -> Result<(), HyperParameterError> {
+        let in_unit_interval = |value: f32| (0f32..=1f32).contains(&value);
+        // The spots `apply_selection` frees up for breeding.
+        let n_refilled = ((1f32 - self.gap) * self.population_size as f32).floor() as usize;
+
+        if self.population_size == 0 {
+            Err(HyperParameterError::EmptyPopulation)
+        } else if !in_unit_interval(self.gap) {
+            Err(HyperParameterError::GapOutOfRange(self.gap))
+        } else if !in_unit_interval(self.n_mutations) {
+            Err(HyperParameterError::MutationRateOutOfRange(
+                self.n_mutations,
+            ))
+        } else if !in_unit_interval(self.n_crossovers) {
+            Err(HyperParameterError::CrossoverRateOutOfRange(
+                self.n_crossovers,
+            ))
+        } else if self.n_mutations + self.n_crossovers > 1f32 {
+            Err(HyperParameterError::BreedingRatesExceedOne {
+                n_mutations: self.n_mutations,
+                n_crossovers: self.n_crossovers,
+            })
+        } else if self.parent_selection == ParentSelection::Tournament(0) {
+            Err(HyperParameterError::EmptyTournament)
+        } else if let Some(cutoff) = self.surrogate_cutoff.filter(|c| !in_unit_interval(*c)) {
+            Err(HyperParameterError::SurrogateCutoffOutOfRange(cutoff))
+        } else if self.replacement == ReplacementStrategy::Generational
+            && self.n_random_immigrants > n_refilled
+        {
+            Err(HyperParameterError::TooManyImmigrants {
+                n_random_immigrants: self.n_random_immigrants,
+                n_refilled,
+            })
+        } else if let Some(AdaptiveMutation {
+            factor, max_rate, ..
+        }) = self.adaptive_mutation
+        {
+            if factor.is_nan() || factor < 1. {
+                Err(HyperParameterError::MutationFactorBelowOne(factor))
+            } else if !(self.n_mutations..=1.).contains(&max_rate) {
+                Err(HyperParameterError::MaxMutationRateOutOfRange(max_rate))
+            } else {
+                self.validate_program_parameters()
+            }
+        } else {
+            self.validate_program_parameters()
+        }
+    }
+
+    fn validate_program_parameters(&self) -> Result<(), HyperParameterError> {
+        OrganismType::validate_parameters(&self.program_parameters)
+            .map_err(HyperParameterError::InvalidProgramParameters)?;
+        OrganismType::validate_fitness_parameters(&self.fitness_parameters)
+            .map_err(HyperParameterError::InvalidFitnessParameters)
+    }
+
+    /// A builder defaulting to 100 individuals over 100 generations, with half the population
+    /// replaced each generation by equal shares of mutation and crossover children.
+    pub fn builder() -> HyperParametersBuilder<OrganismType> {
+        HyperParametersBuilder {
+            population_size: 100,
+            gap: 0.5,
+            n_mutations: 0.5,
+            n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::default(),
+            crossover: CrossoverMethod::default(),
+            parent_selection: ParentSelection::default(),
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
+            max_generations: 100,
+            fitness_parameters: None,
+            program_parameters: None,
+        }
+    }
+
+    /// Estimated peak memory (in bytes) held by a full population.
+    pub fn estimated_memory(&self) -> usize {
+        self.population_size * OrganismType::estimated_memory(&self.program_parameters)
+    }
+
+    pub fn check_memory_budget(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.memory_budget {
+            Some(allowed) if self.estimated_memory() > allowed => Err(format!(
+                "Estimated population memory of {} bytes exceeds the allowed {} bytes.",
+                self.estimated_memory(),
+                allowed
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Fluent alternative to a `HyperParameters` literal, starting from the defaults in
+/// `HyperParameters::builder`. Deserializing one (e.g. from a config file) sets every plain-data
+/// field present; the fitness and program parameters are left to the setters.
+#[derive(Debug, Deserialize)]
+#[serde(default, bound = "", deny_unknown_fields)]
+pub struct HyperParametersBuilder<OrganismType>
+where
+    OrganismType: Fitness + Mutate + Generate,
+{
+    population_size: usize,
+    gap: f32,
+    n_mutations: f32,
+    n_crossovers: f32,
+    adaptive_mutation: Option<AdaptiveMutation>,
+    fitness_weighted_fill: bool,
+    n_random_immigrants: usize,
+    dedup_population: bool,
+    multi_objective: bool,
+    replacement: ReplacementStrategy,
+    crossover: CrossoverMethod,
+    parent_selection: ParentSelection,
+    surrogate_cutoff: Option<f32>,
+    memory_budget: Option<usize>,
+    snapshot_path: Option<PathBuf>,
+    time_budget: Option<Duration>,
+    max_generations: usize,
+    #[serde(skip)]
+    fitness_parameters: Option<OrganismType::FitnessParameters>,
+    #[serde(skip)]
+    program_parameters: Option<OrganismType::GeneratorParameters>,
+}
+
+impl<OrganismType> Default for HyperParametersBuilder<OrganismType>
+where
+    OrganismType: Fitness + Mutate + Generate,
+{
+    fn default() -> Self {
+        HyperParameters::builder()
+    }
+}
+
+impl<OrganismType> HyperParametersBuilder<OrganismType>
+where
+    OrganismType: Fitness + Mutate + Generate,
+{
+    pub fn with_population_size(self, population_size: usize) -> Self {
+        Self {
+            population_size,
+            ..self
+        }
+    }
+
+    pub fn with_gap(self, gap: f32) -> Self {
+        Self { gap, ..self }
+    }
+
+    pub fn with_n_mutations(self, n_mutations: f32) -> Self {
+        Self {
+            n_mutations,
+            ..self
+        }
+    }
+
+    pub fn with_n_crossovers(self, n_crossovers: f32) -> Self {
+        Self {
+            n_crossovers,
+            ..self
+        }
+    }
+
+    pub fn with_adaptive_mutation(self, adaptive_mutation: AdaptiveMutation) -> Self {
+        Self {
+            adaptive_mutation: Some(adaptive_mutation),
+            ..self
+        }
+    }
+
+    pub fn with_fitness_weighted_fill(self, fitness_weighted_fill: bool) -> Self {
+        Self {
+            fitness_weighted_fill,
+            ..self
+        }
+    }
+
+    pub fn with_n_random_immigrants(self, n_random_immigrants: usize) -> Self {
+        Self {
+            n_random_immigrants,
+            ..self
+        }
+    }
+
+    pub fn with_dedup_population(self, dedup_population: bool) -> Self {
+        Self {
+            dedup_population,
+            ..self
+        }
+    }
+
+    pub fn with_multi_objective(self, multi_objective: bool) -> Self {
+        Self {
+            multi_objective,
+            ..self
+        }
+    }
+
+    pub fn with_replacement(self, replacement: ReplacementStrategy) -> Self {
+        Self {
+            replacement,
+            ..self
+        }
+    }
+
+    pub fn with_crossover(self, crossover: CrossoverMethod) -> Self {
+        Self { crossover, ..self }
+    }
+
+    pub fn with_parent_selection(self, parent_selection: ParentSelection) -> Self {
+        Self {
+            parent_selection,
+            ..self
+        }
+    }
+
+    pub fn with_surrogate_cutoff(self, surrogate_cutoff: f32) -> Self {
+        Self {
+            surrogate_cutoff: Some(surrogate_cutoff),
+            ..self
+        }
+    }
+
+    pub fn with_memory_budget(self, memory_budget: usize) -> Self {
+        Self {
+            memory_budget: Some(memory_budget),
+            ..self
+        }
+    }
+
+    pub fn with_snapshot_path(self, snapshot_path: impl Into<PathBuf>) -> Self {
+        Self {
+            snapshot_path: Some(snapshot_path.into()),
+            ..self
+        }
+    }
+
+    pub fn with_time_budget(self, time_budget: Duration) -> Self {
+        Self {
+            time_budget: Some(time_budget),
+            ..self
+        }
+    }
+
+    pub fn with_max_generations(self, max_generations: usize) -> Self {
+        Self {
+            max_generations,
+            ..self
+        }
+    }
+
+    pub fn with_fitness_parameters(
+        self,
+        fitness_parameters: OrganismType::FitnessParameters,
+    ) -> Self {
+        Self {
+            fitness_parameters: Some(fitness_parameters),
+            ..self
+        }
+    }
+
+    pub fn with_program_parameters(
+        self,
+        program_parameters: OrganismType::GeneratorParameters,
+    ) -> Self {
+        Self {
+            program_parameters: Some(program_parameters),
+            ..self
+        }
+    }
+
+    /// Validates the configuration. `fitness_parameters` and `program_parameters` have no
+    /// defaults and must be set.
+    pub fn build(self) -> Result<HyperParameters<OrganismType>, HyperParameterError> {
+        let hyper_params = HyperParameters {
+            population_size: self.population_size,
+            gap: self.gap,
+            n_mutations: self.n_mutations,
+            n_crossovers: self.n_crossovers,
+            adaptive_mutation: self.adaptive_mutation,
+            fitness_weighted_fill: self.fitness_weighted_fill,
+            n_random_immigrants: self.n_random_immigrants,
+            dedup_population: self.dedup_population,
+            multi_objective: self.multi_objective,
+            replacement: self.replacement,
+            crossover: self.crossover,
+            parent_selection: self.parent_selection,
+            surrogate_cutoff: self.surrogate_cutoff,
+            memory_budget: self.memory_budget,
+            snapshot_path: self.snapshot_path,
+            time_budget: self.time_budget,
+            max_generations: self.max_generations,
+            fitness_parameters: self
+                .fitness_parameters
+                .ok_or(HyperParameterError::MissingField("fitness_parameters"))?,
+            program_parameters: self
+                .program_parameters
+                .ok_or(HyperParameterError::MissingField("program_parameters"))?,
+        };
+
+        hyper_params.validate()?;
+
+        Ok(hyper_params)
+    }
+}
+
 pub trait Loader
 where
     Self::InputType: ValidInput + DeserializeOwned,
 {
     type InputType;
 
+    /// Like `try_load_inputs`, but panics on failure.
     fn load_inputs(file_path: impl Into<PathBuf>) -> Inputs<Self::InputType> {
+        match Self::try_load_inputs(file_path) {
+            Ok(inputs) => inputs,
+            Err(error) => panic!("Invalid dataset: {}", error),
+        }
+    }
+
+    /// Reads a header-less CSV, failing if the file can't be read, a row fails to parse or the
+    /// rows are rejected by `ValidInput::validate_dataset`. Rows of differing lengths are read
+    /// so the feature count check can report them.
+    fn try_load_inputs(
+        file_path: impl Into<PathBuf>,
+    ) -> Result<Inputs<Self::InputType>, Box<dyn std::error::Error>> {
         let mut csv_reader = ReaderBuilder::new()
             .has_headers(false)
-            .from_path(file_path.into())
-            .unwrap();
+            .flexible(true)
+            .from_path(file_path.into())?;
 
-        let inputs: Result<Inputs<Self::InputType>, _> = csv_reader
+        let inputs = csv_reader
             .deserialize()
-            .into_iter()
-            .map(|input| input)
-            .collect();
+            .collect::<Result<Inputs<Self::InputType>, _>>()?;
+        Self::InputType::validate_dataset(&inputs)?;
 
-        inputs.unwrap()
+        Ok(inputs)
     }
 }
 
@@ -66,6 +690,8 @@ where
         + Clone
         + Mutate
         + Breed
+        + Length
+        + Lineage
         + fmt::Debug,
 {
     type O;
@@ -98,43 +724,192 @@ where
         population.sort();
     }
 
-    fn apply_selection(population: &mut Population<Self::O>, gap: f32) {
-        assert!(gap >= 0f32 && gap <= 1f32);
-        assert_le!(population.last(), population.first());
+    /// Like `rank`, but individuals whose fitness key is cached take the cached score (see
+    /// `Fitness::set_fitness`) instead of being evaluated again.
+    fn rank_cached(
+        population: &mut Population<Self::O>,
+        fitness_parameters: &mut <Self::O as Fitness>::FitnessParameters,
+        cache: &mut FitnessCache,
+    ) {
+        cache.sync_parameters(Self::O::parameters_key(fitness_parameters));
 
-        let pop_len = population.len();
+        for individual in population.iter_mut() {
+            if individual.get_fitness().is_some() {
+                continue;
+            }
 
-        let cutoff_index = ((1f32 - gap) * (pop_len as f32)).floor() as i32 as usize;
+            let key = individual.fitness_key();
 
-        for _ in 0..cutoff_index {
-            population.pop();
+            if let Some(cached) = key.and_then(|key| cache.get(key)) {
+                individual.set_fitness(cached.fitness, cached.variance);
+            }
+
+            if individual.get_fitness().is_none() {
+                let fitness = individual.eval_fitness(fitness_parameters);
+
+                if let Some(key) = key {
+                    let variance = individual.get_fitness_variance();
+                    cache.insert(key, CachedFitness::new(fitness, variance));
+                }
+            }
         }
+        population.sort();
     }
 
-    fn breed(
+    /// Scores every unevaluated individual with the cheap surrogate fitness first, then fully
+    /// evaluates only those at or above the `cutoff` percentile of surrogate scores. The rest keep
+    /// their surrogate score. Without a cheaper surrogate (see `Fitness::has_surrogate_fitness`),
+    /// this is `rank`.
+    fn rank_two_stage(
         population: &mut Population<Self::O>,
-        mutation_percent: f32,
-        crossover_percent: f32,
-        mutation_parameters: &<Self::O as Generate>::GeneratorParameters,
+        fitness_parameters: &mut <Self::O as Fitness>::FitnessParameters,
+        cutoff: f32,
     ) {
-        assert_ge!(OrderedFloat(mutation_percent), OrderedFloat(0f32));
-        assert_ge!(OrderedFloat(crossover_percent), OrderedFloat(0f32));
-        assert_le!(
-            OrderedFloat(crossover_percent + mutation_percent),
-            OrderedFloat(1f32)
-        );
-        assert_le!(OrderedFloat(mutation_percent), OrderedFloat(1f32));
-        assert_le!(OrderedFloat(crossover_percent), OrderedFloat(1f32));
+        assert!((0f32..=1f32).contains(&cutoff));
 
-        let pop_cap = population.capacity();
-        let pop_len = population.len();
+        if !Self::O::has_surrogate_fitness(fitness_parameters) {
+            return Self::rank(population, fitness_parameters);
+        }
 
-        let mut remaining_pool_spots: usize = pop_cap - pop_len;
+        let mut surrogate_scores = vec![];
 
-        let mut n_mutated_children =
-            ((mutation_percent * remaining_pool_spots as f32) as f64).floor() as usize;
-        let mut n_crossover_children =
-            ((crossover_percent * remaining_pool_spots as f32) as f64).floor() as usize;
+        for individual in population.iter_mut() {
+            if individual.get_fitness().is_none() {
+                let score = individual.eval_surrogate_fitness(fitness_parameters);
+                surrogate_scores.push((individual, score));
+            }
+        }
+
+        let gain = |score: FitnessScore| OrderedFloat(Self::O::DIRECTION.gain(score));
+        let mut sorted_scores = surrogate_scores
+            .iter()
+            .map(|(_, score)| gain(*score))
+            .collect_vec();
+        sorted_scores.sort();
+
+        if let Some(last_index) = sorted_scores.len().checked_sub(1) {
+            let cutoff_index = ((cutoff * sorted_scores.len() as f32) as usize).min(last_index);
+            let threshold = sorted_scores[cutoff_index];
+
+            for (individual, score) in surrogate_scores {
+                if gain(score) >= threshold {
+                    individual.eval_fitness(fitness_parameters);
+                }
+            }
+        }
+
+        population.sort();
+    }
+
+    /// Truncates the population to its fittest individuals. Sorting first means callers need
+    /// not have just run `rank`.
+    fn apply_selection(population: &mut Population<Self::O>, gap: f32) {
+        assert!(gap >= 0f32 && gap <= 1f32);
+        population.sort();
+
+        let pop_len = population.len();
+
+        let cutoff_index = ((1f32 - gap) * (pop_len as f32)).floor() as i32 as usize;
+
+        for _ in 0..cutoff_index {
+            population.pop();
+        }
+    }
+
+    /// NSGA-II survival: keeps as many individuals as `apply_selection` would, filling by
+    /// non-dominated front and breaking ties in the last front by crowding distance.
+    /// Unevaluated individuals only survive once every evaluated one has.
+    fn apply_pareto_selection(population: &mut Population<Self::O>, gap: f32) {
+        assert!(gap >= 0f32 && gap <= 1f32);
+
+        let pop_len = population.len();
+        let n_survivors = pop_len - ((1f32 - gap) * (pop_len as f32)).floor() as usize;
+
+        let mut individuals = vec![];
+        while let Some(individual) = population.pop() {
+            individuals.push(individual);
+        }
+
+        let objectives = individuals
+            .iter()
+            .map(|individual| individual.objectives())
+            .collect_vec();
+
+        let mut survivors = vec![];
+
+        for front in non_dominated_sort(&objectives) {
+            if survivors.len() + front.len() <= n_survivors {
+                survivors.extend(front);
+                continue;
+            }
+
+            let distances = crowding_distances(&objectives, &front);
+            let most_isolated = front
+                .into_iter()
+                .zip(distances)
+                .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+                .map(|(index, _)| index);
+
+            survivors.extend(most_isolated.take(n_survivors - survivors.len()));
+            break;
+        }
+
+        for index in survivors {
+            population.push(individuals[index].clone());
+        }
+    }
+
+    fn breed(
+        population: &mut Population<Self::O>,
+        parameters: &BreedParameters,
+        mutation_parameters: &<Self::O as Generate>::GeneratorParameters,
+        lineage: Option<&mut Genealogy>,
+    ) {
+        Self::breed_with_rng(
+            population,
+            parameters,
+            mutation_parameters,
+            lineage,
+            &mut generator(),
+        )
+    }
+
+    /// Like `breed`, but every random choice (parents, offspring, immigrants and clones) is drawn
+    /// from `rng`, so a seeded `rng` reproduces the same children.
+    fn breed_with_rng<R: Rng + ?Sized>(
+        population: &mut Population<Self::O>,
+        parameters: &BreedParameters,
+        mutation_parameters: &<Self::O as Generate>::GeneratorParameters,
+        mut lineage: Option<&mut Genealogy>,
+        rng: &mut R,
+    ) {
+        let BreedParameters {
+            n_mutations: mutation_percent,
+            n_crossovers: crossover_percent,
+            fitness_weighted_fill,
+            n_random_immigrants,
+            crossover,
+            parent_selection,
+        } = *parameters;
+
+        assert_ge!(OrderedFloat(mutation_percent), OrderedFloat(0f32));
+        assert_ge!(OrderedFloat(crossover_percent), OrderedFloat(0f32));
+        assert_le!(
+            OrderedFloat(crossover_percent + mutation_percent),
+            OrderedFloat(1f32)
+        );
+        assert_le!(OrderedFloat(mutation_percent), OrderedFloat(1f32));
+        assert_le!(OrderedFloat(crossover_percent), OrderedFloat(1f32));
+
+        let pop_cap = population.capacity();
+        let pop_len = population.len();
+
+        let mut remaining_pool_spots: usize = pop_cap - pop_len;
+
+        let mut n_mutated_children =
+            ((mutation_percent * remaining_pool_spots as f32) as f64).floor() as usize;
+        let mut n_crossover_children =
+            ((crossover_percent * remaining_pool_spots as f32) as f64).floor() as usize;
 
         assert_le!(
             n_mutated_children + n_crossover_children,
@@ -145,17 +920,42 @@ where
 
         // Crossover + Mutation
         while (n_crossover_children + n_mutated_children) > 0 {
-            if let [parent_a, parent_b] = population
-                .iter()
-                .choose_multiple(&mut generator(), 2)
-                .as_slice()
-            {
+            let parents = match parent_selection {
+                ParentSelection::Uniform => population.iter().choose_multiple(rng, 2),
+                ParentSelection::FitnessWeighted => {
+                    Self::choose_parents_by_fitness(population, rng)
+                }
+                ParentSelection::Tournament(size) => {
+                    Self::choose_parents_by_tournament(population, size, rng)
+                }
+            };
+
+            if let [parent_a, parent_b] = parents.as_slice() {
                 if n_crossover_children > 0 {
-                    let crossover_child = parent_a
-                        .two_point_crossover(parent_b)
-                        .choose(&mut generator())
-                        .unwrap()
-                        .to_owned();
+                    let mut crossover_child = match crossover {
+                        CrossoverMethod::TwoPoint => parent_a
+                            .two_point_crossover_with_rng(parent_b, rng)
+                            .choose(rng)
+                            .unwrap()
+                            .to_owned(),
+                        CrossoverMethod::Uniform => {
+                            parent_a.uniform_crossover_with_rng(parent_b, rng)
+                        }
+                        CrossoverMethod::Homologous => parent_a
+                            .homologous_crossover_with_rng(parent_b, rng)
+                            .choose(rng)
+                            .unwrap()
+                            .to_owned(),
+                    };
+                    crossover_child.enforce_bounds(mutation_parameters);
+
+                    if let Some(genealogy) = lineage.as_deref_mut() {
+                        let parent_ids = [parent_a, parent_b]
+                            .iter()
+                            .filter_map(|parent| parent.id())
+                            .collect();
+                        genealogy.register(&mut crossover_child, parent_ids);
+                    }
 
                     remaining_pool_spots -= 1;
                     n_crossover_children -= 1;
@@ -164,11 +964,14 @@ where
 
                 if n_mutated_children > 0 {
                     let parents = [parent_a, parent_b];
-                    let selected_parent = parents.choose(&mut generator());
+                    let selected_parent = parents.choose(rng);
+
+                    let parent = selected_parent.unwrap();
+                    let mut mutation_child = parent.mutate_with_rng(mutation_parameters, rng);
 
-                    let mutation_child = selected_parent
-                        .map(|parent| parent.mutate(mutation_parameters))
-                        .unwrap();
+                    if let Some(genealogy) = lineage.as_deref_mut() {
+                        genealogy.register(&mut mutation_child, parent.id().into_iter().collect());
+                    }
 
                     remaining_pool_spots -= 1;
                     n_mutated_children -= 1;
@@ -178,98 +981,663 @@ where
             };
         }
 
+        // Random immigrants take their spots from the clones.
+        let n_immigrants = n_random_immigrants.min(remaining_pool_spots);
+        for _ in 0..n_immigrants {
+            let mut immigrant = Self::O::generate_with_rng(mutation_parameters, rng);
+
+            if let Some(genealogy) = lineage.as_deref_mut() {
+                genealogy.register(&mut immigrant, vec![]);
+            }
+
+            children.push(immigrant);
+        }
+        remaining_pool_spots -= n_immigrants;
+
         // Fill reset with clones
-        for individual in population
-            .iter()
-            .cloned()
-            .choose_multiple(&mut generator(), remaining_pool_spots)
-        {
+        let clones = if fitness_weighted_fill {
+            Self::sample_by_fitness_with_rng(population, remaining_pool_spots, rng)
+        } else {
+            population
+                .iter()
+                .cloned()
+                .choose_multiple(rng, remaining_pool_spots)
+        };
+
+        for mut individual in clones {
+            // A clone is a new individual descending from the one it copies.
+            if let Some(genealogy) = lineage.as_deref_mut() {
+                let parent_ids = individual.id().into_iter().collect();
+                genealogy.register(&mut individual, parent_ids);
+            }
+
             population.push(individual)
         }
 
         population.extend(children)
     }
 
+    /// Replaces every individual equal to an earlier one with a freshly generated individual,
+    /// so clones left over from breeding don't waste evaluations. Individuals are compared by
+    /// `Breed::genome_hash` when they provide one, and by `PartialEq` otherwise.
+    fn dedup_population(
+        population: &mut Population<Self::O>,
+        generator_parameters: &<Self::O as Generate>::GeneratorParameters,
+        mut lineage: Option<&mut Genealogy>,
+    ) {
+        let mut seen_hashes = HashSet::with_capacity(population.len());
+        let mut unhashed: Vec<Self::O> = vec![];
+        let mut already_seen = |individual: &Self::O| match individual.genome_hash() {
+            Some(hash) => !seen_hashes.insert(hash),
+            None if unhashed.contains(individual) => true,
+            None => {
+                unhashed.push(individual.clone());
+                false
+            }
+        };
+
+        for individual in population.iter_mut() {
+            if already_seen(individual) {
+                *individual = Self::O::generate(generator_parameters);
+                // Records the replacement so later copies of it are replaced too.
+                already_seen(individual);
+
+                if let Some(genealogy) = lineage.as_deref_mut() {
+                    genealogy.register(individual, vec![]);
+                }
+            }
+        }
+    }
+
+    /// Mutates every individual once and keeps the child only if its fitness improves on its
+    /// parent's by at least `epsilon`.
+    fn hill_climb(
+        population: &mut Population<Self::O>,
+        fitness_parameters: &mut <Self::O as Fitness>::FitnessParameters,
+        mutation_parameters: &<Self::O as Generate>::GeneratorParameters,
+        epsilon: f32,
+    ) {
+        for individual in population.iter_mut() {
+            let parent_fitness = match individual.get_fitness() {
+                Some(fitness) => fitness,
+                None => individual.eval_fitness(fitness_parameters),
+            };
+
+            let mut child = individual.mutate(mutation_parameters);
+            let child_fitness = child.eval_fitness(fitness_parameters);
+            let gain = |fitness| Self::O::DIRECTION.gain(fitness);
+
+            if gain(child_fitness) >= gain(parent_fitness) + epsilon {
+                *individual = child;
+            }
+        }
+    }
+
+    /// Steady-state evolution: every step replaces the `n_replace` worst individuals with mutated
+    /// crossover children of tournament-selected parents. Returns the number of fitness
+    /// evaluations performed.
+    fn steady_state(
+        population: &mut Population<Self::O>,
+        fitness_parameters: &mut <Self::O as Fitness>::FitnessParameters,
+        generator_parameters: &<Self::O as Generate>::GeneratorParameters,
+        tournament: &TournamentParameters,
+        n_replace: usize,
+        n_steps: usize,
+    ) -> usize {
+        assert_le!(n_replace, population.len());
+
+        let n_unevaluated = |population: &Population<Self::O>| {
+            population
+                .iter()
+                .filter(|individual| individual.get_fitness().is_none())
+                .count()
+        };
+
+        let mut n_evaluations = n_unevaluated(population);
+        Self::rank(population, fitness_parameters);
+
+        for _ in 0..n_steps {
+            let children = (0..n_replace)
+                .filter_map(|_| {
+                    let parent_a = Self::tournament(population, tournament)?;
+                    let parent_b = Self::tournament(population, tournament)?;
+                    let [mut child, _] = parent_a.two_point_crossover(&parent_b);
+                    child.enforce_bounds(generator_parameters);
+                    Some(child.mutate(generator_parameters))
+                })
+                .collect_vec();
+
+            for _ in 0..children.len() {
+                population.pop();
+            }
+            population.extend(children);
+
+            n_evaluations += n_unevaluated(population);
+            Self::rank(population, fitness_parameters);
+        }
+
+        n_evaluations
+    }
+
+    /// Draws the candidates of a single tournament. Without replacement, at most
+    /// `population.len()` distinct candidates are drawn.
+    fn tournament_candidates<'a>(
+        population: &'a Population<Self::O>,
+        parameters: &TournamentParameters,
+    ) -> Vec<&'a Self::O> {
+        if parameters.replacement {
+            (0..parameters.size)
+                .filter_map(|_| population.iter().choose(&mut generator()))
+                .collect()
+        } else {
+            population
+                .iter()
+                .choose_multiple(&mut generator(), parameters.size)
+        }
+    }
+
+    /// Runs a single tournament, returning a copy of its fittest candidate.
+    fn tournament(
+        population: &Population<Self::O>,
+        parameters: &TournamentParameters,
+    ) -> Option<Self::O> {
+        Self::tournament_candidates(population, parameters)
+            .into_iter()
+            .max_by(|a, b| Self::O::DIRECTION.compare(a.get_fitness(), b.get_fitness()))
+            .cloned()
+    }
+
+    /// Selection weights proportional to fitness, shifted to be non-negative. Unevaluated
+    /// individuals weigh as much as a score of 0.
+    fn fitness_weights(population: &Population<Self::O>) -> Vec<f32> {
+        let gains = population
+            .iter()
+            .map(|individual| {
+                individual
+                    .get_fitness()
+                    .map_or(0., |fitness| Self::O::DIRECTION.gain(fitness))
+            })
+            .collect_vec();
+        let min_gain = gains.iter().copied().fold(0f32, f32::min);
+
+        gains.into_iter().map(|gain| gain - min_gain).collect()
+    }
+
+    /// Draws two distinct parents weighted by `fitness_weights`. If every weight is zero,
+    /// parents are drawn uniformly.
+    fn choose_parents_by_fitness<'a, R: Rng + ?Sized>(
+        population: &'a Population<Self::O>,
+        rng: &mut R,
+    ) -> Vec<&'a Self::O> {
+        let weights = Self::fitness_weights(population);
+
+        if weights.iter().all(|weight| *weight == 0.) {
+            return population.iter().choose_multiple(rng, 2);
+        }
+
+        population
+            .iter()
+            .zip(weights)
+            .collect_vec()
+            .choose_multiple_weighted(rng, 2, |(_, weight)| *weight)
+            .map(|parents| parents.map(|(parent, _)| *parent).collect())
+            .unwrap_or_default()
+    }
+
+    /// Draws two parents, each the fittest of `size` candidates sampled with replacement.
+    fn choose_parents_by_tournament<'a, R: Rng + ?Sized>(
+        population: &'a Population<Self::O>,
+        size: usize,
+        rng: &mut R,
+    ) -> Vec<&'a Self::O> {
+        (0..2)
+            .filter_map(|_| {
+                (0..size)
+                    .filter_map(|_| population.iter().choose(rng))
+                    .max_by(|a, b| Self::O::DIRECTION.compare(a.get_fitness(), b.get_fitness()))
+            })
+            .collect()
+    }
+
+    /// Samples (with replacement) individuals weighted by `fitness_weights`. If every weight is
+    /// zero, sampling is uniform.
+    fn sample_by_fitness(population: &Population<Self::O>, n_samples: usize) -> Vec<Self::O> {
+        Self::sample_by_fitness_with_rng(population, n_samples, &mut generator())
+    }
+
+    fn sample_by_fitness_with_rng<R: Rng + ?Sized>(
+        population: &Population<Self::O>,
+        n_samples: usize,
+        rng: &mut R,
+    ) -> Vec<Self::O> {
+        match WeightedIndex::new(Self::fitness_weights(population)) {
+            Ok(distribution) => (0..n_samples)
+                .filter_map(|_| population.get(distribution.sample(rng)))
+                .cloned()
+                .collect(),
+            Err(_) => (0..n_samples)
+                .filter_map(|_| population.iter().choose(rng))
+                .cloned()
+                .collect(),
+        }
+    }
+
     fn execute<'b>(
         hyper_params: &mut HyperParameters<Self::O>,
-        mut hooks: EventHooks<'b, Self::O>,
+        hooks: EventHooks<'b, Self::O>,
     ) -> Result<Population<Self::O>, Box<dyn std::error::Error>> {
+        Self::execute_with_report(hyper_params, hooks).map(|(population, _)| population)
+    }
+
+    /// Ranks a generation through the fitness cache or the surrogate cutoff when either is set.
+    fn rank_generation(
+        population: &mut Population<Self::O>,
+        hyper_params: &mut HyperParameters<Self::O>,
+        state: &mut RunState<Self::O>,
+    ) {
+        match (state.fitness_cache.as_mut(), hyper_params.surrogate_cutoff) {
+            (Some(cache), _) => {
+                Self::rank_cached(population, &mut hyper_params.fitness_parameters, cache)
+            }
+            (None, Some(cutoff)) => {
+                Self::rank_two_stage(population, &mut hyper_params.fitness_parameters, cutoff)
+            }
+            (None, None) => Self::rank(population, &mut hyper_params.fitness_parameters),
+        }
+    }
+
+    /// Drops the individuals the replacement strategy doesn't keep from a ranked generation.
+    fn select_survivors(
+        population: &mut Population<Self::O>,
+        hyper_params: &HyperParameters<Self::O>,
+    ) {
+        match hyper_params.replacement {
+            ReplacementStrategy::Generational => {
+                if hyper_params.multi_objective {
+                    Self::apply_pareto_selection(population, hyper_params.gap);
+                } else {
+                    Self::apply_selection(population, hyper_params.gap);
+                }
+            }
+            // Every lineage survives; only its head may be replaced.
+            ReplacementStrategy::HillClimb { .. } => (),
+        }
+    }
+
+    /// Refills the survivors per the replacement strategy, then dedups and prunes the lineage
+    /// when enabled.
+    fn breed_generation(
+        population: &mut Population<Self::O>,
+        hyper_params: &mut HyperParameters<Self::O>,
+        state: &mut RunState<Self::O>,
+        n_mutations: f32,
+    ) {
+        match hyper_params.replacement {
+            ReplacementStrategy::Generational => Self::breed(
+                population,
+                &BreedParameters {
+                    n_mutations,
+                    ..BreedParameters::from(&*hyper_params)
+                },
+                &hyper_params.program_parameters,
+                state.lineage.as_mut(),
+            ),
+            ReplacementStrategy::HillClimb { epsilon } => Self::hill_climb(
+                population,
+                &mut hyper_params.fitness_parameters,
+                &hyper_params.program_parameters,
+                epsilon,
+            ),
+        }
+        if hyper_params.dedup_population {
+            Self::dedup_population(
+                population,
+                &hyper_params.program_parameters,
+                state.lineage.as_mut(),
+            );
+        }
+        if let Some(genealogy) = state.lineage.as_mut() {
+            genealogy.prune(population.iter().filter_map(|individual| individual.id()));
+        }
+    }
+
+    /// Like `execute`, but also summarizes the run in a `RunReport`.
+    fn execute_with_report<'b>(
+        hyper_params: &mut HyperParameters<Self::O>,
+        hooks: EventHooks<'b, Self::O>,
+    ) -> RunOutcome<Self::O> {
+        Self::execute_with_state(hyper_params, &mut RunState::default(), hooks)
+    }
+
+    /// Like `execute_with_report`, but accumulates the archive, lineage and fitness cache in
+    /// `state`, which outlives the run.
+    fn execute_with_state<'b>(
+        hyper_params: &mut HyperParameters<Self::O>,
+        state: &mut RunState<Self::O>,
+        mut hooks: EventHooks<'b, Self::O>,
+    ) -> RunOutcome<Self::O> {
         Self::init_env();
+        hyper_params.validate()?;
+        hyper_params.check_memory_budget()?;
 
         let EventHooks {
             after_init,
             after_rank,
             after_selection,
             after_breed,
+            after_generation,
+            stop_condition,
+            on_progress,
             ..
         } = &mut hooks;
 
+        let mut snapshots = match &hyper_params.snapshot_path {
+            Some(path) => Some(create_jsonl(path)?),
+            None => None,
+        };
+
         let mut population = Self::init_population(hyper_params);
 
+        if let Some(genealogy) = state.lineage.as_mut() {
+            for individual in population.iter_mut() {
+                genealogy.register(individual, vec![]);
+            }
+        }
+
         if let Some(hook) = after_init {
             (hook)(&mut population)?;
         }
 
-        for _ in 0..hyper_params.max_generations {
-            Self::rank(&mut population, &mut hyper_params.fitness_parameters);
+        let mut n_mutations = hyper_params.n_mutations;
+        let mut best_fitness = None;
+        let mut n_stagnant = 0;
+        let mut n_generations = 0;
+        let start = Instant::now();
+
+        for generation in 0..hyper_params.max_generations {
+            if let Some(budget) = hyper_params.time_budget {
+                if start.elapsed() >= budget {
+                    break;
+                }
+            }
+            n_generations = generation + 1;
+
+            Self::rank_generation(&mut population, hyper_params, state);
             if let Some(hook) = after_rank {
                 (hook)(&mut population)?;
             }
 
-            Self::apply_selection(&mut population, hyper_params.gap);
+            if let Some(archive) = state.archive.as_mut() {
+                archive.update(&population, &hyper_params.fitness_parameters);
+            }
+
+            if let Some(adaptive_mutation) = &hyper_params.adaptive_mutation {
+                let best = population.first().and_then(|i| i.get_fitness());
+
+                if Self::O::DIRECTION.compare(best, best_fitness) == Ordering::Greater {
+                    best_fitness = best;
+                    n_stagnant = 0;
+                } else {
+                    n_stagnant += 1;
+                }
+
+                n_mutations = adaptive_mutation
+                    .next_rate(hyper_params.n_mutations, n_mutations, n_stagnant)
+                    .min(1. - hyper_params.n_crossovers);
+            }
+
+            if let Some(writer) = snapshots.as_mut() {
+                let fitness = population.iter().map(|i| i.get_fitness()).collect_vec();
+                write_jsonl_line(writer, &fitness)?;
+            }
+
+            // Only summarize when someone is listening.
+            let stats = after_generation
+                .as_ref()
+                .and_then(|_| GenerationStats::new(generation, &population, n_mutations));
+
+            if let Some(stop_condition) = stop_condition {
+                if (stop_condition)(&population) {
+                    // The final generation is ranked but never selected from.
+                    if let (Some(hook), Some(stats)) = (after_generation.as_mut(), stats) {
+                        (hook)(&stats)?;
+                    }
+                    break;
+                }
+            }
+
+            Self::select_survivors(&mut population, hyper_params);
             if let Some(hook) = after_selection {
                 (hook)(&mut population)?;
             }
 
-            Self::breed(
-                &mut population,
-                hyper_params.n_mutations,
-                hyper_params.n_crossovers,
-                &hyper_params.program_parameters,
-            );
+            let stats = stats.map(|stats| stats.with_selection(&population));
+
+            Self::breed_generation(&mut population, hyper_params, state, n_mutations);
             if let Some(hook) = after_breed {
                 (hook)(&mut population)?;
             }
+
+            if let (Some(hook), Some(stats)) = (after_generation.as_mut(), stats) {
+                (hook)(&stats)?;
+            }
+
+            if let Some(hook) = on_progress {
+                (hook)(generation + 1, hyper_params.max_generations);
+            }
+        }
+
+        if let Some(writer) = snapshots.as_mut() {
+            writer.flush()?;
         }
 
-        Ok(population)
+        let report = RunReport::new(hyper_params, n_generations, &population);
+
+        Ok((population, report))
     }
 }
 
-pub type GpHook<'a, O> =
-    &'a mut dyn FnMut(&mut Population<O>) -> Result<(), Box<dyn std::error::Error>>;
-pub struct EventHooks<'a, O>
-where
-    O: PartialOrd + Clone,
-{
-    pub after_init: Option<GpHook<'a, O>>,
-    pub after_evaluate: Option<GpHook<'a, O>>,
-    pub after_rank: Option<GpHook<'a, O>>,
-    pub after_selection: Option<GpHook<'a, O>>,
-    pub after_breed: Option<GpHook<'a, O>>,
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best: FitnessScore,
+    pub median: FitnessScore,
+    pub worst: FitnessScore,
+    pub mean_fitness: FitnessScore,
+    pub fitness_std_dev: f32,
+    pub mean_program_length: f32,
+    /// See `Population::fitness_entropy`.
+    pub fitness_entropy: f32,
+    pub selection_intensity: f32,
+    /// The fraction of offspring produced by mutation this generation.
+    pub mutation_rate: f32,
 }
 
-impl<'a, O> EventHooks<'a, O>
-where
-    O: PartialOrd + Clone,
-{
-    pub fn with_after_init(self, f: GpHook<'a, O>) -> Self {
-        Self {
-            after_init: Some(f),
-            ..self
-        }
-    }
+impl GenerationStats {
+    /// Summarizes a ranked generation, or `None` when none of it has been evaluated.
+    /// `selection_intensity` stays 0 until `with_selection` records the survivors.
+    pub fn new<O>(generation: usize, ranked: &Population<O>, mutation_rate: f32) -> Option<Self>
+    where
+        O: Fitness + Length + PartialOrd + Clone,
+    {
+        let ComplexityBenchmark {
+            best,
+            median,
+            worst,
+        } = ranked.get_benchmark_individuals();
 
-    pub fn with_after_selection(self, f: GpHook<'a, O>) -> Self {
-        Self {
-            after_selection: Some(f),
-            ..self
-        }
+        Some(GenerationStats {
+            generation,
+            best: best?,
+            median: median?,
+            worst: worst?,
+            mean_fitness: ranked.mean_fitness(),
+            fitness_std_dev: ranked.fitness_std_dev(),
+            mean_program_length: ranked.iter().map(|i| i.length()).sum::<usize>() as f32
+                / ranked.len() as f32,
+            fitness_entropy: ranked.fitness_entropy(),
+            selection_intensity: 0.,
+            mutation_rate,
+        })
     }
 
-    pub fn with_after_rank(self, f: GpHook<'a, O>) -> Self {
-        Self {
-            after_rank: Some(f),
+    /// Records the selection intensity of the `selected` survivors (see
+    /// `Population::selection_intensity`); 0 when the generation had no fitness variance or
+    /// nothing selected was evaluated.
+    pub fn with_selection<O>(self, selected: &Population<O>) -> Self
+    where
+        O: Fitness + PartialOrd + Clone,
+    {
+        let selected_mean = selected.mean_fitness();
+
+        let selection_intensity = if self.fitness_std_dev > 0. && selected_mean.is_finite() {
+            O::DIRECTION.gain(selected_mean - self.mean_fitness) / self.fitness_std_dev
+        } else {
+            0.
+        };
+
+        GenerationStats {
+            selection_intensity,
+            ..self
+        }
+    }
+}
+
+/// What a run was configured with and what it achieved, as returned by
+/// `GeneticAlgorithm::execute_with_report`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RunReport {
+    pub population_size: usize,
+    pub gap: f32,
+    pub n_mutations: f32,
+    pub n_crossovers: f32,
+    pub max_generations: usize,
+    /// Generations actually run, which falls short of `max_generations` when a stop condition
+    /// or time budget ends the run early.
+    pub n_generations: usize,
+    /// Fitness over the evaluated individuals of the final population.
+    pub fitness: ComplexityBenchmark<Option<FitnessScore>>,
+    pub best_length: Option<usize>,
+}
+
+impl RunReport {
+    pub fn new<O>(
+        hyper_params: &HyperParameters<O>,
+        n_generations: usize,
+        population: &Population<O>,
+    ) -> Self
+    where
+        O: Fitness + Mutate + Generate + Length + PartialOrd + Clone,
+    {
+        let best_length = population
+            .iter()
+            .filter(|individual| individual.get_fitness().is_some())
+            .max_by(|a, b| O::DIRECTION.compare(a.get_fitness(), b.get_fitness()))
+            .map(|individual| individual.length());
+
+        RunReport {
+            population_size: hyper_params.population_size,
+            gap: hyper_params.gap,
+            n_mutations: hyper_params.n_mutations,
+            n_crossovers: hyper_params.n_crossovers,
+            max_generations: hyper_params.max_generations,
+            n_generations,
+            fitness: population.get_benchmark_individuals(),
+            best_length,
+        }
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: Option<String>| value.unwrap_or_else(|| "n/a".to_string());
+        let fitness = |score: Option<FitnessScore>| show(score.map(|score| score.to_string()));
+
+        write!(
+            f,
+            "generations: {}/{}, population: {}, gap: {}, mutations: {}, crossovers: {}, \
+             fitness (best/median/worst): {}/{}/{}, best length: {}",
+            self.n_generations,
+            self.max_generations,
+            self.population_size,
+            self.gap,
+            self.n_mutations,
+            self.n_crossovers,
+            fitness(self.fitness.best),
+            fitness(self.fitness.median),
+            fitness(self.fitness.worst),
+            show(self.best_length.map(|length| length.to_string())),
+        )
+    }
+}
+
+pub type GpHook<'a, O> =
+    &'a mut dyn FnMut(&mut Population<O>) -> Result<(), Box<dyn std::error::Error>>;
+pub type StatsHook<'a> =
+    &'a mut dyn FnMut(&GenerationStats) -> Result<(), Box<dyn std::error::Error>>;
+/// Called at the end of every generation with the number of completed generations and
+/// `max_generations`.
+pub type ProgressHook<'a> = &'a mut dyn FnMut(usize, usize);
+/// Checked after ranking; returning `true` ends `execute` early.
+pub type StopCondition<'a, O> = &'a mut dyn FnMut(&Population<O>) -> bool;
+
+/// A stop condition that fires once the best fitness has gone `n_generations` consecutive
+/// generations without improving.
+pub fn best_unchanged_for<O>(n_generations: usize) -> impl FnMut(&Population<O>) -> bool
+where
+    O: Fitness + PartialOrd + Clone,
+{
+    let mut best_fitness = None;
+    let mut n_unchanged = 0;
+
+    move |population| {
+        let best = population.first().and_then(|i| i.get_fitness());
+
+        if O::DIRECTION.compare(best, best_fitness) == Ordering::Greater {
+            best_fitness = best;
+            n_unchanged = 0;
+        } else {
+            n_unchanged += 1;
+        }
+
+        n_unchanged >= n_generations
+    }
+}
+pub struct EventHooks<'a, O>
+where
+    O: PartialOrd + Clone,
+{
+    pub after_init: Option<GpHook<'a, O>>,
+    pub after_evaluate: Option<GpHook<'a, O>>,
+    pub after_rank: Option<GpHook<'a, O>>,
+    pub after_selection: Option<GpHook<'a, O>>,
+    pub after_breed: Option<GpHook<'a, O>>,
+    pub after_generation: Option<StatsHook<'a>>,
+    pub stop_condition: Option<StopCondition<'a, O>>,
+    pub on_progress: Option<ProgressHook<'a>>,
+}
+
+impl<'a, O> EventHooks<'a, O>
+where
+    O: PartialOrd + Clone,
+{
+    pub fn with_after_init(self, f: GpHook<'a, O>) -> Self {
+        Self {
+            after_init: Some(f),
+            ..self
+        }
+    }
+
+    pub fn with_after_selection(self, f: GpHook<'a, O>) -> Self {
+        Self {
+            after_selection: Some(f),
+            ..self
+        }
+    }
+
+    pub fn with_after_rank(self, f: GpHook<'a, O>) -> Self {
+        Self {
+            after_rank: Some(f),
             ..self
         }
     }
@@ -280,6 +1648,27 @@ where
             ..self
         }
     }
+
+    pub fn with_after_generation(self, f: StatsHook<'a>) -> Self {
+        Self {
+            after_generation: Some(f),
+            ..self
+        }
+    }
+
+    pub fn with_stop_condition(self, f: StopCondition<'a, O>) -> Self {
+        Self {
+            stop_condition: Some(f),
+            ..self
+        }
+    }
+
+    pub fn with_on_progress(self, f: ProgressHook<'a>) -> Self {
+        Self {
+            on_progress: Some(f),
+            ..self
+        }
+    }
 }
 
 impl<'a, O> fmt::Debug for EventHooks<'a, O>
@@ -293,6 +1682,9 @@ where
             .field("after_selection", &"after_selection")
             .field("after_rank", &"after_rank")
             .field("after_breed", &"after_breed")
+            .field("after_generation", &"after_generation")
+            .field("stop_condition", &"stop_condition")
+            .field("on_progress", &"on_progress")
             .finish()
     }
 }
@@ -308,25 +1700,241 @@ where
             after_rank: None,
             after_selection: None,
             after_breed: None,
+            after_generation: None,
+            stop_condition: None,
+            on_progress: None,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{cell::RefCell, rc::Rc};
+    use std::{cell::RefCell, rc::Rc, time::Duration};
+
+    use itertools::Itertools;
+    use more_asserts::assert_gt;
 
     use crate::{
-        core::{instruction::InstructionGeneratorParameters, program::ProgramGeneratorParameters},
+        core::{
+            archive::QualityDiversityArchive,
+            characteristics::{Fitness, Generate},
+            fitness_cache::FitnessCache,
+            genealogy::{Ancestry, Genealogy},
+            instruction::{Instruction, InstructionGeneratorParameters, Mode},
+            instructions::Instructions,
+            pareto::dominates,
+            population::Population,
+            program::{Program, ProgramGeneratorParameters, ProgramParameterError},
+            registers::{Registers, R32},
+        },
         extensions::classification::ClassificationParameters,
         utils::{
+            executables::add,
             random::generator,
-            test::{TestInput, TestLgp},
+            test::{TestInput, TestLgp, TestOrganism, TestOrganismLgp},
         },
     };
-    use rand::{distributions::Standard, Rng};
+    use rand::{distributions::Standard, Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::{
+        best_unchanged_for, AdaptiveMutation, BreedParameters, CrossoverMethod, EventHooks,
+        GenerationStats, GeneticAlgorithm, HyperParameterError, HyperParameters, ParentSelection,
+        ReplacementStrategy, RunReport, RunState, TournamentParameters,
+    };
+
+    fn valid_hyper_params() -> HyperParameters<TestOrganism> {
+        HyperParameters {
+            population_size: 10,
+            gap: 0.5,
+            n_mutations: 0.5,
+            n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
+            max_generations: 1,
+            fitness_parameters: (),
+            program_parameters: (),
+        }
+    }
+
+    #[test]
+    fn given_valid_hyper_params_when_validate_then_ok() {
+        pretty_assertions::assert_eq!(valid_hyper_params().validate(), Ok(()));
+    }
+
+    #[test]
+    fn given_empty_population_when_execute_then_error_is_returned() {
+        let mut hyper_params = valid_hyper_params();
+        hyper_params.population_size = 0;
+
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::EmptyPopulation)
+        );
+        assert!(TestOrganismLgp::execute(&mut hyper_params, EventHooks::default()).is_err());
+    }
+
+    #[test]
+    fn given_gap_outside_unit_interval_when_validate_then_error_is_returned() {
+        for gap in [-0.1, 1.1] {
+            let mut hyper_params = valid_hyper_params();
+            hyper_params.gap = gap;
+
+            pretty_assertions::assert_eq!(
+                hyper_params.validate(),
+                Err(HyperParameterError::GapOutOfRange(gap))
+            );
+        }
+    }
+
+    #[test]
+    fn given_breeding_rates_outside_unit_interval_when_validate_then_error_is_returned() {
+        let mut hyper_params = valid_hyper_params();
+        hyper_params.n_mutations = -0.5;
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::MutationRateOutOfRange(-0.5))
+        );
+
+        let mut hyper_params = valid_hyper_params();
+        hyper_params.n_crossovers = 1.5;
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::CrossoverRateOutOfRange(1.5))
+        );
+    }
+
+    #[test]
+    fn given_breeding_rates_summing_past_one_when_execute_then_error_is_returned() {
+        let mut hyper_params = valid_hyper_params();
+        hyper_params.n_mutations = 0.7;
+        hyper_params.n_crossovers = 0.6;
+
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::BreedingRatesExceedOne {
+                n_mutations: 0.7,
+                n_crossovers: 0.6
+            })
+        );
+        assert!(TestOrganismLgp::execute(&mut hyper_params, EventHooks::default()).is_err());
+    }
+
+    #[test]
+    fn given_out_of_range_cutoff_immigrants_or_adaptive_mutation_when_validate_then_error_is_returned(
+    ) {
+        let hyper_params = HyperParameters {
+            surrogate_cutoff: Some(1.5),
+            ..valid_hyper_params()
+        };
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::SurrogateCutoffOutOfRange(1.5))
+        );
+
+        // A gap of 0.5 refills 5 of the 10 spots.
+        let hyper_params = HyperParameters {
+            n_random_immigrants: 6,
+            ..valid_hyper_params()
+        };
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::TooManyImmigrants {
+                n_random_immigrants: 6,
+                n_refilled: 5,
+            })
+        );
+
+        let hyper_params = HyperParameters {
+            adaptive_mutation: Some(AdaptiveMutation::new(2, 0.5, 0.9)),
+            ..valid_hyper_params()
+        };
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::MutationFactorBelowOne(0.5))
+        );
+
+        let hyper_params = HyperParameters {
+            adaptive_mutation: Some(AdaptiveMutation::new(2, 1.5, 0.2)),
+            ..valid_hyper_params()
+        };
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::MaxMutationRateOutOfRange(0.2))
+        );
+
+        let hyper_params = HyperParameters {
+            n_random_immigrants: 5,
+            adaptive_mutation: Some(AdaptiveMutation::new(2, 1.5, 0.9)),
+            surrogate_cutoff: Some(0.5),
+            ..valid_hyper_params()
+        };
+        pretty_assertions::assert_eq!(hyper_params.validate(), Ok(()));
+    }
+
+    #[test]
+    fn given_unordered_register_clamp_when_build_then_program_parameters_are_rejected() {
+        let inputs = [0; 5].map(|_| generator().sample(Standard)).to_vec();
+        let mut program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+        program_parameters.register_clamp = Some((1., -1.));
+
+        let error = HyperParameters::<Program<ClassificationParameters<TestInput>>>::builder()
+            .with_fitness_parameters(ClassificationParameters::new(inputs))
+            .with_program_parameters(program_parameters)
+            .build()
+            .unwrap_err();
+
+        pretty_assertions::assert_eq!(
+            error,
+            HyperParameterError::InvalidProgramParameters(
+                ProgramParameterError::InvalidRegisterClamp(1., -1.).to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn given_builder_when_build_then_defaults_are_applied_and_validated() {
+        let hyper_params = HyperParameters::<TestOrganism>::builder()
+            .with_population_size(20)
+            .with_fitness_parameters(())
+            .with_program_parameters(())
+            .build()
+            .unwrap();
+
+        pretty_assertions::assert_eq!(hyper_params.population_size, 20);
+        pretty_assertions::assert_eq!(hyper_params.gap, 0.5);
+        pretty_assertions::assert_eq!(hyper_params.n_mutations, 0.5);
+        pretty_assertions::assert_eq!(hyper_params.n_crossovers, 0.5);
+        pretty_assertions::assert_eq!(hyper_params.max_generations, 100);
+
+        let missing_parameters = HyperParameters::<TestOrganism>::builder()
+            .with_fitness_parameters(())
+            .build();
+        pretty_assertions::assert_eq!(
+            missing_parameters.unwrap_err(),
+            HyperParameterError::MissingField("program_parameters")
+        );
 
-    use super::{EventHooks, GeneticAlgorithm, HyperParameters};
+        let invalid = HyperParameters::<TestOrganism>::builder()
+            .with_gap(2.)
+            .with_fitness_parameters(())
+            .with_program_parameters(())
+            .build();
+        pretty_assertions::assert_eq!(invalid.unwrap_err(), HyperParameterError::GapOutOfRange(2.));
+    }
 
     #[test]
     fn given_lgp_instance_with_event_hooks_when_execute_then_closures_are_executed(
@@ -338,6 +1946,18 @@ mod tests {
             gap: 0.5,
             n_mutations: 0.5,
             n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
             max_generations: 1,
             fitness_parameters: ClassificationParameters::new(inputs),
             program_parameters: ProgramGeneratorParameters::new(
@@ -371,4 +1991,1117 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn given_fitness_weighted_fill_when_breed_then_fitter_survivors_are_cloned_more_often() {
+        let program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+
+        let mut weak =
+            Program::<ClassificationParameters<TestInput>>::generate(&program_parameters);
+        weak.fitness = Some(0.1);
+        let mut strong =
+            Program::<ClassificationParameters<TestInput>>::generate(&program_parameters);
+        strong.fitness = Some(0.9);
+
+        let mut population = Population::with_capacity(1002);
+        population.push(weak.clone());
+        population.push(strong.clone());
+
+        TestLgp::breed(
+            &mut population,
+            &BreedParameters {
+                fitness_weighted_fill: true,
+                ..BreedParameters::new(0., 0.)
+            },
+            &program_parameters,
+            None,
+        );
+
+        let clones = population.iter().skip(2);
+        let n_weak = clones.clone().filter(|program| **program == weak).count();
+        let n_strong = clones.filter(|program| **program == strong).count();
+
+        pretty_assertions::assert_eq!(n_weak + n_strong, 1000);
+        assert_gt!(n_strong, n_weak * 2);
+    }
+
+    #[test]
+    fn given_individuals_without_genome_hash_when_dedup_population_then_equal_ones_are_replaced() {
+        let mut population = Population::with_capacity(4);
+        population.push(TestOrganism::new(0.1, 0.1));
+        population.push(TestOrganism::new(0.2, 0.2));
+        population.push(TestOrganism::new(0.1, 0.1));
+        population.push(TestOrganism::new(0.2, 0.2));
+
+        TestOrganismLgp::dedup_population(&mut population, &(), None);
+
+        let fitnesses = population
+            .iter()
+            .map(|organism| organism.full_fitness)
+            .collect_vec();
+        pretty_assertions::assert_eq!(fitnesses[..2], [0.1, 0.2]);
+        assert!(fitnesses[2..]
+            .iter()
+            .all(|fitness| ![0.1, 0.2].contains(fitness)));
+    }
+
+    #[test]
+    fn given_population_of_clones_when_dedup_population_then_no_two_programs_share_instructions() {
+        let program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+
+        let original =
+            Program::<ClassificationParameters<TestInput>>::generate(&program_parameters);
+        let mut population = Population::with_capacity(20);
+        population.extend(std::iter::repeat(original.clone()).take(20));
+
+        TestLgp::dedup_population(&mut population, &program_parameters, None);
+
+        let instructions = population
+            .iter()
+            .map(|program| program.instructions.iter().cloned().collect_vec())
+            .collect_vec();
+
+        pretty_assertions::assert_eq!(population.len(), 20);
+        pretty_assertions::assert_eq!(population.first(), Some(&original));
+        assert!(instructions.iter().all_unique());
+    }
+
+    #[test]
+    fn given_lineage_when_breed_then_children_record_their_parent_ids() {
+        let program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+        let mut genealogy = Genealogy::new();
+
+        let breed_children = |genealogy: &mut Genealogy, n_mutations, n_crossovers| {
+            let mut population = Population::with_capacity(4);
+            for _ in 0..2 {
+                let mut program =
+                    Program::<ClassificationParameters<TestInput>>::generate(&program_parameters);
+                genealogy.register(&mut program, vec![]);
+                population.push(program);
+            }
+
+            TestLgp::breed(
+                &mut population,
+                &BreedParameters::new(n_mutations, n_crossovers),
+                &program_parameters,
+                Some(genealogy),
+            );
+
+            population.iter().skip(2).take(2).cloned().collect_vec()
+        };
+
+        for child in breed_children(&mut genealogy, 0., 1.) {
+            pretty_assertions::assert_eq!(child.parent_ids.len(), 2);
+        }
+
+        let mutation_children = breed_children(&mut genealogy, 1., 0.);
+        for child in mutation_children.iter() {
+            pretty_assertions::assert_eq!(child.parent_ids.len(), 1);
+        }
+
+        let child = &mutation_children[0];
+        let parent_id = child.parent_ids[0];
+        pretty_assertions::assert_eq!(
+            genealogy.ancestry(child.id.unwrap()),
+            vec![
+                Ancestry {
+                    id: child.id.unwrap(),
+                    parent_ids: vec![parent_id]
+                },
+                Ancestry {
+                    id: parent_id,
+                    parent_ids: vec![]
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn given_seeded_rng_when_breed_with_rng_then_offspring_composition_is_exact_and_reproducible() {
+        let program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+        let survivors = (0..4)
+            .map(|_| Program::<ClassificationParameters<TestInput>>::generate(&program_parameters))
+            .collect_vec();
+
+        let breed_seeded = |seed: u64| {
+            let mut genealogy = Genealogy::new();
+            let mut population = Population::with_capacity(14);
+            for survivor in survivors.iter() {
+                let mut survivor = survivor.clone();
+                genealogy.register(&mut survivor, vec![]);
+                population.push(survivor);
+            }
+
+            TestLgp::breed_with_rng(
+                &mut population,
+                &BreedParameters::new(0.3, 0.5),
+                &program_parameters,
+                Some(&mut genealogy),
+                &mut ChaCha8Rng::seed_from_u64(seed),
+            );
+
+            population.iter().skip(4).cloned().collect_vec()
+        };
+
+        let offspring = breed_seeded(7);
+        let n_with_parents = |n_parents: usize| {
+            offspring
+                .iter()
+                .filter(|program| program.parent_ids.len() == n_parents)
+                .count()
+        };
+
+        // 10 open spots: 5 crossover children, then 3 mutation children and 2 clones with a
+        // single parent each.
+        pretty_assertions::assert_eq!(n_with_parents(2), 5);
+        pretty_assertions::assert_eq!(n_with_parents(1), 5);
+
+        // Draws from the shared generator in between don't change the seeded offspring.
+        generator().gen::<u64>();
+        let repeated = breed_seeded(7);
+        let genomes = |programs: &[Program<ClassificationParameters<TestInput>>]| {
+            programs
+                .iter()
+                .map(|program| (program.instructions.clone(), program.parent_ids.clone()))
+                .collect_vec()
+        };
+        pretty_assertions::assert_eq!(genomes(&repeated), genomes(&offspring));
+    }
+
+    #[test]
+    fn given_parents_at_max_length_when_crossed_repeatedly_then_no_child_exceeds_the_limit() {
+        let max_instructions = 10;
+        let program_parameters = ProgramGeneratorParameters {
+            min_instructions: max_instructions - 1,
+            ..ProgramGeneratorParameters::new(
+                max_instructions,
+                InstructionGeneratorParameters::from::<TestInput>(1),
+            )
+        };
+
+        for _ in 0..20 {
+            let mut population = Population::with_capacity(50);
+            population.extend_from_generate(2, &program_parameters);
+
+            TestLgp::breed(
+                &mut population,
+                &BreedParameters::new(0., 1.),
+                &program_parameters,
+                None,
+            );
+
+            assert!(population
+                .iter()
+                .all(|program| program.instructions.len() <= max_instructions));
+        }
+    }
+
+    #[test]
+    fn given_random_immigrants_when_breed_then_some_offspring_are_not_derived_from_any_survivor() {
+        let program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+
+        let mut genealogy = Genealogy::new();
+        let mut population = Population::with_capacity(10);
+        for _ in 0..4 {
+            let mut survivor =
+                Program::<ClassificationParameters<TestInput>>::generate(&program_parameters);
+            genealogy.register(&mut survivor, vec![]);
+            population.push(survivor);
+        }
+        let survivor_ids = population
+            .iter()
+            .filter_map(|program| program.id)
+            .collect_vec();
+
+        TestLgp::breed(
+            &mut population,
+            &BreedParameters {
+                n_random_immigrants: 3,
+                ..BreedParameters::new(0., 0.)
+            },
+            &program_parameters,
+            Some(&mut genealogy),
+        );
+
+        let (immigrants, clones): (Vec<_>, Vec<_>) = population
+            .iter()
+            .skip(4)
+            .partition(|program| program.parent_ids.is_empty());
+
+        pretty_assertions::assert_eq!(population.len(), 10);
+        pretty_assertions::assert_eq!(immigrants.len(), 3);
+        assert!(clones.iter().all(|clone| {
+            !survivor_ids.contains(&clone.id.unwrap())
+                && survivor_ids.contains(&clone.parent_ids[0])
+        }));
+    }
+
+    #[test]
+    fn given_fitness_weighted_parent_selection_when_choosing_parents_then_fitter_survivors_are_chosen_more_often(
+    ) {
+        let program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+        let mut population = Population::with_capacity(4);
+        for fitness in [0.9, 0.5, 0.3, 0.1] {
+            let mut program =
+                Program::<ClassificationParameters<TestInput>>::generate(&program_parameters);
+            program.fitness = Some(fitness);
+            population.push(program);
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let mut n_chosen = [0; 4];
+        for _ in 0..2000 {
+            let parents = TestLgp::choose_parents_by_fitness(&population, &mut rng);
+            pretty_assertions::assert_eq!(parents.len(), 2);
+
+            for parent in parents {
+                let index = population.iter().position(|p| p == parent).unwrap();
+                n_chosen[index] += 1;
+            }
+        }
+
+        let [fittest, .., least_fit] = n_chosen;
+        assert_gt!(fittest, least_fit * 3);
+    }
+
+    #[test]
+    fn given_tournament_parent_selection_when_choosing_parents_then_fitter_survivors_win_more_often(
+    ) {
+        let population: Population<TestOrganism> = [0.9, 0.5, 0.3, 0.1]
+            .into_iter()
+            .map(|fitness| {
+                let mut organism = TestOrganism::new(0., fitness);
+                organism.eval_fitness(&mut ());
+                organism
+            })
+            .collect();
+
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let mut n_chosen = [0; 4];
+        for _ in 0..2000 {
+            let parents = TestOrganismLgp::choose_parents_by_tournament(&population, 3, &mut rng);
+            pretty_assertions::assert_eq!(parents.len(), 2);
+
+            for parent in parents {
+                let index = population.iter().position(|p| p == parent).unwrap();
+                n_chosen[index] += 1;
+            }
+        }
+
+        let [fittest, second, third, least_fit] = n_chosen;
+        assert_gt!(fittest, second);
+        assert_gt!(second, third);
+        assert_gt!(third, least_fit);
+    }
+
+    #[test]
+    fn given_empty_tournament_when_validate_then_it_is_rejected() {
+        let hyper_params = HyperParameters {
+            parent_selection: ParentSelection::Tournament(0),
+            ..valid_hyper_params()
+        };
+
+        pretty_assertions::assert_eq!(
+            hyper_params.validate(),
+            Err(HyperParameterError::EmptyTournament)
+        );
+    }
+
+    #[test]
+    fn given_after_generation_hook_when_execute_then_stats_are_reported_each_generation(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let inputs = [0; 5].map(|_| generator().sample(Standard)).to_vec();
+        let mut hyper_params = HyperParameters {
+            population_size: 10,
+            gap: 0.5,
+            n_mutations: 0.5,
+            n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
+            max_generations: 3,
+            fitness_parameters: ClassificationParameters::new(inputs),
+            program_parameters: ProgramGeneratorParameters::new(
+                10,
+                InstructionGeneratorParameters::from::<TestInput>(1),
+            ),
+        };
+
+        let mut received = vec![];
+
+        TestLgp::execute(
+            &mut hyper_params,
+            EventHooks::default().with_after_generation(&mut |stats| {
+                received.push(stats.clone());
+                Ok(())
+            }),
+        )?;
+
+        pretty_assertions::assert_eq!(
+            received
+                .iter()
+                .map(|stats| stats.generation)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+
+        for stats in received {
+            assert!(stats.best >= stats.median && stats.median >= stats.worst);
+            assert!(stats.best >= stats.mean_fitness && stats.mean_fitness >= stats.worst);
+            assert!((1f32..=10f32).contains(&stats.mean_program_length));
+            assert!(stats.selection_intensity >= 0.);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_unevaluated_population_when_generation_stats_then_none_is_returned() {
+        let empty: Population<TestOrganism> = Population::with_capacity(0);
+        let unevaluated: Population<TestOrganism> = (1..=3)
+            .map(|score| TestOrganism::new(0., score as f32))
+            .collect();
+
+        pretty_assertions::assert_eq!(GenerationStats::new(0, &empty, 0.5), None);
+        pretty_assertions::assert_eq!(GenerationStats::new(0, &unevaluated, 0.5), None);
+    }
+
+    #[test]
+    fn given_selected_survivors_when_with_selection_then_intensity_matches_population() {
+        let ranked: Population<TestOrganism> = (1..=4)
+            .map(|score| {
+                let mut organism = TestOrganism::new(0., score as f32);
+                organism.eval_fitness(&mut ());
+                organism
+            })
+            .collect();
+        let selected: Population<TestOrganism> = ranked.iter().skip(2).cloned().collect();
+        let unevaluated: Population<TestOrganism> = Population::with_capacity(0);
+
+        let stats = GenerationStats::new(0, &ranked, 0.5).unwrap();
+
+        pretty_assertions::assert_eq!(stats.selection_intensity, 0.);
+        pretty_assertions::assert_eq!(
+            stats.clone().with_selection(&selected).selection_intensity,
+            ranked.selection_intensity(&selected)
+        );
+        pretty_assertions::assert_eq!(stats.with_selection(&unevaluated).selection_intensity, 0.);
+    }
+
+    #[test]
+    fn given_surrogate_cutoff_when_rank_two_stage_then_only_individuals_above_cutoff_are_fully_evaluated(
+    ) {
+        let mut population: Population<TestOrganism> = (1..=10)
+            .map(|score| TestOrganism::new(score as f32, 100. + score as f32))
+            .collect();
+
+        TestOrganismLgp::rank_two_stage(&mut population, &mut (), 0.5);
+
+        for individual in population.iter() {
+            if individual.surrogate_fitness >= 6. {
+                pretty_assertions::assert_eq!(individual.n_full_evaluations, 1);
+                pretty_assertions::assert_eq!(individual.fitness, Some(individual.full_fitness));
+            } else {
+                pretty_assertions::assert_eq!(individual.n_full_evaluations, 0);
+                pretty_assertions::assert_eq!(
+                    individual.fitness,
+                    Some(individual.surrogate_fitness)
+                );
+            }
+        }
+
+        pretty_assertions::assert_eq!(population.first().unwrap().fitness, Some(110.));
+        pretty_assertions::assert_eq!(population.last().unwrap().fitness, Some(1.));
+    }
+
+    #[test]
+    fn given_archive_when_execute_then_elites_are_accumulated_by_behavior(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let inputs = [0; 20].map(|_| generator().sample(Standard)).to_vec();
+        let mut hyper_params = HyperParameters {
+            population_size: 10,
+            gap: 0.5,
+            n_mutations: 0.5,
+            n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
+            max_generations: 3,
+            fitness_parameters: ClassificationParameters::new(inputs),
+            program_parameters: ProgramGeneratorParameters::new(
+                10,
+                InstructionGeneratorParameters::from::<TestInput>(1),
+            ),
+        };
+
+        let mut state = RunState::new()
+            .with_archive(QualityDiversityArchive::new(5, Program::per_class_accuracy));
+
+        TestLgp::execute_with_state(&mut hyper_params, &mut state, EventHooks::default())?;
+
+        let archive = state.archive.as_ref().unwrap();
+        assert!(!archive.is_empty());
+
+        for (cell, elite) in archive.iter() {
+            let descriptor = elite.per_class_accuracy(&hyper_params.fitness_parameters);
+            pretty_assertions::assert_eq!(*cell, archive.cell(&descriptor));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_memory_budget_when_configuration_exceeds_it_then_execute_is_rejected() {
+        let inputs = [0; 5].map(|_| generator().sample(Standard)).to_vec();
+        let mut hyper_params = HyperParameters {
+            population_size: 10,
+            gap: 0.5,
+            n_mutations: 0.5,
+            n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: Some(1024),
+            snapshot_path: None,
+            time_budget: None,
+            max_generations: 1,
+            fitness_parameters: ClassificationParameters::new(inputs),
+            program_parameters: ProgramGeneratorParameters::new(
+                100,
+                InstructionGeneratorParameters::from::<TestInput>(1),
+            ),
+        };
+
+        let estimated = hyper_params.estimated_memory();
+        assert_gt!(estimated, 10 * 100);
+
+        let error = TestLgp::execute(&mut hyper_params, EventHooks::default()).unwrap_err();
+        let message = error.to_string();
+
+        assert!(message.contains(&estimated.to_string()));
+        assert!(message.contains("1024"));
+
+        hyper_params.memory_budget = Some(estimated);
+        assert!(hyper_params.check_memory_budget().is_ok());
+    }
+
+    #[test]
+    fn given_snapshot_path_when_execute_then_one_fitness_line_is_written_per_generation(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let inputs = [0; 5].map(|_| generator().sample(Standard)).to_vec();
+        let snapshot_file = tempfile::NamedTempFile::new()?;
+        let mut hyper_params = HyperParameters {
+            population_size: 10,
+            gap: 0.5,
+            n_mutations: 0.5,
+            n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: Some(snapshot_file.path().to_path_buf()),
+            time_budget: None,
+            max_generations: 3,
+            fitness_parameters: ClassificationParameters::new(inputs),
+            program_parameters: ProgramGeneratorParameters::new(
+                10,
+                InstructionGeneratorParameters::from::<TestInput>(1),
+            ),
+        };
+
+        TestLgp::execute(&mut hyper_params, EventHooks::default())?;
+
+        let contents = std::fs::read_to_string(snapshot_file.path())?;
+        let lines = contents.lines().collect_vec();
+        pretty_assertions::assert_eq!(lines.len(), 3);
+
+        for line in lines {
+            let fitness: Vec<f32> = serde_json::from_str(line)?;
+            pretty_assertions::assert_eq!(fitness.len(), 10);
+            assert!(fitness.windows(2).all(|w| w[0] >= w[1]));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_hill_climb_when_mutation_does_not_improve_by_epsilon_then_parent_survives() {
+        let inputs = [0; 5].map(|_| generator().sample(Standard)).to_vec();
+        let mut fitness_parameters = ClassificationParameters::new(inputs);
+        let program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+
+        let mut population = Population::with_capacity(10);
+        for _ in 0..10 {
+            population.push(Program::generate(&program_parameters));
+        }
+        TestLgp::rank(&mut population, &mut fitness_parameters);
+        let parents = population.iter().cloned().collect_vec();
+
+        // Accuracy lies in [0, 1], so no child can improve by more than 1.
+        TestLgp::hill_climb(
+            &mut population,
+            &mut fitness_parameters,
+            &program_parameters,
+            1.5,
+        );
+
+        pretty_assertions::assert_eq!(population.iter().cloned().collect_vec(), parents);
+
+        // A negative epsilon accepts children up to 1.5 worse than their parent, i.e. all of them.
+        TestLgp::hill_climb(
+            &mut population,
+            &mut fitness_parameters,
+            &program_parameters,
+            -1.5,
+        );
+
+        assert_ne!(population.iter().cloned().collect_vec(), parents);
+    }
+
+    #[test]
+    fn given_hill_climb_replacement_when_execute_then_after_selection_runs_each_generation(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut hyper_params = HyperParameters {
+            replacement: ReplacementStrategy::HillClimb { epsilon: 0. },
+            max_generations: 3,
+            ..valid_hyper_params()
+        };
+
+        let mut n_selections = 0;
+        TestOrganismLgp::execute(
+            &mut hyper_params,
+            EventHooks::default().with_after_selection(&mut |population| {
+                pretty_assertions::assert_eq!(population.len(), 10);
+                n_selections += 1;
+                Ok(())
+            }),
+        )?;
+
+        pretty_assertions::assert_eq!(n_selections, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_tournament_replacement_when_drawing_candidates_then_duplicates_occur_only_with_replacement(
+    ) {
+        let mut population = Population::with_capacity(5);
+        for fitness in 0..5 {
+            population.push(TestOrganism::new(fitness as f32, fitness as f32));
+        }
+
+        let has_duplicates = |candidates: Vec<&TestOrganism>| {
+            candidates
+                .iter()
+                .enumerate()
+                .any(|(i, a)| candidates[..i].iter().any(|b| std::ptr::eq(*a, *b)))
+        };
+
+        let without_replacement = TournamentParameters::new(5, false);
+        let with_replacement = TournamentParameters::new(5, true);
+
+        for _ in 0..100 {
+            let candidates =
+                TestOrganismLgp::tournament_candidates(&population, &without_replacement);
+            pretty_assertions::assert_eq!(candidates.len(), 5);
+            assert!(!has_duplicates(candidates));
+        }
+
+        assert!(
+            (0..100).any(|_| has_duplicates(TestOrganismLgp::tournament_candidates(
+                &population,
+                &with_replacement
+            )))
+        );
+    }
+
+    #[test]
+    fn given_evaluated_population_when_run_report_is_formatted_then_key_fields_are_included() {
+        let mut population = Population::with_capacity(3);
+        population.extend([0.2, 0.9, 0.5].map(|fitness| {
+            let mut organism = TestOrganism::new(fitness, fitness);
+            organism.fitness = Some(fitness);
+            organism
+        }));
+
+        let report = RunReport::new(&valid_hyper_params(), 7, &population);
+        let formatted = report.to_string();
+
+        pretty_assertions::assert_eq!(report.fitness.best, Some(0.9));
+        for field in [
+            "generations: 7/",
+            "population: 10",
+            "fitness (best/median/worst): 0.9/0.5/0.2",
+            "best length: ",
+        ] {
+            assert!(
+                formatted.contains(field),
+                "{} missing from {}",
+                field,
+                formatted
+            );
+        }
+    }
+
+    #[test]
+    fn given_stop_condition_when_execute_with_report_then_generations_run_are_reported(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut hyper_params = HyperParameters {
+            max_generations: 10,
+            ..valid_hyper_params()
+        };
+        let mut n_checks = 0;
+        let mut stop_on_third = |_: &Population<TestOrganism>| {
+            n_checks += 1;
+            n_checks == 3
+        };
+
+        let mut generations = vec![];
+        let mut record = |stats: &GenerationStats| {
+            generations.push(stats.generation);
+            Ok(())
+        };
+
+        let (population, report) = TestOrganismLgp::execute_with_report(
+            &mut hyper_params,
+            EventHooks::default()
+                .with_stop_condition(&mut stop_on_third)
+                .with_after_generation(&mut record),
+        )?;
+
+        pretty_assertions::assert_eq!(report.n_generations, 3);
+        pretty_assertions::assert_eq!(report.max_generations, 10);
+        pretty_assertions::assert_eq!(report.population_size, population.len());
+        // The generation that stopped the run is reported too.
+        pretty_assertions::assert_eq!(generations, vec![0, 1, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_exhausted_time_budget_when_execute_then_run_stops_early_with_full_population(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut hyper_params = HyperParameters {
+            time_budget: Some(Duration::from_nanos(1)),
+            max_generations: 1_000_000,
+            ..valid_hyper_params()
+        };
+        let mut n_generations = 0;
+        let mut count_generations = |_generation: usize, _max: usize| n_generations += 1;
+
+        let population = TestOrganismLgp::execute(
+            &mut hyper_params,
+            EventHooks::default().with_on_progress(&mut count_generations),
+        )?;
+
+        pretty_assertions::assert_eq!(population.len(), hyper_params.population_size);
+        assert!(n_generations < hyper_params.max_generations);
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_population_of_clones_when_rank_cached_then_behavior_is_evaluated_once() {
+        let mut population = Population::with_capacity(10);
+        for _ in 0..10 {
+            population.push(TestOrganism::new(0.5, 0.5));
+        }
+        let mut cache = FitnessCache::new();
+
+        TestOrganismLgp::rank_cached(&mut population, &mut (), &mut cache);
+
+        pretty_assertions::assert_eq!(cache.misses(), 1);
+        pretty_assertions::assert_eq!(cache.hits(), 9);
+        pretty_assertions::assert_eq!(cache.len(), 1);
+        assert!(population
+            .iter()
+            .all(|individual| individual.fitness == Some(0.5)));
+
+        // Only the first clone ran `eval_fitness`; the rest took its cached score.
+        pretty_assertions::assert_eq!(
+            population
+                .iter()
+                .map(|individual| individual.n_full_evaluations)
+                .sum::<usize>(),
+            1
+        );
+    }
+
+    #[test]
+    fn given_individuals_sharing_a_fitness_key_when_rank_cached_then_only_the_score_is_copied() {
+        let mut population = Population::with_capacity(2);
+        population.push(TestOrganism::new(0.1, 0.5));
+        population.push(TestOrganism::new(0.2, 0.5));
+        let mut cache = FitnessCache::new();
+
+        TestOrganismLgp::rank_cached(&mut population, &mut (), &mut cache);
+
+        pretty_assertions::assert_eq!(cache.hits(), 1);
+        pretty_assertions::assert_eq!(
+            population
+                .iter()
+                .map(|individual| individual.surrogate_fitness)
+                .sorted_by(|a, b| a.partial_cmp(b).unwrap())
+                .collect_vec(),
+            vec![0.1, 0.2]
+        );
+    }
+
+    #[test]
+    fn given_population_of_clones_when_ranked_with_and_without_cache_then_cache_saves_evaluations()
+    {
+        let clones = || {
+            let mut population = Population::with_capacity(10);
+            population.extend((0..10).map(|_| TestOrganism::new(0.5, 0.5)));
+            population
+        };
+        let n_evaluations = |population: &Population<TestOrganism>| {
+            population
+                .iter()
+                .map(|individual| individual.n_full_evaluations)
+                .sum::<usize>()
+        };
+
+        let mut uncached = clones();
+        TestOrganismLgp::rank(&mut uncached, &mut ());
+
+        let mut cached = clones();
+        let mut cache = FitnessCache::new();
+        TestOrganismLgp::rank_cached(&mut cached, &mut (), &mut cache);
+
+        pretty_assertions::assert_eq!(n_evaluations(&uncached), 10);
+        // Only cache misses call `eval_fitness`; hits copy the cached score.
+        pretty_assertions::assert_eq!(n_evaluations(&cached), 1);
+        pretty_assertions::assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn given_cleared_cache_when_rank_cached_then_individuals_are_evaluated_again() {
+        let mut cache = FitnessCache::new();
+
+        let mut population = Population::with_capacity(2);
+        population.extend((0..2).map(|_| TestOrganism::new(0.5, 0.5)));
+        TestOrganismLgp::rank_cached(&mut population, &mut (), &mut cache);
+
+        cache.clear();
+        pretty_assertions::assert_eq!(cache.len(), 0);
+
+        let mut population = Population::with_capacity(2);
+        population.extend((0..2).map(|_| TestOrganism::new(0.5, 0.5)));
+        TestOrganismLgp::rank_cached(&mut population, &mut (), &mut cache);
+
+        pretty_assertions::assert_eq!(cache.misses(), 2);
+        pretty_assertions::assert_eq!(cache.hits(), 2);
+    }
+
+    #[test]
+    fn given_plateaued_fitness_when_adaptive_mutation_then_mutation_rate_rises_to_its_cap(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Every organism scores the same, so the best fitness never improves.
+        let mut hyper_params = HyperParameters {
+            population_size: 10,
+            gap: 0.5,
+            n_mutations: 0.1,
+            n_crossovers: 0.2,
+            adaptive_mutation: Some(AdaptiveMutation::new(2, 2., 0.6)),
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
+            max_generations: 6,
+            fitness_parameters: (),
+            program_parameters: (),
+        };
+
+        let mut rates = vec![];
+
+        TestOrganismLgp::execute(
+            &mut hyper_params,
+            EventHooks::default().with_after_generation(&mut |stats| {
+                rates.push(stats.mutation_rate);
+                Ok(())
+            }),
+        )?;
+
+        pretty_assertions::assert_eq!(rates, vec![0.1, 0.1, 0.2, 0.4, 0.6, 0.6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_converged_population_when_best_unchanged_stop_condition_then_execute_stops_early(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut hyper_params = HyperParameters {
+            population_size: 10,
+            gap: 0.5,
+            n_mutations: 0.5,
+            n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
+            max_generations: 100,
+            fitness_parameters: (),
+            program_parameters: (),
+        };
+
+        let mut n_ranks = 0;
+        let mut stop_condition = best_unchanged_for(3);
+
+        let population = TestOrganismLgp::execute(
+            &mut hyper_params,
+            EventHooks::default()
+                .with_after_rank(&mut |_| {
+                    n_ranks += 1;
+                    Ok(())
+                })
+                .with_stop_condition(&mut stop_condition),
+        )?;
+
+        // The first generation sets the best; the next three fail to improve it.
+        pretty_assertions::assert_eq!(n_ranks, 4);
+        pretty_assertions::assert_eq!(population.len(), 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_on_progress_hook_when_execute_then_it_ticks_once_per_completed_generation(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut hyper_params = valid_hyper_params();
+        hyper_params.max_generations = 5;
+
+        let mut ticks = vec![];
+        TestOrganismLgp::execute(
+            &mut hyper_params,
+            EventHooks::default().with_on_progress(&mut |generation, max_generations| {
+                ticks.push((generation, max_generations))
+            }),
+        )?;
+
+        pretty_assertions::assert_eq!(
+            ticks,
+            (1..=5).map(|generation| (generation, 5)).collect_vec()
+        );
+
+        let mut n_ticks = 0;
+        let mut n_checks = 0;
+        TestOrganismLgp::execute(
+            &mut hyper_params,
+            EventHooks::default()
+                .with_on_progress(&mut |_, _| n_ticks += 1)
+                .with_stop_condition(&mut |_| {
+                    n_checks += 1;
+                    n_checks == 3
+                }),
+        )?;
+
+        // The generation that triggers the stop condition does not complete.
+        pretty_assertions::assert_eq!(n_ticks, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_two_objective_population_when_pareto_selection_then_survivors_form_non_dominated_front(
+    ) {
+        // (accuracy, length): the last two are dominated by (0.8, 5).
+        let toy = [(0.9, 10), (0.8, 5), (0.7, 2), (0.6, 8), (0.5, 20)];
+
+        let mut population = Population::with_capacity(toy.len());
+        for (fitness, length) in toy {
+            let instructions: Instructions = (0..length)
+                .map(|_| Instruction::new(0, 0, Mode::External, add))
+                .collect();
+            let program = Program::<ClassificationParameters<TestInput>>::new(
+                instructions,
+                Registers::new(3),
+                Some(fitness),
+            );
+            population.push(program);
+        }
+
+        let front_fitness = |front: Vec<&Program<ClassificationParameters<TestInput>>>| {
+            front
+                .iter()
+                .map(|program| program.fitness.unwrap())
+                .sorted_by(|a, b| b.partial_cmp(a).unwrap())
+                .collect_vec()
+        };
+
+        pretty_assertions::assert_eq!(
+            front_fitness(population.pareto_front()),
+            vec![0.9, 0.8, 0.7]
+        );
+
+        TestLgp::apply_pareto_selection(&mut population, 0.5);
+
+        pretty_assertions::assert_eq!(population.len(), 3);
+        let survivors = population.iter().collect_vec();
+        for a in survivors.iter() {
+            for b in survivors.iter() {
+                assert!(!dominates(
+                    &a.objectives().unwrap(),
+                    &b.objectives().unwrap()
+                ));
+            }
+        }
+        pretty_assertions::assert_eq!(front_fitness(survivors), vec![0.9, 0.8, 0.7]);
+    }
+
+    #[test]
+    fn given_infinite_objective_in_truncated_front_when_pareto_selection_then_boundaries_survive() {
+        // (accuracy, length): mutually non-dominated, so one front of which two survive.
+        let toy = [(R32::NEG_INFINITY, 2), (0.5, 5), (0.9, 10)];
+
+        let mut population = Population::with_capacity(toy.len());
+        for (fitness, length) in toy {
+            let instructions: Instructions = (0..length)
+                .map(|_| Instruction::new(0, 0, Mode::External, add))
+                .collect();
+            population.push(Program::<ClassificationParameters<TestInput>>::new(
+                instructions,
+                Registers::new(3),
+                Some(fitness),
+            ));
+        }
+
+        TestLgp::apply_pareto_selection(&mut population, 0.5);
+
+        pretty_assertions::assert_eq!(
+            population
+                .iter()
+                .map(|program| program.fitness)
+                .sorted_by(|a, b| a.partial_cmp(b).unwrap())
+                .collect_vec(),
+            vec![Some(R32::NEG_INFINITY), Some(0.9)]
+        );
+    }
+
+    #[test]
+    fn given_unevaluated_individuals_when_pareto_selection_then_evaluated_ones_survive_first() {
+        let mut population: Population<TestOrganism> = (1..=6)
+            .map(|score| TestOrganism::new(0., score as f32))
+            .collect();
+        for individual in population.iter_mut().skip(3) {
+            individual.eval_fitness(&mut ());
+        }
+
+        TestOrganismLgp::apply_pareto_selection(&mut population, 0.5);
+
+        pretty_assertions::assert_eq!(
+            population
+                .iter()
+                .map(|individual| individual.fitness)
+                .collect_vec(),
+            vec![Some(6.), Some(5.), Some(4.)]
+        );
+    }
+
+    #[test]
+    fn given_unsorted_population_when_apply_selection_then_fittest_survive() {
+        let mut population = Population::with_capacity(4);
+        for fitness in [0.2, 0.9, 0.5, 0.1] {
+            let mut organism = TestOrganism::new(fitness, fitness);
+            organism.fitness = Some(fitness);
+            population.push(organism);
+        }
+
+        TestOrganismLgp::apply_selection(&mut population, 0.5);
+
+        let survivors = population
+            .iter()
+            .map(|organism| organism.fitness.unwrap())
+            .collect_vec();
+        pretty_assertions::assert_eq!(survivors, vec![0.9, 0.5]);
+    }
+
+    #[test]
+    fn given_steady_state_when_stepping_then_size_is_constant_and_only_children_are_evaluated() {
+        let mut population = Population::with_capacity(10);
+        for fitness in 0..10 {
+            population.push(TestOrganism::new(fitness as f32, fitness as f32));
+        }
+
+        let n_evaluations = TestOrganismLgp::steady_state(
+            &mut population,
+            &mut (),
+            &(),
+            &TournamentParameters::new(3, false),
+            2,
+            5,
+        );
+
+        pretty_assertions::assert_eq!(population.len(), 10);
+        pretty_assertions::assert_eq!(n_evaluations, 10 + 5 * 2);
+        assert!(population
+            .iter()
+            .all(|individual| individual.get_fitness().is_some()));
+    }
 }
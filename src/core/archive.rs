@@ -0,0 +1,171 @@
+use std::{cmp::Ordering, collections::HashMap, fmt};
+
+use super::{characteristics::Fitness, population::Population};
+
+/// Maps an individual to a point in behavior space, with every coordinate in `[0, 1]`.
+pub type BehaviorDescriptor<O> = fn(&O, &<O as Fitness>::FitnessParameters) -> Vec<f32>;
+
+pub type Cell = Vec<usize>;
+
+/// MAP-Elites style archive: behavior space is split into a grid of `n_bins` per dimension and
+/// only the fittest individual seen in each cell is kept.
+#[derive(Clone)]
+pub struct QualityDiversityArchive<O>
+where
+    O: Fitness,
+{
+    n_bins: usize,
+    descriptor: BehaviorDescriptor<O>,
+    cells: HashMap<Cell, O>,
+}
+
+impl<O> QualityDiversityArchive<O>
+where
+    O: Fitness + Clone,
+{
+    pub fn new(n_bins: usize, descriptor: BehaviorDescriptor<O>) -> Self {
+        assert!(n_bins > 0);
+
+        QualityDiversityArchive {
+            n_bins,
+            descriptor,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn cell(&self, descriptor: &[f32]) -> Cell {
+        descriptor
+            .iter()
+            .map(|value| ((value.clamp(0., 1.) * self.n_bins as f32) as usize).min(self.n_bins - 1))
+            .collect()
+    }
+
+    /// Stores the individual if its cell is empty or it beats the current elite per
+    /// `Fitness::DIRECTION`.
+    /// Returns whether the archive changed.
+    pub fn insert(&mut self, individual: &O, parameters: &O::FitnessParameters) -> bool {
+        let fitness = match individual.get_fitness() {
+            Some(fitness) => fitness,
+            None => return false,
+        };
+
+        let cell = self.cell(&(self.descriptor)(individual, parameters));
+
+        let elite_fitness = self.cells.get(&cell).and_then(|elite| elite.get_fitness());
+        let is_elite = O::DIRECTION.compare(Some(fitness), elite_fitness) == Ordering::Greater;
+
+        if is_elite {
+            self.cells.insert(cell, individual.clone());
+        }
+
+        is_elite
+    }
+
+    pub fn update(&mut self, population: &Population<O>, parameters: &O::FitnessParameters)
+    where
+        O: PartialOrd,
+    {
+        for individual in population.iter() {
+            self.insert(individual, parameters);
+        }
+    }
+
+    pub fn get(&self, cell: &[usize]) -> Option<&O> {
+        self.cells.get(cell)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Cell, &O)> {
+        self.cells.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+impl<O> fmt::Debug for QualityDiversityArchive<O>
+where
+    O: Fitness + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QualityDiversityArchive")
+            .field("n_bins", &self.n_bins)
+            .field("cells", &self.cells)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            characteristics::{Fitness, FitnessDirection, FitnessScore},
+            population::Population,
+        },
+        utils::test::TestOrganism,
+    };
+
+    use super::QualityDiversityArchive;
+
+    fn surrogate_descriptor(organism: &TestOrganism, _parameters: &()) -> Vec<f32> {
+        vec![organism.surrogate_fitness]
+    }
+
+    #[test]
+    fn given_population_when_archive_updated_then_best_individual_per_cell_is_retained() {
+        let population: Population<TestOrganism> = [(0.1, 1.), (0.15, 2.), (0.5, 3.), (0.9, 0.5)]
+            .into_iter()
+            .map(|(behavior, fitness)| {
+                let mut organism = TestOrganism::new(behavior, fitness);
+                organism.fitness = Some(fitness);
+                organism
+            })
+            .collect();
+
+        let mut archive = QualityDiversityArchive::new(4, surrogate_descriptor);
+        archive.update(&population, &());
+
+        pretty_assertions::assert_eq!(archive.len(), 3);
+        pretty_assertions::assert_eq!(archive.get(&[0]).and_then(|o| o.fitness), Some(2.));
+        pretty_assertions::assert_eq!(archive.get(&[2]).and_then(|o| o.fitness), Some(3.));
+        pretty_assertions::assert_eq!(archive.get(&[3]).and_then(|o| o.fitness), Some(0.5));
+        assert!(archive.get(&[1]).is_none());
+
+        let mut weaker = TestOrganism::new(0.5, 1.);
+        weaker.fitness = Some(1.);
+        assert!(!archive.insert(&weaker, &()));
+        pretty_assertions::assert_eq!(archive.get(&[2]).and_then(|o| o.fitness), Some(3.));
+    }
+
+    /// Scored by an error, so lower is better.
+    #[derive(Clone)]
+    struct ErrorScored(FitnessScore);
+
+    impl Fitness for ErrorScored {
+        type FitnessParameters = ();
+
+        const DIRECTION: FitnessDirection = FitnessDirection::Minimize;
+
+        fn eval_fitness(&mut self, _parameters: &mut ()) -> FitnessScore {
+            self.0
+        }
+
+        fn get_fitness(&self) -> Option<FitnessScore> {
+            Some(self.0)
+        }
+    }
+
+    #[test]
+    fn given_minimized_fitness_when_inserted_then_lower_error_becomes_the_elite() {
+        let mut archive = QualityDiversityArchive::new(1, |_: &ErrorScored, _: &()| vec![0.]);
+
+        assert!(archive.insert(&ErrorScored(0.5), &()));
+        assert!(!archive.insert(&ErrorScored(0.9), &()));
+        assert!(archive.insert(&ErrorScored(0.1), &()));
+        pretty_assertions::assert_eq!(archive.get(&[0]).map(|elite| elite.0), Some(0.1));
+    }
+}
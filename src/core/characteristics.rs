@@ -1,24 +1,201 @@
+use std::{cmp::Ordering, mem};
+
+use rand::Rng;
+use serde::Serialize;
+
+use crate::utils::random::generator;
+
 use super::registers::R32;
 
 pub type FitnessScore = R32;
 
+/// Whether higher (e.g. accuracy) or lower (e.g. mean squared error) fitness scores are better.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum FitnessDirection {
+    Maximize,
+    Minimize,
+}
+
+impl FitnessDirection {
+    /// Maps a score onto a scale where higher is always better.
+    pub fn gain(&self, score: FitnessScore) -> FitnessScore {
+        match self {
+            FitnessDirection::Maximize => score,
+            FitnessDirection::Minimize => -score,
+        }
+    }
+
+    /// Orders scores from worst to best. Unevaluated individuals (`None`) are worst of all.
+    pub fn compare(&self, a: Option<FitnessScore>, b: Option<FitnessScore>) -> Ordering {
+        let gain = |score: Option<FitnessScore>| score.map(|score| self.gain(score));
+        gain(a).partial_cmp(&gain(b)).unwrap()
+    }
+}
+
 pub trait Fitness {
     type FitnessParameters;
 
+    /// How scores are compared when ranking, selecting and reporting the best individual.
+    const DIRECTION: FitnessDirection = FitnessDirection::Maximize;
+
     fn eval_fitness(&mut self, parameters: &mut Self::FitnessParameters) -> FitnessScore;
     fn get_fitness(&self) -> Option<FitnessScore>;
+
+    /// Spread of the score across evaluation runs, for individuals whose `eval_fitness`
+    /// aggregates several runs.
+    fn get_fitness_variance(&self) -> Option<FitnessScore> {
+        None
+    }
+
+    /// Records the outcome of an earlier `eval_fitness` call made on an identically keyed
+    /// individual (see `fitness_key`), leaving everything else untouched. Individuals opting
+    /// into caching must override this; the default ignores the score.
+    fn set_fitness(&mut self, _fitness: FitnessScore, _variance: Option<FitnessScore>) {}
+
+    /// Cheap estimate of `eval_fitness` used to filter individuals before a full evaluation.
+    /// Defaults to the full evaluation.
+    fn eval_surrogate_fitness(&mut self, parameters: &mut Self::FitnessParameters) -> FitnessScore {
+        self.eval_fitness(parameters)
+    }
+
+    /// Explains why `parameters` can't evaluate individuals, so runs can reject them upfront
+    /// rather than panic in `eval_fitness`. Accepts everything by default.
+    fn validate_fitness_parameters(_parameters: &Self::FitnessParameters) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Whether `eval_surrogate_fitness` is cheaper than `eval_fitness` under `parameters`.
+    /// Defaults to `false`, matching the default surrogate.
+    fn has_surrogate_fitness(_parameters: &Self::FitnessParameters) -> bool {
+        false
+    }
+
+    /// Objective values (all maximized) used by multi-objective selection. Defaults to the
+    /// single fitness score, negated when `DIRECTION` minimizes it.
+    fn objectives(&self) -> Option<Vec<FitnessScore>> {
+        self.get_fitness()
+            .map(|fitness| vec![Self::DIRECTION.gain(fitness)])
+    }
+
+    /// Identifies individuals guaranteed to share a fitness score, letting a `FitnessCache`
+    /// skip re-evaluating them. Requires `eval_fitness` to be deterministic; `None` (the
+    /// default) opts out of caching.
+    fn fitness_key(&self) -> Option<u64> {
+        None
+    }
+
+    /// Identifies the fitness parameters scores were computed under, so a `FitnessCache` can
+    /// drop its scores once they change (e.g. new inputs). With `None` (the default), the cache
+    /// must be cleared by hand.
+    fn parameters_key(_parameters: &Self::FitnessParameters) -> Option<u64>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
+pub trait Length {
+    /// The number of genes (e.g. instructions) making up the individual.
+    fn length(&self) -> usize;
+}
+
+/// Identity used to rebuild family trees with a `Genealogy`. The defaults opt out of lineage
+/// tracking.
+pub trait Lineage {
+    fn id(&self) -> Option<u64> {
+        None
+    }
+
+    fn parent_ids(&self) -> &[u64] {
+        &[]
+    }
+
+    fn set_lineage(&mut self, _id: u64, _parent_ids: Vec<u64>) {}
+}
+
+/// Crossover operators. Each draws its random choices from `generator()`, or from a given `rng`
+/// through its `*_with_rng` form.
 pub trait Breed: Clone {
-    fn two_point_crossover(&self, mate: &Self) -> [Self; 2];
+    fn two_point_crossover(&self, mate: &Self) -> [Self; 2] {
+        self.two_point_crossover_with_rng(mate, &mut generator())
+    }
+
+    fn two_point_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R) -> [Self; 2];
+
+    /// Produces a child drawing each gene from either parent with equal probability.
+    fn uniform_crossover(&self, mate: &Self) -> Self {
+        self.uniform_crossover_with_rng(mate, &mut generator())
+    }
+
+    fn uniform_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R) -> Self;
+
+    /// Like `two_point_crossover`, but only swaps segments occupying the same positions in both
+    /// parents, so each child keeps the length of its parent.
+    fn homologous_crossover(&self, mate: &Self) -> [Self; 2] {
+        self.homologous_crossover_with_rng(mate, &mut generator())
+    }
+
+    fn homologous_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R)
+        -> [Self; 2];
+
+    /// Hash of the genes, equal for individuals with identical genes, letting deduplication
+    /// compare hashes instead of whole individuals. `None` (the default) falls back to
+    /// `PartialEq`.
+    fn genome_hash(&self) -> Option<u64> {
+        None
+    }
 }
 
 pub trait Mutate: Generate + Clone {
-    fn mutate<'a>(&self, parameters: &'a Self::GeneratorParameters) -> Self;
+    fn mutate(&self, parameters: &Self::GeneratorParameters) -> Self {
+        self.mutate_with_rng(parameters, &mut generator())
+    }
+
+    /// Like `mutate`, but every random choice is drawn from `rng`.
+    fn mutate_with_rng<R: Rng + ?Sized>(
+        &self,
+        parameters: &Self::GeneratorParameters,
+        rng: &mut R,
+    ) -> Self;
 }
 
 pub trait Generate {
     type GeneratorParameters;
 
-    fn generate<'a>(parameters: &'a Self::GeneratorParameters) -> Self;
+    fn generate(parameters: &Self::GeneratorParameters) -> Self
+    where
+        Self: Sized,
+    {
+        Self::generate_with_rng(parameters, &mut generator())
+    }
+
+    /// Like `generate`, but every random choice is drawn from `rng`.
+    fn generate_with_rng<R: Rng + ?Sized>(
+        parameters: &Self::GeneratorParameters,
+        rng: &mut R,
+    ) -> Self
+    where
+        Self: Sized;
+
+    /// Explains why `parameters` can't generate individuals, so runs can reject them upfront
+    /// rather than panic in `generate`. Accepts everything by default.
+    fn validate_parameters(_parameters: &Self::GeneratorParameters) -> Result<(), String>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    /// Upper bound on the memory (in bytes) held by a generated individual.
+    fn estimated_memory(_parameters: &Self::GeneratorParameters) -> usize
+    where
+        Self: Sized,
+    {
+        mem::size_of::<Self>()
+    }
+
+    /// Brings an offspring back within the limits of `parameters`, e.g. after crossover grew it
+    /// past the maximum length. Does nothing by default.
+    fn enforce_bounds(&mut self, _parameters: &Self::GeneratorParameters) {}
 }
@@ -0,0 +1,133 @@
+use std::collections::{HashMap, VecDeque};
+
+use derive_new::new;
+
+use super::characteristics::FitnessScore;
+
+/// The outcome of a single `eval_fitness` call, as restored by `Fitness::set_fitness`.
+#[derive(Clone, Copy, Debug, PartialEq, new)]
+pub struct CachedFitness {
+    pub fitness: FitnessScore,
+    pub variance: Option<FitnessScore>,
+}
+
+/// Evaluations keyed by `Fitness::fitness_key`, so individuals sharing a key reuse a single
+/// evaluation. Only sound when `eval_fitness` is deterministic.
+#[derive(Clone, Debug, Default)]
+pub struct FitnessCache {
+    entries: HashMap<u64, CachedFitness>,
+    /// Keys in insertion order, oldest first, used to evict once `max_len` is reached.
+    order: VecDeque<u64>,
+    max_len: Option<usize>,
+    /// `Fitness::parameters_key` of the parameters the entries were evaluated under.
+    parameters_key: Option<u64>,
+    hits: usize,
+    misses: usize,
+}
+
+impl FitnessCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bounds the cache to `max_len` entries, evicting the oldest evaluation first.
+    pub fn with_max_len(self, max_len: usize) -> Self {
+        Self {
+            max_len: Some(max_len),
+            ..self
+        }
+    }
+
+    /// Looks up an evaluation, counting the hit or miss.
+    pub fn get(&mut self, key: u64) -> Option<CachedFitness> {
+        let entry = self.entries.get(&key).copied();
+
+        match entry {
+            Some(_) => self.hits += 1,
+            None => self.misses += 1,
+        }
+
+        entry
+    }
+
+    pub fn insert(&mut self, key: u64, evaluation: CachedFitness) {
+        if self.max_len == Some(0) {
+            return;
+        }
+
+        if self.entries.insert(key, evaluation).is_none() {
+            self.order.push_back(key);
+        }
+
+        if let Some(max_len) = self.max_len {
+            while self.entries.len() > max_len {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    /// Drops every cached evaluation when `parameters_key` differs from the key of the
+    /// parameters they were made under, since their scores no longer apply.
+    pub fn sync_parameters(&mut self, parameters_key: Option<u64>) {
+        if self.parameters_key != parameters_key {
+            self.clear();
+            self.parameters_key = parameters_key;
+        }
+    }
+
+    /// Drops every cached evaluation, keeping the hit and miss counts. Call this whenever
+    /// fitness parameters without a `Fitness::parameters_key` change.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachedFitness, FitnessCache};
+
+    #[test]
+    fn given_full_cache_when_insert_then_oldest_evaluation_is_evicted() {
+        let mut cache = FitnessCache::new().with_max_len(2);
+
+        for key in 0..3 {
+            cache.insert(key, CachedFitness::new(key as f32, None));
+        }
+
+        pretty_assertions::assert_eq!(cache.len(), 2);
+        pretty_assertions::assert_eq!(cache.get(0), None);
+        pretty_assertions::assert_eq!(cache.get(2), Some(CachedFitness::new(2., None)));
+    }
+
+    #[test]
+    fn given_new_parameters_key_when_sync_parameters_then_evaluations_are_dropped() {
+        let mut cache = FitnessCache::new();
+        cache.sync_parameters(Some(1));
+        cache.insert(0, CachedFitness::new(0.5, None));
+
+        cache.sync_parameters(Some(1));
+        pretty_assertions::assert_eq!(cache.len(), 1);
+
+        cache.sync_parameters(Some(2));
+        pretty_assertions::assert_eq!(cache.len(), 0);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use super::characteristics::Lineage;
+
+/// An individual and the ids of the individuals it was bred from.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Ancestry {
+    pub id: u64,
+    pub parent_ids: Vec<u64>,
+}
+
+/// Assigns ids to individuals and records the parents of each, so the family tree of any
+/// individual can be rebuilt after a run.
+#[derive(Clone, Debug, Default)]
+pub struct Genealogy {
+    next_id: u64,
+    parent_ids: HashMap<u64, Vec<u64>>,
+    /// Generations of ancestors kept by `prune`; every ancestor is kept when unset.
+    max_depth: Option<usize>,
+}
+
+impl Genealogy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self {
+            max_depth: Some(max_depth),
+            ..self
+        }
+    }
+
+    /// Gives `individual` a fresh id and records `parent_ids` as its parents.
+    pub fn register<O>(&mut self, individual: &mut O, parent_ids: Vec<u64>)
+    where
+        O: Lineage,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.parent_ids.insert(id, parent_ids.clone());
+        individual.set_lineage(id, parent_ids);
+    }
+
+    pub fn parent_ids(&self, id: u64) -> Option<&[u64]> {
+        self.parent_ids
+            .get(&id)
+            .map(|parent_ids| parent_ids.as_slice())
+    }
+
+    /// Every registered ancestor of `id` (and `id` itself) within `max_depth` generations,
+    /// nearest first. Ancestors shared by several lines of descent appear once.
+    pub fn ancestry(&self, id: u64) -> Vec<Ancestry> {
+        self.walk(vec![id])
+            .into_iter()
+            .map(|id| Ancestry {
+                id,
+                parent_ids: self.parent_ids(id).unwrap_or_default().to_vec(),
+            })
+            .collect()
+    }
+
+    /// Forgets every individual that is neither one of `living_ids` nor their ancestor within
+    /// `max_depth` generations, so the genealogy stops growing with dead lines of descent.
+    pub fn prune(&mut self, living_ids: impl IntoIterator<Item = u64>) {
+        let kept: HashSet<u64> = self.walk(living_ids).into_iter().collect();
+        self.parent_ids.retain(|id, _| kept.contains(id));
+    }
+
+    /// Breadth-first walk from `ids` towards their ancestors, visiting each id once.
+    fn walk(&self, ids: impl IntoIterator<Item = u64>) -> Vec<u64> {
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<(u64, usize)> = ids.into_iter().map(|id| (id, 0)).collect();
+        let mut order = vec![];
+
+        while let Some((id, depth)) = queue.pop_front() {
+            if !visited.insert(id) {
+                continue;
+            }
+            order.push(id);
+
+            if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                for parent_id in self.parent_ids(id).unwrap_or_default() {
+                    queue.push_back((*parent_id, depth + 1));
+                }
+            }
+        }
+
+        order
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent_ids.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::core::characteristics::Lineage;
+
+    use super::Genealogy;
+
+    struct Individual;
+
+    impl Lineage for Individual {}
+
+    /// Registers `n_generations` generations of two individuals, each bred from both
+    /// individuals of the previous generation.
+    fn interbred(genealogy: &mut Genealogy, n_generations: usize) -> Vec<u64> {
+        let mut generation = vec![];
+
+        for _ in 0..n_generations {
+            let parent_ids = generation.clone();
+            generation = (0..2)
+                .map(|_| {
+                    genealogy.register(&mut Individual, parent_ids.clone());
+                    genealogy.len() as u64 - 1
+                })
+                .collect();
+        }
+
+        generation
+    }
+
+    #[test]
+    fn given_shared_ancestors_when_ancestry_then_each_ancestor_appears_once() {
+        let mut genealogy = Genealogy::new();
+        let last_generation = interbred(&mut genealogy, 40);
+
+        let ancestry = genealogy.ancestry(last_generation[0]);
+
+        // The individual itself, then two ancestors per earlier generation.
+        pretty_assertions::assert_eq!(ancestry.len(), 1 + 2 * 39);
+        assert!(ancestry.iter().map(|ancestor| ancestor.id).all_unique());
+    }
+
+    #[test]
+    fn given_max_depth_when_prune_then_only_recent_ancestors_of_the_living_are_kept() {
+        let mut genealogy = Genealogy::new().with_max_depth(2);
+        let last_generation = interbred(&mut genealogy, 10);
+
+        genealogy.prune(last_generation);
+
+        pretty_assertions::assert_eq!(genealogy.len(), 2 * 3);
+    }
+}
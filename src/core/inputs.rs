@@ -1,15 +1,106 @@
-use super::registers::{Registers, R32};
+use std::fmt;
+
+use crate::utils::executables::{Executables, DEFAULT_EXECUTABLES};
+
+use super::{
+    instruction::Mode,
+    registers::{Registers, R32},
+};
 
 pub type Inputs<InputType> = Vec<InputType>;
 
+/// A dataset row rejected by `ValidInput::validate_dataset`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatasetError {
+    /// The row converted to a different number of registers than `N_INPUT_REGISTERS`.
+    FeatureCount {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The row's class label has no matching action register.
+    ClassOutOfRange {
+        row: usize,
+        class: usize,
+        n_classes: usize,
+    },
+}
+
+impl fmt::Display for DatasetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatasetError::FeatureCount {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} has {} features, expected {}.",
+                row, found, expected
+            ),
+            DatasetError::ClassOutOfRange {
+                row,
+                class,
+                n_classes,
+            } => write!(
+                f,
+                "Row {} has class {}, expected one of the {} classes.",
+                row, class, n_classes
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DatasetError {}
+
+/// Checks that every input converts to exactly `N_INPUT_REGISTERS` registers.
+pub fn validate_feature_counts<T>(inputs: &Inputs<T>) -> Result<(), DatasetError>
+where
+    T: ValidInput,
+{
+    for (row, input) in inputs.iter().enumerate() {
+        let found = Registers::from(input).len();
+        if found != T::N_INPUT_REGISTERS {
+            return Err(DatasetError::FeatureCount {
+                row,
+                expected: T::N_INPUT_REGISTERS,
+                found,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 pub trait ValidInput: Clone
 where
     for<'a> Registers: From<&'a Self>,
 {
     const N_INPUT_REGISTERS: usize;
     const N_ACTION_REGISTERS: usize;
+    /// Modes instructions generated via `InstructionGeneratorParameters::from` may take.
+    const AVAILABLE_MODES: &'static [Mode] = Mode::ALL;
+    /// Executables instructions generated via `InstructionGeneratorParameters::from` may use.
+    const AVAILABLE_EXECUTABLES: Executables = DEFAULT_EXECUTABLES;
 
     fn flat(&self) -> Vec<R32>;
+
+    /// Names of the input features in `flat` order, used in place of `I[index]` when rendering
+    /// programs.
+    fn feature_names() -> Option<Vec<&'static str>> {
+        None
+    }
+
+    /// Called by `Loader::load_inputs` so malformed rows fail at load instead of mid-run.
+    /// Classification inputs can additionally call
+    /// `extensions::classification::validate_classes` to fail at load rather than when the
+    /// `ClassificationParameters` are built.
+    fn validate_dataset(inputs: &Inputs<Self>) -> Result<(), DatasetError>
+    where
+        Self: Sized,
+    {
+        validate_feature_counts(inputs)
+    }
 }
 
 impl<T> From<&T> for Registers
@@ -20,3 +111,59 @@ where
         input.flat().into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use serde::Deserialize;
+
+    use crate::core::{
+        algorithm::Loader,
+        registers::{Registers, R32},
+    };
+
+    use super::{DatasetError, ValidInput};
+
+    #[derive(Clone, Debug, Deserialize)]
+    struct RowInput(Vec<R32>);
+
+    impl ValidInput for RowInput {
+        const N_INPUT_REGISTERS: usize = 3;
+        const N_ACTION_REGISTERS: usize = 2;
+
+        fn flat(&self) -> Vec<R32> {
+            self.0.clone()
+        }
+    }
+
+    struct RowLoader;
+
+    impl Loader for RowLoader {
+        type InputType = RowInput;
+    }
+
+    #[test]
+    fn given_a_short_row_when_dataset_is_validated_then_the_row_is_reported() {
+        let inputs = vec![RowInput(vec![1., 2., 3.]), RowInput(vec![1., 2.])];
+
+        pretty_assertions::assert_eq!(
+            RowInput::validate_dataset(&inputs),
+            Err(DatasetError::FeatureCount {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        pretty_assertions::assert_eq!(Registers::from(&inputs[0]).len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Row 1 has 2 features, expected 3.")]
+    fn given_a_csv_with_a_short_row_when_inputs_are_loaded_then_loading_fails() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(&file, "1,2,3\n4,5").unwrap();
+
+        RowLoader::load_inputs(file.path());
+    }
+}
@@ -2,33 +2,50 @@ use derive_new::new;
 use rand::distributions::uniform::{UniformInt, UniformSampler};
 use rand::prelude::SliceRandom;
 use rand::Rng;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
 
-use crate::utils::executables::{Op, DEFAULT_EXECUTABLES};
-use crate::utils::random::generator;
+use crate::utils::executables::{
+    deserialize_executable, display_executable, serialize_executable, serialize_executables, Op,
+    DEFAULT_EXECUTABLES,
+};
 
 use super::characteristics::{Generate, Mutate};
 use super::inputs::ValidInput;
-use super::registers::Registers;
+use super::registers::{sanitize, Registers, R32};
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Mode {
+    /// Reads `I[target]`, an input feature. Features live outside the register file, so no
+    /// instruction can overwrite them.
     External,
+    /// Reads `R[target]`, another register.
     Internal,
+    /// Compares `R[source] < R[target]`; when false, the next instruction is skipped.
+    Branch,
 }
 
 impl Mode {
-    fn sample<R: Rng + ?Sized>(rng: &mut R) -> Mode {
-        let mode_repr = UniformInt::<usize>::new_inclusive(0, 1).sample(rng);
+    pub const ALL: &'static [Mode] = &[Mode::External, Mode::Internal, Mode::Branch];
 
-        if mode_repr == 0 {
-            Mode::External
-        } else {
-            Mode::Internal
-        }
+    /// Draws uniformly from `available_modes`, leaving out branches unless they are allowed.
+    fn sample<R: Rng + ?Sized>(
+        rng: &mut R,
+        available_modes: &[Mode],
+        allow_branches: bool,
+    ) -> Mode {
+        let modes = available_modes
+            .iter()
+            .filter(|mode| allow_branches || **mode != Mode::Branch)
+            .collect::<Vec<_>>();
+        assert!(!modes.is_empty(), "No mode is available to generate from.");
+
+        let mode_index = UniformInt::<usize>::new(0, modes.len()).sample(rng);
+
+        modes[mode_index].clone()
     }
 }
 
@@ -36,23 +53,45 @@ impl Mode {
 pub struct InstructionGeneratorParameters {
     pub n_registers: usize,
     pub n_features: usize,
+    /// Whether generated instructions may be conditional branches.
+    #[new(default)]
+    pub allow_branches: bool,
+    /// Modes generated instructions are drawn from: `External` instructions read an input
+    /// feature, `Internal` ones another register.
+    #[new(value = "Mode::ALL.to_vec()")]
+    pub available_modes: Vec<Mode>,
+    /// Executables generated instructions are drawn from. Overriding it restricts the operator
+    /// set for a single run, e.g. for ablation studies.
+    #[new(value = "DEFAULT_EXECUTABLES.to_vec()")]
+    #[serde(serialize_with = "serialize_executables")]
+    pub available_executables: Vec<Op>,
 }
 
 impl InstructionGeneratorParameters {
+    /// Sizes the register file for `T`: its output registers followed by `n_extras` scratch
+    /// registers. Instructions read and write every register, but only the outputs are read as
+    /// the program's prediction.
     pub fn from<T: ValidInput>(n_extras: usize) -> Self {
-        InstructionGeneratorParameters::new(
-            <T as ValidInput>::N_ACTION_REGISTERS + n_extras,
-            <T as ValidInput>::N_INPUT_REGISTERS,
-        )
+        InstructionGeneratorParameters {
+            available_modes: <T as ValidInput>::AVAILABLE_MODES.to_vec(),
+            available_executables: <T as ValidInput>::AVAILABLE_EXECUTABLES.to_vec(),
+            ..InstructionGeneratorParameters::new(
+                <T as ValidInput>::N_ACTION_REGISTERS + n_extras,
+                <T as ValidInput>::N_INPUT_REGISTERS,
+            )
+        }
     }
 }
 
-#[derive(Serialize, Eq)]
+#[derive(Serialize, Deserialize, Eq, new)]
 pub struct Instruction {
     source_index: usize,
     target_index: usize,
     mode: Mode,
-    #[serde(skip_serializing)]
+    #[serde(
+        serialize_with = "serialize_executable",
+        deserialize_with = "deserialize_executable"
+    )]
     executable: Op,
 }
 
@@ -67,32 +106,43 @@ impl Clone for Instruction {
     }
 }
 
+/// The name of input feature `index`, falling back to `I[index]`.
+pub fn feature_label(index: usize, feature_names: Option<&[&str]>) -> String {
+    match feature_names.and_then(|names| names.get(index)) {
+        Some(name) => name.to_string(),
+        None => format!("I[{}]", index),
+    }
+}
+
 impl Generate for Instruction {
     type GeneratorParameters = InstructionGeneratorParameters;
 
-    fn generate<'a>(parameters: &'a Self::GeneratorParameters) -> Self {
+    fn generate_with_rng<R: Rng + ?Sized>(
+        parameters: &Self::GeneratorParameters,
+        rng: &mut R,
+    ) -> Self {
         let InstructionGeneratorParameters {
             n_features: n_inputs,
             n_registers,
+            allow_branches,
+            available_modes,
+            available_executables,
         } = parameters;
 
-        let current_generator = &mut generator();
-
-        let source_index = UniformInt::<usize>::new(0, n_registers).sample(current_generator);
+        let source_index = UniformInt::<usize>::new(0, n_registers).sample(rng);
 
-        let mode = Mode::sample(current_generator);
+        let mode = Mode::sample(rng, available_modes, *allow_branches);
 
         let upper_bound_target_index = *(if mode == Mode::External {
             n_inputs
         } else {
             n_registers
         });
-        let target_index =
-            UniformInt::<usize>::new(0, upper_bound_target_index).sample(current_generator);
+        let target_index = UniformInt::<usize>::new(0, upper_bound_target_index).sample(rng);
 
-        let exec = DEFAULT_EXECUTABLES
-            .choose(current_generator)
-            .unwrap()
+        let exec = available_executables
+            .choose(rng)
+            .expect("No executable is available to generate from.")
             .to_owned();
 
         Instruction {
@@ -113,6 +163,15 @@ impl PartialEq for Instruction {
     }
 }
 
+impl Hash for Instruction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source_index.hash(state);
+        self.target_index.hash(state);
+        self.mode.hash(state);
+        (self.executable as usize).hash(state);
+    }
+}
+
 impl Debug for Instruction {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("Instruction")
@@ -124,12 +183,16 @@ impl Debug for Instruction {
 }
 
 impl Mutate for Instruction {
-    fn mutate<'a>(&self, params: &'a Self::GeneratorParameters) -> Self {
-        let mut mutated = Self::generate(&params);
+    fn mutate_with_rng<R: Rng + ?Sized>(
+        &self,
+        params: &Self::GeneratorParameters,
+        rng: &mut R,
+    ) -> Self {
+        let mut mutated = Self::generate_with_rng(params, rng);
 
-        let swap_target = generator().gen_bool(0.5);
-        let swap_source = generator().gen_bool(0.5);
-        let swap_exec = generator().gen_bool(0.5);
+        let swap_target = rng.gen_bool(0.5);
+        let swap_source = rng.gen_bool(0.5);
+        let swap_exec = rng.gen_bool(0.5);
 
         // Flip a Coin: Target
         if swap_target {
@@ -152,27 +215,192 @@ impl Mutate for Instruction {
 }
 
 impl Instruction {
-    fn get_target_data<'b, T>(&self, registers: Registers, data: &'b T) -> Registers
+    /// The register written to (and read from) by this instruction.
+    pub fn source_index(&self) -> usize {
+        self.source_index
+    }
+
+    pub fn target_index(&self) -> usize {
+        self.target_index
+    }
+
+    pub fn mode(&self) -> &Mode {
+        &self.mode
+    }
+
+    pub fn executable(&self) -> Op {
+        self.executable
+    }
+
+    /// A copy whose indices are reduced the way `apply` reduces them: modulo `n_registers`,
+    /// or modulo `n_features` for an `External` target. Indices into an empty range are kept.
+    pub fn wrapped(&self, n_registers: usize, n_features: usize) -> Self {
+        let wrap = |index: usize, len: usize| index.checked_rem(len).unwrap_or(index);
+        let n_targets = match self.mode {
+            Mode::Internal | Mode::Branch => n_registers,
+            Mode::External => n_features,
+        };
+
+        Instruction {
+            source_index: wrap(self.source_index, n_registers),
+            target_index: wrap(self.target_index, n_targets),
+            ..self.clone()
+        }
+    }
+
+    /// Renders the instruction as e.g. `R[2] = R[2] + I[0]`, where `R` holds registers and `I`
+    /// holds input features. Branches render as `if R[0] < R[1]`.
+    pub fn to_assembly(&self) -> String {
+        self.to_named_assembly(None)
+    }
+
+    /// Like `to_assembly`, but input features are rendered by name where one is given.
+    pub fn to_named_assembly(&self, feature_names: Option<&[&str]>) -> String {
+        let source = format!("R[{}]", self.source_index);
+        let target = match self.mode {
+            Mode::Internal | Mode::Branch => format!("R[{}]", self.target_index),
+            Mode::External => feature_label(self.target_index, feature_names),
+        };
+
+        match self.mode {
+            Mode::Branch => format!("if {} < {}", source, target),
+            _ => format!(
+                "{} = {}",
+                source,
+                display_executable(self.executable, &source, &target)
+            ),
+        }
+    }
+
+    /// Whether a branch lets the next instruction execute. Indices wrap like in `apply`.
+    pub fn condition_holds(&self, registers: &Registers) -> bool {
+        match (
+            registers.get_wrapped(self.source_index),
+            registers.get_wrapped(self.target_index),
+        ) {
+            (Some(source), Some(target)) => source < target,
+            _ => true,
+        }
+    }
+
+    /// The operand read from `R[target]` or, for `Mode::External`, `I[target]`, wrapped into
+    /// range. `None` when there is nothing to read.
+    fn target_value<T>(&self, registers: &Registers, input: &T) -> Option<R32>
     where
         T: ValidInput,
     {
-        let target_data: Registers = match self.mode {
-            Mode::Internal => registers,
-            Mode::External => data.into(),
-        };
+        match self.mode {
+            Mode::Internal | Mode::Branch => registers.get_wrapped(self.target_index).copied(),
+            Mode::External => {
+                let features = input.flat();
+                let index = self.target_index.checked_rem(features.len())?;
 
-        target_data
+                Some(features[index])
+            }
+        }
     }
 
+    /// Writes the (sanitized) result of the instruction to its source register. Branches write
+    /// nothing.
+    ///
+    /// Indices are taken modulo the number of registers (or input features), so no mutation or
+    /// crossover can produce a crashing program. The tradeoff is a slight bias towards low
+    /// indices when an index exceeds the register count.
     pub fn apply<'b, T>(&self, registers: &'b mut Registers, input: &'b T)
     where
         T: ValidInput,
     {
-        let cloned_registers = registers.clone();
-        let data = self.get_target_data(cloned_registers, input);
-        let target_value = *data.get(self.target_index);
-        let source_value = *registers.get(self.source_index);
-        let new_source_value = (self.executable)(source_value, target_value);
-        registers.update(self.source_index, new_source_value);
+        if self.mode == Mode::Branch {
+            return;
+        }
+
+        let target_value = match self.target_value(registers, input) {
+            Some(value) => value,
+            None => return,
+        };
+
+        if let Some(source) = registers.get_wrapped_mut(self.source_index) {
+            *source = sanitize((self.executable)(*source, target_value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::utils::{executables::add, test::TestInput};
+
+    use super::*;
+
+    #[test]
+    fn given_all_modes_when_generate_then_both_data_modes_appear() {
+        let params = InstructionGeneratorParameters::from::<TestInput>(1);
+        pretty_assertions::assert_eq!(params.available_modes, Mode::ALL.to_vec());
+
+        let modes = (0..500)
+            .map(|_| Instruction::generate(&params).mode)
+            .collect::<HashSet<_>>();
+
+        pretty_assertions::assert_eq!(modes, HashSet::from([Mode::External, Mode::Internal]));
+    }
+
+    #[test]
+    fn given_single_available_mode_when_generate_then_only_that_mode_appears() {
+        let mut params = InstructionGeneratorParameters::from::<TestInput>(1);
+        params.available_modes = vec![Mode::Internal];
+        params.allow_branches = true;
+
+        for _ in 0..500 {
+            let instruction = Instruction::generate(&params);
+
+            pretty_assertions::assert_eq!(instruction.mode, Mode::Internal);
+            assert!(instruction.target_index < params.n_registers);
+        }
+    }
+
+    #[test]
+    fn given_mode_when_apply_then_operand_is_read_from_matching_source() {
+        let input = TestInput([5., 6., 7., 8., 0.]);
+        let mut registers = Registers::from(vec![1., 2., 3.]);
+
+        Instruction::new(0, 1, Mode::External, add).apply(&mut registers, &input);
+        pretty_assertions::assert_eq!(*registers.get(0), 1. + 6.);
+
+        Instruction::new(0, 1, Mode::Internal, add).apply(&mut registers, &input);
+        pretty_assertions::assert_eq!(*registers.get(0), 7. + 2.);
+    }
+
+    #[test]
+    fn given_out_of_range_indices_when_apply_then_indices_wrap_around_the_registers() {
+        let input = TestInput([1., 2., 3., 4., 0.]);
+        let mut registers = Registers::from(vec![1., 2., 3., 4.]);
+
+        // R[99 % 4] = R[3] + R[1]
+        Instruction::new(99, 1, Mode::Internal, add).apply(&mut registers, &input);
+        pretty_assertions::assert_eq!(*registers.get(99 % 4), 4. + 2.);
+
+        // R[0] = R[0] + R[99 % 4]
+        Instruction::new(0, 99, Mode::Internal, add).apply(&mut registers, &input);
+        pretty_assertions::assert_eq!(*registers.get(0), 1. + 6.);
+
+        // R[1] = R[1] + I[9 % 4]
+        Instruction::new(1, 9, Mode::External, add).apply(&mut registers, &input);
+        pretty_assertions::assert_eq!(*registers.get(1), 2. + 2.);
+
+        // if R[99 % 4] < R[0], i.e. 6 < 7
+        assert!(Instruction::new(99, 0, Mode::Branch, add).condition_holds(&registers));
+    }
+
+    #[test]
+    fn given_instruction_writing_register_zero_when_apply_then_input_features_are_unchanged() {
+        let input = TestInput([1., 2., 3., 4., 0.]);
+        let mut registers = Registers::new(2);
+
+        Instruction::new(0, 0, Mode::External, add).apply(&mut registers, &input);
+        Instruction::new(0, 0, Mode::Internal, add).apply(&mut registers, &input);
+
+        pretty_assertions::assert_eq!(*registers.get(0), 2.);
+        pretty_assertions::assert_eq!(input.flat(), vec![1., 2., 3., 4.]);
     }
 }
@@ -1,39 +1,88 @@
-use rand::{distributions::Uniform, prelude::Distribution};
+use rand::{distributions::Uniform, prelude::Distribution, Rng};
 
-use crate::utils::{linked_list::LinkedList, random::generator};
+use crate::utils::linked_list::LinkedList;
 
 use super::{characteristics::Breed, instruction::Instruction};
 
 impl Breed for Instructions {
-    fn two_point_crossover(&self, mate: &Self) -> [Self; 2] {
+    fn two_point_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R) -> [Self; 2] {
         let mut instructions_a = self.clone();
         let mut instructions_b = mate.clone();
-        let current_generator = &mut generator();
 
-        let a_start = Uniform::new(0, instructions_a.len()).sample(current_generator);
+        let a_start = Uniform::new(0, instructions_a.len()).sample(rng);
         let a_end = if a_start == instructions_a.len() - 1 {
             None
         } else {
-            let tmp_end = Uniform::new(a_start + 1, instructions_a.len()).sample(current_generator);
+            let tmp_end = Uniform::new(a_start + 1, instructions_a.len()).sample(rng);
 
             Some(tmp_end)
         };
 
-        let b_start = Uniform::new(0, instructions_b.len()).sample(current_generator);
+        let b_start = Uniform::new(0, instructions_b.len()).sample(rng);
         let b_end = if b_start == instructions_b.len() - 1 {
             None
         } else {
-            let tmp_end = Uniform::new(b_start + 1, instructions_b.len()).sample(current_generator);
+            let tmp_end = Uniform::new(b_start + 1, instructions_b.len()).sample(rng);
             Some(tmp_end)
         };
 
         let mut cursor_a = instructions_a.cursor_mut();
         let mut cursor_b = instructions_b.cursor_mut();
 
-        cursor_a.swap(&mut cursor_b, a_start, b_start, a_end, b_end);
+        if cursor_a
+            .swap(&mut cursor_b, a_start, b_start, a_end, b_end)
+            .is_err()
+        {
+            // A refused swap leaves both lists untouched; the children are plain copies.
+            return [self.clone(), mate.clone()];
+        }
 
         [instructions_a, instructions_b]
     }
+
+    fn homologous_crossover_with_rng<R: Rng + ?Sized>(
+        &self,
+        mate: &Self,
+        rng: &mut R,
+    ) -> [Self; 2] {
+        let mut instructions_a = self.clone();
+        let mut instructions_b = mate.clone();
+
+        // Segments must lie within both parents to be aligned.
+        let shared_len = instructions_a.len().min(instructions_b.len());
+        if shared_len == 0 {
+            return [instructions_a, instructions_b];
+        }
+
+        let start = Uniform::new(0, shared_len).sample(rng);
+        let end = Uniform::new_inclusive(start + 1, shared_len).sample(rng);
+
+        let mut cursor_a = instructions_a.cursor_mut();
+        let mut cursor_b = instructions_b.cursor_mut();
+
+        if cursor_a
+            .swap(&mut cursor_b, start, start, Some(end), Some(end))
+            .is_err()
+        {
+            // A refused swap leaves both lists untouched; the children are plain copies.
+            return [self.clone(), mate.clone()];
+        }
+
+        [instructions_a, instructions_b]
+    }
+
+    /// The child keeps the length of `self`: aligned positions are taken from either parent
+    /// with probability 0.5, while the tail of `self` past the end of `mate` is inherited as is.
+    fn uniform_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R) -> Self {
+        let mut mate_instructions = mate.iter();
+
+        self.iter()
+            .map(|instruction| match mate_instructions.next() {
+                Some(mate_instruction) if rng.gen_bool(0.5) => mate_instruction.clone(),
+                _ => instruction.clone(),
+            })
+            .collect()
+    }
 }
 
 pub type Instructions = LinkedList<Instruction>;
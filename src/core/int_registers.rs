@@ -0,0 +1,171 @@
+use core::slice::Iter;
+use std::{ops::Index, slice::SliceIndex};
+
+use serde::{Deserialize, Serialize};
+
+pub type I32 = i32;
+
+/// Integer counterpart of `Op`: the destination value `a` combined with the operand `b`.
+pub type IntOp = fn(a: I32, b: I32) -> I32;
+
+pub const INT_EXECUTABLES: &[IntOp] = &[int_add, int_subtract, int_multiply, int_divide];
+
+/// Integer executables under stable names.
+pub const NAMED_INT_EXECUTABLES: &[(&str, IntOp)] = &[
+    ("int_add", int_add),
+    ("int_subtract", int_subtract),
+    ("int_multiply", int_multiply),
+    ("int_divide", int_divide),
+];
+
+pub fn int_executable_name(executable: IntOp) -> Option<&'static str> {
+    NAMED_INT_EXECUTABLES
+        .iter()
+        .find(|(_, named)| *named as usize == executable as usize)
+        .map(|(name, _)| *name)
+}
+
+/// Integer-valued registers for discrete problems (boolean logic, integer arithmetic), where
+/// `Registers`' floats only add rounding noise. Arithmetic wraps on overflow, so every program
+/// stays well-defined.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IntRegisters {
+    data: Vec<I32>,
+    /// Values restored on `reset`.
+    initial: Vec<I32>,
+}
+
+impl From<Vec<I32>> for IntRegisters {
+    fn from(data: Vec<I32>) -> Self {
+        let initial = data.clone();
+        IntRegisters { data, initial }
+    }
+}
+
+impl IntRegisters {
+    pub fn new(n_registers: usize) -> Self {
+        vec![0; n_registers].into()
+    }
+
+    pub fn reset(&mut self) {
+        let IntRegisters { data, initial } = self;
+        data.clone_from(initial);
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// `R[index]`, or `None` when `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<&I32> {
+        self.data.get(index)
+    }
+
+    /// Writes `value` to `R[index]`, ignoring out-of-range indices.
+    pub fn update(&mut self, index: usize, value: I32) {
+        if let Some(register) = self.data.get_mut(index) {
+            *register = value;
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, I32> {
+        self.data.iter()
+    }
+
+    /// Writes `executable(R[destination], operand)` to `R[destination]`, wrapping the index into
+    /// range like `Registers::get_wrapped`. Does nothing when there are no registers.
+    pub fn apply(&mut self, destination: usize, executable: IntOp, operand: I32) {
+        let destination = match destination.checked_rem(self.len()) {
+            Some(destination) => destination,
+            None => return,
+        };
+
+        self.data[destination] = executable(self.data[destination], operand);
+    }
+}
+
+impl<Idx> Index<Idx> for IntRegisters
+where
+    Idx: SliceIndex<[I32]>,
+{
+    type Output = Idx::Output;
+
+    fn index(&self, index: Idx) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+pub fn int_add(a: I32, b: I32) -> I32 {
+    a.wrapping_add(b)
+}
+
+pub fn int_subtract(a: I32, b: I32) -> I32 {
+    a.wrapping_sub(b)
+}
+
+pub fn int_multiply(a: I32, b: I32) -> I32 {
+    a.wrapping_mul(b)
+}
+
+/// Protected division truncating towards zero: `a / b`, or `a` unchanged when `b` is 0.
+pub fn int_divide(a: I32, b: I32) -> I32 {
+    if b == 0 {
+        a
+    } else {
+        a.wrapping_div(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_integer_operands_when_executables_are_applied_then_results_are_exact() {
+        pretty_assertions::assert_eq!(int_add(7, -3), 4);
+        pretty_assertions::assert_eq!(int_subtract(7, -3), 10);
+        pretty_assertions::assert_eq!(int_multiply(7, -3), -21);
+        pretty_assertions::assert_eq!(int_divide(7, 2), 3);
+        pretty_assertions::assert_eq!(int_divide(-7, 2), -3);
+    }
+
+    #[test]
+    fn given_edge_cases_when_executables_are_applied_then_they_wrap_or_fall_back() {
+        pretty_assertions::assert_eq!(int_divide(7, 0), 7);
+        pretty_assertions::assert_eq!(int_divide(I32::MIN, -1), I32::MIN);
+        pretty_assertions::assert_eq!(int_add(I32::MAX, 1), I32::MIN);
+        pretty_assertions::assert_eq!(int_subtract(I32::MIN, 1), I32::MAX);
+        pretty_assertions::assert_eq!(int_multiply(I32::MAX, 2), -2);
+    }
+
+    #[test]
+    fn given_int_registers_when_applied_and_reset_then_initial_values_are_restored() {
+        let mut registers = IntRegisters::from(vec![1, 2, 3]);
+
+        registers.apply(0, int_multiply, 5);
+        registers.apply(4, int_add, 10);
+        pretty_assertions::assert_eq!(registers[..], [5, 12, 3]);
+
+        registers.reset();
+        pretty_assertions::assert_eq!(registers[..], [1, 2, 3]);
+    }
+
+    #[test]
+    fn given_out_of_range_or_empty_registers_when_accessed_then_nothing_panics() {
+        let mut registers = IntRegisters::from(vec![1, 2]);
+        registers.update(2, 10);
+
+        pretty_assertions::assert_eq!(registers.get(1), Some(&2));
+        pretty_assertions::assert_eq!(registers.get(2), None);
+        pretty_assertions::assert_eq!(registers[..], [1, 2]);
+
+        let mut empty = IntRegisters::new(0);
+        empty.apply(3, int_add, 1);
+
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+use std::marker::PhantomData;
+
+use derive_new::new;
+use serde::Serialize;
+
+use super::{
+    algorithm::{GeneticAlgorithm, HyperParameters, RunState},
+    characteristics::Fitness,
+    population::Population,
+};
+
+#[derive(Clone, Debug, Serialize, new)]
+pub struct IslandParameters {
+    pub n_islands: usize,
+    /// Generations between migrations.
+    pub migration_interval: usize,
+    /// Number of top individuals each island sends to its neighbor.
+    pub migration_size: usize,
+}
+
+/// Evolves several populations, periodically copying the best individuals of each island over
+/// the worst of the next one (in a ring).
+///
+/// Islands are stepped one after another on the calling thread. Each generation goes through the
+/// same ranking, selection and breeding as `GeneticAlgorithm::execute` (fitness cache, surrogate
+/// cutoff, replacement strategy, dedup and archive included), but `EventHooks`, stop conditions,
+/// adaptive mutation, snapshots and time budgets are not applied.
+pub struct IslandModel<G>
+where
+    G: GeneticAlgorithm,
+{
+    pub islands: Vec<Population<G::O>>,
+    pub parameters: IslandParameters,
+    marker: PhantomData<G>,
+}
+
+impl<G> IslandModel<G>
+where
+    G: GeneticAlgorithm,
+{
+    pub fn new(hyper_params: &HyperParameters<G::O>, parameters: IslandParameters) -> Self {
+        assert!(parameters.n_islands > 0);
+        assert!(parameters.migration_interval > 0);
+
+        let islands = (0..parameters.n_islands)
+            .map(|_| G::init_population(hyper_params))
+            .collect();
+
+        IslandModel {
+            islands,
+            parameters,
+            marker: PhantomData,
+        }
+    }
+
+    /// Replaces the worst `migration_size` individuals of every island with copies of the best
+    /// ones of the island before it. Islands are expected to be ranked.
+    pub fn migrate(&mut self) {
+        let n_islands = self.islands.len();
+
+        if n_islands < 2 {
+            return;
+        }
+
+        let emigrants: Vec<Vec<G::O>> = self
+            .islands
+            .iter()
+            .map(|island| {
+                island
+                    .iter()
+                    .take(self.parameters.migration_size)
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+
+        for (index, migrants) in emigrants.into_iter().enumerate() {
+            let neighbor = &mut self.islands[(index + 1) % n_islands];
+
+            for _ in 0..migrants.len() {
+                neighbor.pop();
+            }
+
+            neighbor.extend(migrants);
+            neighbor.sort();
+        }
+    }
+
+    /// Ranks every island, migrates when due, then selects and breeds each island.
+    pub fn step(
+        &mut self,
+        hyper_params: &mut HyperParameters<G::O>,
+        state: &mut RunState<G::O>,
+        generation: usize,
+    ) {
+        for island in self.islands.iter_mut() {
+            G::rank_generation(island, hyper_params, state);
+
+            if let Some(archive) = state.archive.as_mut() {
+                archive.update(island, &hyper_params.fitness_parameters);
+            }
+        }
+
+        if (generation + 1).is_multiple_of(self.parameters.migration_interval) {
+            self.migrate();
+        }
+
+        for island in self.islands.iter_mut() {
+            G::select_survivors(island, hyper_params);
+            G::breed_generation(island, hyper_params, state, hyper_params.n_mutations);
+        }
+    }
+
+    /// Runs `max_generations` steps and ranks the islands one last time. The islands share
+    /// `state`.
+    pub fn run(&mut self, hyper_params: &mut HyperParameters<G::O>, state: &mut RunState<G::O>) {
+        for generation in 0..hyper_params.max_generations {
+            self.step(hyper_params, state, generation);
+        }
+
+        for island in self.islands.iter_mut() {
+            G::rank(island, &mut hyper_params.fitness_parameters);
+        }
+    }
+
+    /// The fittest individual across all (ranked) islands.
+    pub fn best(&self) -> Option<&G::O> {
+        self.islands
+            .iter()
+            .filter_map(|island| island.first())
+            .max_by(|a, b| G::O::DIRECTION.compare(a.get_fitness(), b.get_fitness()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            algorithm::{
+                CrossoverMethod, GeneticAlgorithm, HyperParameters, ParentSelection,
+                ReplacementStrategy, RunState,
+            },
+            population::Population,
+        },
+        utils::test::{TestOrganism, TestOrganismLgp},
+    };
+
+    use super::{IslandModel, IslandParameters};
+
+    fn hyper_params() -> HyperParameters<TestOrganism> {
+        HyperParameters {
+            population_size: 5,
+            gap: 0.5,
+            n_mutations: 0.5,
+            n_crossovers: 0.5,
+            adaptive_mutation: None,
+            fitness_weighted_fill: false,
+            n_random_immigrants: 0,
+            dedup_population: false,
+            multi_objective: false,
+            replacement: ReplacementStrategy::Generational,
+            crossover: CrossoverMethod::TwoPoint,
+            parent_selection: ParentSelection::Uniform,
+            surrogate_cutoff: None,
+            memory_budget: None,
+            snapshot_path: None,
+            time_budget: None,
+            max_generations: 1,
+            fitness_parameters: (),
+            program_parameters: (),
+        }
+    }
+
+    #[test]
+    fn given_known_best_program_when_migrate_then_it_is_copied_to_the_next_island() {
+        let mut hyper_params = hyper_params();
+        let mut model =
+            IslandModel::<TestOrganismLgp>::new(&hyper_params, IslandParameters::new(3, 1, 1));
+
+        let champion = TestOrganism::new(100., 100.);
+        let mut first_island = Population::with_capacity(5);
+        first_island.push(champion);
+        for _ in 0..4 {
+            first_island.push(TestOrganism::new(0., 0.));
+        }
+        model.islands[0] = first_island;
+
+        for island in model.islands.iter_mut() {
+            TestOrganismLgp::rank(island, &mut hyper_params.fitness_parameters);
+        }
+        model.migrate();
+
+        let has_champion = |island: &Population<TestOrganism>| {
+            island
+                .iter()
+                .any(|individual| individual.fitness == Some(100.))
+        };
+
+        assert!(has_champion(&model.islands[0]));
+        assert!(has_champion(&model.islands[1]));
+        assert!(!has_champion(&model.islands[2]));
+        assert!(model.islands.iter().all(|island| island.len() == 5));
+
+        // A second migration carries it around the ring.
+        model.migrate();
+        assert!(has_champion(&model.islands[2]));
+    }
+
+    #[test]
+    fn given_island_model_when_run_then_every_island_keeps_its_size() {
+        let mut hyper_params = hyper_params();
+        hyper_params.max_generations = 4;
+
+        let mut model =
+            IslandModel::<TestOrganismLgp>::new(&hyper_params, IslandParameters::new(2, 2, 1));
+        model.run(&mut hyper_params, &mut RunState::default());
+
+        pretty_assertions::assert_eq!(model.islands.len(), 2);
+        assert!(model.islands.iter().all(|island| island.len() == 5));
+        assert!(model.best().is_some());
+    }
+
+    #[test]
+    fn given_hill_climb_replacement_when_step_then_islands_are_hill_climbed() {
+        let mut hyper_params = HyperParameters {
+            replacement: ReplacementStrategy::HillClimb { epsilon: 0. },
+            ..hyper_params()
+        };
+
+        let mut model =
+            IslandModel::<TestOrganismLgp>::new(&hyper_params, IslandParameters::new(2, 1, 1));
+        model.step(&mut hyper_params, &mut RunState::default(), 0);
+
+        // Hill climbing evaluates every child it keeps, where breeding leaves them unevaluated.
+        assert!(model
+            .islands
+            .iter()
+            .flat_map(|island| island.iter())
+            .all(|individual| individual.fitness.is_some()));
+    }
+}
@@ -1,8 +1,14 @@
 pub mod algorithm;
+pub mod archive;
 pub mod characteristics;
+pub mod fitness_cache;
+pub mod genealogy;
 pub mod inputs;
 pub mod instruction;
 pub mod instructions;
+pub mod int_registers;
+pub mod island;
+pub mod pareto;
 pub mod population;
 pub mod program;
 pub mod registers;
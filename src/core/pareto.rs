@@ -0,0 +1,150 @@
+/// Whether `a` is at least as good as `b` in every objective and strictly better in one. All
+/// objectives are maximized, so `Fitness::objectives` maps scores through
+/// `FitnessDirection::gain`.
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a >= b) && a.iter().zip(b).any(|(a, b)| a > b)
+}
+
+/// Splits the individuals (by index) into successive non-dominated fronts, best first.
+/// Unevaluated individuals (`None`) neither dominate nor are dominated, so they form a final
+/// front of their own.
+pub fn non_dominated_sort(objectives: &[Option<Vec<f32>>]) -> Vec<Vec<usize>> {
+    let n_individuals = objectives.len();
+    let mut dominated_by = vec![vec![]; n_individuals];
+    let mut n_dominating = vec![0usize; n_individuals];
+
+    let evaluated = (0..n_individuals)
+        .filter_map(|index| objectives[index].as_deref().map(|o| (index, o)))
+        .collect::<Vec<_>>();
+    let unevaluated = (0..n_individuals)
+        .filter(|index| objectives[*index].is_none())
+        .collect::<Vec<_>>();
+
+    for (a, objectives_a) in evaluated.iter() {
+        for (b, objectives_b) in evaluated.iter() {
+            if dominates(objectives_a, objectives_b) {
+                dominated_by[*a].push(*b);
+            } else if dominates(objectives_b, objectives_a) {
+                n_dominating[*a] += 1;
+            }
+        }
+    }
+
+    let mut fronts = vec![];
+    let mut front: Vec<usize> = evaluated
+        .iter()
+        .map(|(index, _)| *index)
+        .filter(|index| n_dominating[*index] == 0)
+        .collect();
+
+    while !front.is_empty() {
+        let mut next_front = vec![];
+
+        for index in front.iter() {
+            for dominated in dominated_by[*index].iter() {
+                n_dominating[*dominated] -= 1;
+
+                if n_dominating[*dominated] == 0 {
+                    next_front.push(*dominated);
+                }
+            }
+        }
+
+        fronts.push(front);
+        front = next_front;
+    }
+
+    if !unevaluated.is_empty() {
+        fronts.push(unevaluated);
+    }
+
+    fronts
+}
+
+/// Crowding distance of each member of `front`; boundary members are infinitely far.
+/// Unevaluated members have no neighbours to measure against and are given 0. An objective
+/// spanning a non-finite range (e.g. an infinite error) only marks its boundaries.
+pub fn crowding_distances(objectives: &[Option<Vec<f32>>], front: &[usize]) -> Vec<f32> {
+    let mut distances = vec![0f32; front.len()];
+    let evaluated = front
+        .iter()
+        .enumerate()
+        .filter_map(|(position, index)| objectives[*index].as_deref().map(|o| (position, o)))
+        .collect::<Vec<_>>();
+    let n_objectives = evaluated.first().map_or(0, |(_, o)| o.len());
+
+    for objective in 0..n_objectives {
+        let mut order = evaluated.clone();
+        order.sort_by(|(_, a), (_, b)| a[objective].total_cmp(&b[objective]));
+
+        let value = |rank: usize| order[rank].1[objective];
+        let (first, last) = (0, order.len() - 1);
+        let range = value(last) - value(first);
+
+        distances[order[first].0] = f32::INFINITY;
+        distances[order[last].0] = f32::INFINITY;
+
+        if range == 0. || !range.is_finite() {
+            continue;
+        }
+
+        for rank in 1..last {
+            distances[order[rank].0] += (value(rank + 1) - value(rank - 1)) / range;
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_toy_objectives_when_non_dominated_sort_then_fronts_are_ordered() {
+        let objectives = vec![
+            Some(vec![0.9, -10.]),
+            Some(vec![0.8, -5.]),
+            Some(vec![0.7, -2.]),
+            Some(vec![0.6, -8.]),
+            Some(vec![0.5, -20.]),
+        ];
+
+        let fronts = non_dominated_sort(&objectives);
+
+        pretty_assertions::assert_eq!(fronts, vec![vec![0, 1, 2], vec![3], vec![4]]);
+
+        let distances = crowding_distances(&objectives, &fronts[0]);
+        assert!(distances[0].is_infinite() && distances[2].is_infinite());
+        assert!(distances[1].is_finite());
+    }
+
+    #[test]
+    fn given_unevaluated_individuals_when_non_dominated_sort_then_they_form_the_last_front() {
+        let objectives = vec![None, Some(vec![0.5, -2.]), None, Some(vec![0.9, -1.])];
+
+        let fronts = non_dominated_sort(&objectives);
+
+        pretty_assertions::assert_eq!(fronts, vec![vec![3], vec![1], vec![0, 2]]);
+        pretty_assertions::assert_eq!(crowding_distances(&objectives, &fronts[2]), vec![0., 0.]);
+        pretty_assertions::assert_eq!(
+            crowding_distances(&objectives, &[0, 1, 3]),
+            vec![0., f32::INFINITY, f32::INFINITY]
+        );
+    }
+
+    #[test]
+    fn given_infinite_objective_when_crowding_distances_then_no_distance_is_nan() {
+        let objectives = vec![
+            Some(vec![f32::NEG_INFINITY, -2.]),
+            Some(vec![0.5, -5.]),
+            Some(vec![0.9, -10.]),
+        ];
+
+        // Only the finite second objective spaces out the interior member.
+        pretty_assertions::assert_eq!(
+            crowding_distances(&objectives, &[0, 1, 2]),
+            vec![f32::INFINITY, 1., f32::INFINITY]
+        );
+    }
+}
@@ -1,8 +1,41 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
 use std::slice::{Iter, IterMut};
 use std::vec::IntoIter;
 
+use rand::Rng;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{extensions::core::ExtensionParameters, utils::random::generator};
+
+use super::{
+    characteristics::{Fitness, FitnessScore, Generate},
+    instruction::Instruction,
+    pareto::non_dominated_sort,
+    program::Program,
+};
+
 pub type InnerPopulation<T> = Vec<T>;
-#[derive(Clone, Debug)]
+
+/// Number of bins used by `Population::fitness_entropy`.
+pub const FITNESS_ENTROPY_BINS: usize = 10;
+
+/// Populations larger than this have their structural diversity estimated from a sample of
+/// pairs rather than every pair.
+pub const STRUCTURAL_DIVERSITY_MAX_EXACT: usize = 64;
+
+/// The best, median and worst of some per-individual measure across a population.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ComplexityBenchmark<T> {
+    pub best: T,
+    pub median: T,
+    pub worst: T,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Population<T>
 where
     T: PartialEq + PartialOrd + Clone,
@@ -39,16 +72,12 @@ where
         self.list.get_mut(index)
     }
 
-    pub fn sort(&mut self) -> () {
-        self.list.sort_by(|a, b| b.partial_cmp(a).unwrap());
-    }
-
     pub fn first(&self) -> Option<&T> {
         self.list.first()
     }
 
     pub fn middle(&self) -> Option<&T> {
-        let middle_index = (((self.list.len() - 1) as f64) / 2.).floor() as usize;
+        let middle_index = self.list.len().checked_sub(1)? / 2;
         self.list.get(middle_index)
     }
 
@@ -85,6 +114,215 @@ where
     }
 }
 
+impl<T> Population<T>
+where
+    T: PartialOrd + Clone + Serialize + DeserializeOwned,
+{
+    /// Writes the population (every individual and its fitness) to `path` as JSON. Programs do
+    /// not hold their inputs, so a loaded population is ready to evaluate against any dataset.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+impl<T> Population<T>
+where
+    T: Generate + PartialOrd + Clone,
+{
+    /// Appends `n` individuals generated from `parameters`. Calling this with different
+    /// parameters seeds a heterogeneous population, e.g. part short programs and part long.
+    pub fn extend_from_generate(&mut self, n: usize, parameters: &T::GeneratorParameters) {
+        self.extend((0..n).map(|_| T::generate(parameters)));
+    }
+}
+
+impl<T> Population<T>
+where
+    T: Fitness + PartialOrd + Clone,
+{
+    /// Orders the population from best to worst per `Fitness::DIRECTION`, with unevaluated
+    /// individuals last.
+    pub fn sort(&mut self) -> () {
+        self.list
+            .sort_by(|a, b| T::DIRECTION.compare(b.get_fitness(), a.get_fitness()));
+    }
+
+    /// Yields the individuals from worst to best per `Fitness::DIRECTION` (unevaluated ones
+    /// first) without reordering the population.
+    pub fn iter_sorted_by_fitness(&self) -> impl Iterator<Item = &T> {
+        let mut indices = (0..self.list.len()).collect::<Vec<_>>();
+        indices.sort_by(|&a, &b| {
+            T::DIRECTION.compare(self.list[a].get_fitness(), self.list[b].get_fitness())
+        });
+        indices.into_iter().map(move |index| &self.list[index])
+    }
+
+    fn fitness_scores(&self) -> Vec<f32> {
+        self.iter()
+            .filter_map(|individual| individual.get_fitness())
+            .collect()
+    }
+
+    pub fn mean_fitness(&self) -> f32 {
+        let scores = self.fitness_scores();
+        scores.iter().sum::<f32>() / scores.len() as f32
+    }
+
+    pub fn fitness_std_dev(&self) -> f32 {
+        let scores = self.fitness_scores();
+        let mean = self.mean_fitness();
+        let variance = scores
+            .iter()
+            .map(|score| (score - mean).powi(2))
+            .sum::<f32>()
+            / scores.len() as f32;
+        variance.sqrt()
+    }
+
+    /// Shannon entropy (in bits) of the evaluated fitness scores, binned into
+    /// `FITNESS_ENTROPY_BINS` equal-width bins between the lowest and highest score. 0 when every
+    /// score falls in one bin; a spread over every bin approaches `log2(FITNESS_ENTROPY_BINS)`.
+    pub fn fitness_entropy(&self) -> f32 {
+        let scores = self.fitness_scores();
+        let min = scores.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+        if scores.is_empty() || max <= min {
+            return 0.;
+        }
+
+        let mut counts = [0usize; FITNESS_ENTROPY_BINS];
+        for score in scores.iter() {
+            let bin = ((score - min) / (max - min) * FITNESS_ENTROPY_BINS as f32) as usize;
+            counts[bin.min(FITNESS_ENTROPY_BINS - 1)] += 1;
+        }
+
+        counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let probability = count as f32 / scores.len() as f32;
+                -probability * probability.log2()
+            })
+            .sum()
+    }
+
+    /// Best, median and worst fitness among the evaluated individuals, regardless of whether the
+    /// population is sorted. Every entry is `None` when nothing has been evaluated.
+    pub fn get_benchmark_individuals(&self) -> ComplexityBenchmark<Option<FitnessScore>> {
+        let mut scores = self.fitness_scores();
+        scores.sort_by(|a, b| T::DIRECTION.compare(Some(*b), Some(*a)));
+
+        let median_index = scores.len().saturating_sub(1) / 2;
+
+        ComplexityBenchmark {
+            best: scores.first().copied(),
+            median: scores.get(median_index).copied(),
+            worst: scores.last().copied(),
+        }
+    }
+
+    /// The evaluated individuals that no other individual dominates on `Fitness::objectives`.
+    pub fn pareto_front(&self) -> Vec<&T> {
+        let objectives = self
+            .iter()
+            .map(|individual| individual.objectives())
+            .collect::<Vec<_>>();
+
+        non_dominated_sort(&objectives)
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|index| objectives[*index].is_some())
+            .filter_map(|index| self.get(index))
+            .collect()
+    }
+
+    /// Standardized difference between the mean fitness of the `selected` individuals and the
+    /// mean fitness of this population, positive when selection favours better individuals per
+    /// `Fitness::DIRECTION`. Returns 0 when the population has no fitness variance.
+    pub fn selection_intensity(&self, selected: &Population<T>) -> f32 {
+        let std_dev = self.fitness_std_dev();
+
+        if std_dev == 0. || std_dev.is_nan() {
+            return 0.;
+        }
+
+        T::DIRECTION.gain(selected.mean_fitness() - self.mean_fitness()) / std_dev
+    }
+}
+
+impl<T> Population<Program<T>>
+where
+    T: ExtensionParameters,
+{
+    /// Number of programs per instruction count, in increasing length.
+    pub fn length_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+
+        for program in self.iter() {
+            *histogram.entry(program.instructions.len()).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    /// Mean Jaccard distance between the instruction sets of pairs of programs: 0 when every
+    /// program uses the same instructions, 1 when no two programs share one. Above
+    /// `STRUCTURAL_DIVERSITY_MAX_EXACT` programs, `len()` random pairs are averaged instead of
+    /// every pair.
+    pub fn structural_diversity(&self) -> f32 {
+        let n = self.len();
+        if n < 2 {
+            return 0.;
+        }
+
+        let instruction_sets = self
+            .iter()
+            .map(|program| {
+                program
+                    .instructions
+                    .iter()
+                    .collect::<HashSet<&Instruction>>()
+            })
+            .collect::<Vec<_>>();
+
+        let distance = |a: usize, b: usize| {
+            let [set_a, set_b] = [&instruction_sets[a], &instruction_sets[b]];
+            let n_union = set_a.union(set_b).count();
+
+            match n_union {
+                0 => 0.,
+                _ => 1. - set_a.intersection(set_b).count() as f32 / n_union as f32,
+            }
+        };
+
+        let pairs: Vec<(usize, usize)> = if n <= STRUCTURAL_DIVERSITY_MAX_EXACT {
+            (0..n)
+                .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+                .collect()
+        } else {
+            (0..n)
+                .map(|_| {
+                    let a = generator().gen_range(0..n);
+                    let b = (a + generator().gen_range(1..n)) % n;
+                    (a, b)
+                })
+                .collect()
+        };
+
+        pairs.iter().map(|&(a, b)| distance(a, b)).sum::<f32>() / pairs.len() as f32
+    }
+}
+
 impl<T> IntoIterator for Population<T>
 where
     T: PartialOrd + Clone,
@@ -110,3 +348,247 @@ where
         population
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            characteristics::{Fitness, FitnessDirection, FitnessScore, Generate},
+            instruction::InstructionGeneratorParameters,
+            program::{Program, ProgramGeneratorParameters},
+        },
+        extensions::classification::ClassificationParameters,
+        utils::test::TestInput,
+    };
+
+    use super::{ComplexityBenchmark, Population, FITNESS_ENTROPY_BINS};
+
+    fn population_with_fitness(
+        scores: &[f32],
+    ) -> Population<Program<ClassificationParameters<TestInput>>> {
+        let params = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+
+        scores
+            .iter()
+            .map(|score| {
+                let mut program = Program::generate(&params);
+                program.fitness = Some(*score);
+                program
+            })
+            .collect()
+    }
+
+    #[test]
+    fn given_population_and_selected_subset_when_selection_intensity_then_standardized_difference_is_returned(
+    ) {
+        let population = population_with_fitness(&[4., 3., 2., 1.]);
+        let selected = population_with_fitness(&[4., 3.]);
+
+        let expected = (3.5 - 2.5) / 1.25f32.sqrt();
+
+        assert!((population.selection_intensity(&selected) - expected).abs() < 1e-6);
+        assert!((population.selection_intensity(&population) - 0.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn given_population_without_variance_when_selection_intensity_then_zero_is_returned() {
+        let population = population_with_fitness(&[0.5, 0.5, 0.5]);
+        let selected = population_with_fitness(&[0.5]);
+
+        pretty_assertions::assert_eq!(population.selection_intensity(&selected), 0.);
+    }
+
+    #[test]
+    fn given_population_when_saved_and_loaded_then_programs_and_fitness_round_trip(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let population = population_with_fitness(&[0.9, 0.5, 0.1]);
+        let checkpoint = tempfile::NamedTempFile::new()?;
+
+        population.save(checkpoint.path())?;
+        let loaded: Population<Program<ClassificationParameters<TestInput>>> =
+            Population::load(checkpoint.path())?;
+
+        pretty_assertions::assert_eq!(loaded.len(), population.len());
+        pretty_assertions::assert_eq!(loaded.capacity(), population.capacity());
+
+        for (loaded, original) in loaded.iter().zip(population.iter()) {
+            pretty_assertions::assert_eq!(loaded, original);
+            pretty_assertions::assert_eq!(loaded.registers, original.registers);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_small_populations_when_get_benchmark_individuals_then_no_panic_occurs() {
+        let benchmark =
+            |scores: &[f32]| population_with_fitness(scores).get_benchmark_individuals();
+
+        pretty_assertions::assert_eq!(
+            benchmark(&[]),
+            ComplexityBenchmark {
+                best: None,
+                median: None,
+                worst: None
+            }
+        );
+        pretty_assertions::assert_eq!(
+            benchmark(&[0.4]),
+            ComplexityBenchmark {
+                best: Some(0.4),
+                median: Some(0.4),
+                worst: Some(0.4)
+            }
+        );
+        pretty_assertions::assert_eq!(
+            benchmark(&[0.2, 0.6]),
+            ComplexityBenchmark {
+                best: Some(0.6),
+                median: Some(0.6),
+                worst: Some(0.2)
+            }
+        );
+        pretty_assertions::assert_eq!(
+            benchmark(&[0.5, 0.1, 0.9]),
+            ComplexityBenchmark {
+                best: Some(0.9),
+                median: Some(0.5),
+                worst: Some(0.1)
+            }
+        );
+    }
+
+    #[test]
+    fn given_unsorted_population_when_iter_sorted_by_fitness_then_order_is_ascending_and_population_is_untouched(
+    ) {
+        let population = population_with_fitness(&[0.5, 0.1, 0.9, 0.3]);
+
+        let sorted = population
+            .iter_sorted_by_fitness()
+            .map(|program| program.fitness)
+            .collect::<Vec<_>>();
+        let original = population
+            .iter()
+            .map(|program| program.fitness)
+            .collect::<Vec<_>>();
+
+        pretty_assertions::assert_eq!(sorted, vec![Some(0.1), Some(0.3), Some(0.5), Some(0.9)]);
+        pretty_assertions::assert_eq!(original, vec![Some(0.5), Some(0.1), Some(0.9), Some(0.3)]);
+    }
+
+    #[test]
+    fn given_two_generators_when_extend_from_generate_then_population_mixes_both() {
+        let instruction_parameters = InstructionGeneratorParameters::from::<TestInput>(1);
+        let short = ProgramGeneratorParameters::new(5, instruction_parameters.clone());
+        let long = ProgramGeneratorParameters {
+            min_instructions: 20,
+            ..ProgramGeneratorParameters::new(30, instruction_parameters)
+        };
+
+        let mut population: Population<Program<ClassificationParameters<TestInput>>> =
+            Population::with_capacity(10);
+        population.extend_from_generate(4, &short);
+        population.extend_from_generate(6, &long);
+
+        let lengths = population
+            .iter()
+            .map(|program| program.instructions.len())
+            .collect::<Vec<_>>();
+
+        pretty_assertions::assert_eq!(population.len(), 10);
+        assert!(lengths[..4].iter().all(|length| (1..=5).contains(length)));
+        assert!(lengths[4..].iter().all(|length| (20..=30).contains(length)));
+    }
+
+    /// Scored by an error, so lower is better.
+    #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    struct ErrorScored(Option<FitnessScore>);
+
+    impl Fitness for ErrorScored {
+        type FitnessParameters = ();
+
+        const DIRECTION: FitnessDirection = FitnessDirection::Minimize;
+
+        fn eval_fitness(&mut self, _parameters: &mut ()) -> FitnessScore {
+            self.0.unwrap()
+        }
+
+        fn get_fitness(&self) -> Option<FitnessScore> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn given_minimized_fitness_when_sorted_then_smallest_fitness_is_best() {
+        let mut population: Population<ErrorScored> = [Some(0.4), None, Some(0.1), Some(0.9)]
+            .into_iter()
+            .map(ErrorScored)
+            .collect();
+
+        population.sort();
+
+        let order = population.iter().map(|i| i.0).collect::<Vec<_>>();
+        pretty_assertions::assert_eq!(order, vec![Some(0.1), Some(0.4), Some(0.9), None]);
+        pretty_assertions::assert_eq!(
+            population
+                .iter_sorted_by_fitness()
+                .map(|i| i.0)
+                .collect::<Vec<_>>(),
+            vec![None, Some(0.9), Some(0.4), Some(0.1)]
+        );
+        pretty_assertions::assert_eq!(
+            population.get_benchmark_individuals(),
+            ComplexityBenchmark {
+                best: Some(0.1),
+                median: Some(0.4),
+                worst: Some(0.9)
+            }
+        );
+    }
+
+    #[test]
+    fn given_shared_or_spread_fitness_when_fitness_entropy_then_entropy_is_zero_or_maximal() {
+        let shared = population_with_fitness(&[0.5; 20]);
+        let spread =
+            population_with_fitness(&(0..20).map(|index| index as f32 / 19.).collect::<Vec<_>>());
+
+        pretty_assertions::assert_eq!(shared.fitness_entropy(), 0.);
+        assert!((spread.fitness_entropy() - (FITNESS_ENTROPY_BINS as f32).log2()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn given_identical_or_distinct_programs_when_structural_diversity_then_zero_or_positive() {
+        let program = population_with_fitness(&[0.5]).into_iter().next().unwrap();
+        let identical: Population<_> = std::iter::repeat(program).take(100).collect();
+        let distinct = population_with_fitness(&[0.5; 100]);
+
+        pretty_assertions::assert_eq!(identical.structural_diversity(), 0.);
+        assert!(distinct.structural_diversity() > 0.5);
+    }
+
+    #[test]
+    fn given_programs_of_known_lengths_when_length_histogram_then_lengths_are_counted() {
+        let instruction_parameters = InstructionGeneratorParameters::from::<TestInput>(1);
+        let with_length = |length: usize| ProgramGeneratorParameters {
+            min_instructions: length,
+            ..ProgramGeneratorParameters::new(length, instruction_parameters.clone())
+        };
+
+        let mut population: Population<Program<ClassificationParameters<TestInput>>> =
+            Population::with_capacity(6);
+        population.extend_from_generate(3, &with_length(2));
+        population.extend_from_generate(1, &with_length(5));
+        population.extend_from_generate(2, &with_length(7));
+
+        pretty_assertions::assert_eq!(
+            population
+                .length_histogram()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![(2, 3), (5, 1), (7, 2)]
+        );
+    }
+}
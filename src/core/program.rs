@@ -1,25 +1,123 @@
-use std::{fmt::Display, marker::PhantomData};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap, HashSet},
+    error::Error,
+    fmt::{self, Display},
+    fs::File,
+    hash::{Hash, Hasher},
+    io::{BufReader, BufWriter},
+    marker::PhantomData,
+    mem,
+    path::Path,
+};
 
-use crate::{extensions::core::ExtensionParameters, utils::random::generator};
+use crate::{
+    extensions::core::ExtensionParameters,
+    utils::{
+        executables::{display_executable, executable_name},
+        linked_list::Node,
+    },
+};
 use derivative::Derivative;
 use derive_new::new;
 use rand::{
     distributions::Uniform,
     prelude::{Distribution, IteratorRandom},
+    Rng,
 };
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
 use super::{
-    characteristics::{Breed, FitnessScore, Generate, Mutate},
+    characteristics::{Breed, FitnessScore, Generate, Length, Lineage, Mutate},
     inputs::ValidInput,
-    instruction::{Instruction, InstructionGeneratorParameters},
+    instruction::{feature_label, Instruction, InstructionGeneratorParameters, Mode},
     instructions::Instructions,
-    registers::Registers,
+    registers::{Registers, R32},
 };
 #[derive(Clone, Debug, Serialize, new)]
 pub struct ProgramGeneratorParameters {
     pub max_instructions: usize,
+    /// Generated programs have at least this many instructions (1 by default).
+    #[new(value = "1")]
+    pub min_instructions: usize,
     pub instruction_generator_parameters: InstructionGeneratorParameters,
+    /// When set, initial register values are evolved within `[-bound, bound]`.
+    #[new(default)]
+    pub initial_register_bound: Option<R32>,
+    /// Probability that a mutation inserts a freshly generated instruction (up to
+    /// `max_instructions`).
+    #[new(default)]
+    pub insertion_rate: f64,
+    /// Probability that a mutation deletes an instruction (down to `min_instructions`).
+    #[new(default)]
+    pub deletion_rate: f64,
+    /// When set, register values are clamped to `(min, max)` after every instruction so
+    /// repeated multiplications can't dominate the outputs.
+    #[new(default)]
+    pub register_clamp: Option<(R32, R32)>,
+}
+
+/// Program generator parameters rejected by `ProgramGeneratorParameters::validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgramParameterError {
+    /// The clamp's bounds are out of order or `NaN`.
+    InvalidRegisterClamp(R32, R32),
+    /// `min_instructions` is 0, letting `Program::generate` produce empty programs.
+    NoMinInstructions,
+    /// `min_instructions` exceeds `max_instructions`.
+    InstructionBoundsOutOfOrder { min: usize, max: usize },
+    /// No executable is available to generate instructions from.
+    NoExecutables,
+}
+
+impl fmt::Display for ProgramParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgramParameterError::InvalidRegisterClamp(min, max) => write!(
+                f,
+                "The register clamp bounds must be ordered and not NaN, got ({}, {}).",
+                min, max
+            ),
+            ProgramParameterError::NoMinInstructions => {
+                write!(f, "The minimum number of instructions must be at least 1.")
+            }
+            ProgramParameterError::InstructionBoundsOutOfOrder { min, max } => write!(
+                f,
+                "The minimum number of instructions must be at most the maximum, got {} > {}.",
+                min, max
+            ),
+            ProgramParameterError::NoExecutables => {
+                write!(f, "At least one executable must be available.")
+            }
+        }
+    }
+}
+
+impl Error for ProgramParameterError {}
+
+impl ProgramGeneratorParameters {
+    /// Rejects parameters `Program::generate` would panic on.
+    pub fn validate(&self) -> Result<(), ProgramParameterError> {
+        match self.register_clamp {
+            Some((min, max)) if min.is_nan() || max.is_nan() || min > max => {
+                Err(ProgramParameterError::InvalidRegisterClamp(min, max))
+            }
+            _ if self.min_instructions == 0 => Err(ProgramParameterError::NoMinInstructions),
+            _ if self.min_instructions > self.max_instructions => {
+                Err(ProgramParameterError::InstructionBoundsOutOfOrder {
+                    min: self.min_instructions,
+                    max: self.max_instructions,
+                })
+            }
+            _ if self
+                .instruction_generator_parameters
+                .available_executables
+                .is_empty() =>
+            {
+                Err(ProgramParameterError::NoExecutables)
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 impl<T> Clone for Program<T>
@@ -31,13 +129,17 @@ where
             instructions: self.instructions.clone(),
             registers: self.registers.clone(),
             fitness: self.fitness.clone(),
+            fitness_variance: self.fitness_variance,
+            id: self.id,
+            parent_ids: self.parent_ids.clone(),
             marker: self.marker.clone(),
         }
     }
 }
 
-#[derive(Debug, Serialize, new, Derivative)]
+#[derive(Debug, Serialize, Deserialize, Derivative)]
 #[derivative(PartialEq, Eq, PartialOrd, Ord)]
+#[serde(bound = "")]
 pub struct Program<T>
 where
     T: ExtensionParameters,
@@ -45,34 +147,394 @@ where
     #[derivative(Ord = "ignore", PartialOrd = "ignore")]
     pub instructions: Instructions,
     #[derivative(Ord = "ignore", PartialOrd = "ignore", PartialEq = "ignore")]
+    #[serde(deserialize_with = "deserialize_registers::<T, _>")]
     pub registers: Registers,
     #[derivative(Ord = "ignore")]
     pub fitness: Option<FitnessScore>,
+    /// Spread of the scores `fitness` was aggregated from, when the evaluation reports one.
+    #[serde(default)]
+    #[derivative(PartialEq = "ignore", Ord = "ignore", PartialOrd = "ignore")]
+    pub fitness_variance: Option<FitnessScore>,
+    /// Assigned by a `Genealogy` when lineage is tracked.
+    #[serde(default)]
+    #[derivative(PartialEq = "ignore", Ord = "ignore", PartialOrd = "ignore")]
+    pub id: Option<u64>,
+    #[serde(default)]
+    #[derivative(PartialEq = "ignore", Ord = "ignore", PartialOrd = "ignore")]
+    pub parent_ids: Vec<u64>,
     #[derivative(PartialEq = "ignore", Ord = "ignore", PartialOrd = "ignore")]
     marker: PhantomData<T>,
 }
 
+/// Gives registers without an output layout (e.g. saved before registers stored one) the
+/// `N_OUTPUT_REGISTERS` outputs of `T`.
+fn with_output_layout<T>(registers: Registers) -> Registers
+where
+    T: ExtensionParameters,
+{
+    match registers.n_outputs() {
+        Some(_) => registers,
+        None => registers.with_n_outputs(Some(T::N_OUTPUT_REGISTERS)),
+    }
+}
+
+fn deserialize_registers<'de, T, D>(deserializer: D) -> Result<Registers, D::Error>
+where
+    T: ExtensionParameters,
+    D: Deserializer<'de>,
+{
+    Registers::deserialize(deserializer).map(with_output_layout::<T>)
+}
+
 impl<T> Program<T>
 where
     T: ExtensionParameters,
 {
+    /// A program running `instructions` over `registers`. Registers without an output layout
+    /// get the `N_OUTPUT_REGISTERS` outputs of `T`.
+    pub fn new(
+        instructions: Instructions,
+        registers: Registers,
+        fitness: Option<FitnessScore>,
+    ) -> Self {
+        Program {
+            instructions,
+            registers: with_output_layout::<T>(registers),
+            fitness,
+            fitness_variance: None,
+            id: None,
+            parent_ids: vec![],
+            marker: PhantomData,
+        }
+    }
+
+    /// Runs the instructions in order. A branch whose condition is false skips the next
+    /// instruction, and a skipped branch also skips the one after it.
     pub fn exec<I>(&mut self, input: &I)
     where
         I: ValidInput,
     {
+        self.exec_observed(input, |_| ());
+    }
+
+    /// Runs a copy of the program on `input`, returning the registers after each instruction
+    /// (skipped instructions leave them unchanged). The program itself is not mutated.
+    pub fn exec_trace<I>(&self, input: &I) -> Vec<Registers>
+    where
+        I: ValidInput,
+    {
+        let mut program = self.clone();
+        let mut trace = Vec::with_capacity(self.instructions.len());
+
+        program.exec_observed(input, |registers| trace.push(registers.clone()));
+
+        trace
+    }
+
+    /// `exec`, calling `observe` with the registers after every instruction.
+    fn exec_observed<I, F>(&mut self, input: &I, mut observe: F)
+    where
+        I: ValidInput,
+        F: FnMut(&Registers),
+    {
+        let mut skip = false;
+
         for instruction in &self.instructions {
-            instruction.apply(&mut &mut self.registers, input)
+            let is_branch = *instruction.mode() == Mode::Branch;
+
+            if skip {
+                skip = is_branch;
+            } else if is_branch {
+                skip = !instruction.condition_holds(&self.registers);
+            } else {
+                instruction.apply(&mut &mut self.registers, input);
+                self.registers.apply_clamp();
+            }
+
+            observe(&self.registers);
         }
     }
+
+    /// Writes the instructions, registers and fitness to `path` as JSON. The dataset is not
+    /// part of a program, so a loaded program can be run on any inputs.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Number of working registers, outputs and scratch registers included.
+    pub fn n_registers(&self) -> usize {
+        self.registers.len()
+    }
+
+    /// Number of input features, which are read from the input rather than stored in
+    /// `registers`.
+    pub fn n_input_registers(&self) -> usize {
+        T::N_INPUT_REGISTERS
+    }
+
+    /// Number of leading registers read as the output.
+    pub fn n_output_registers(&self) -> usize {
+        T::N_OUTPUT_REGISTERS
+    }
+
+    /// Hashes the instructions and initial register values, which fully determine behavior.
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        for instruction in self.instructions.iter() {
+            instruction.hash(&mut hasher);
+        }
+
+        for value in self.registers.initial() {
+            value.to_bits().hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    /// Softmax-normalized output register values after running the program on `input`. Equal
+    /// outputs yield a uniform distribution.
+    pub fn confidences<I>(&self, input: &I) -> Vec<f32>
+    where
+        I: ValidInput,
+    {
+        let mut program = self.clone();
+        program.exec(input);
+        program.registers.softmax(T::N_OUTPUT_REGISTERS)
+    }
+
+    /// The instructions with their indices reduced the way they are when executed (see
+    /// `Instruction::wrapped`).
+    fn wrapped_instructions(&self) -> impl Iterator<Item = Instruction> + '_ {
+        self.instructions
+            .iter()
+            .map(|instruction| instruction.wrapped(self.registers.len(), T::N_INPUT_REGISTERS))
+    }
+
+    /// Renders the program one instruction per line, e.g. `R[2] = R[2] + I[0]`.
+    pub fn to_assembly(&self) -> String {
+        let feature_names = T::feature_names();
+
+        self.wrapped_instructions()
+            .map(|instruction| instruction.to_named_assembly(feature_names.as_deref()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Renders the effective instructions as if-then rules over the input features, to be read
+    /// in order. Unconditional instructions are folded into the expressions they feed; each
+    /// branch-guarded instruction becomes an `if ... then ...` rule, after which its register is
+    /// referred to by name. The final rules give the output registers the class is chosen from.
+    pub fn to_rules(&self) -> Vec<String> {
+        let parenthesize = |expression: &str| match expression.contains(' ') {
+            true => format!("({})", expression),
+            false => expression.to_string(),
+        };
+
+        let mut expressions = self
+            .registers
+            .initial()
+            .iter()
+            .map(|value| format!("{:?}", value))
+            .collect::<Vec<_>>();
+        let mut conditions = vec![];
+        let mut rules = vec![];
+        let feature_names = T::feature_names();
+
+        for instruction in self.simplify().instructions.iter() {
+            let source = parenthesize(&expressions[instruction.source_index()]);
+            let target = match instruction.mode() {
+                Mode::Internal | Mode::Branch => {
+                    parenthesize(&expressions[instruction.target_index()])
+                }
+                Mode::External => {
+                    feature_label(instruction.target_index(), feature_names.as_deref())
+                }
+            };
+
+            if *instruction.mode() == Mode::Branch {
+                conditions.push(format!("{} < {}", source, target));
+                continue;
+            }
+
+            let expression = match source.as_str() {
+                "0.0" if executable_name(instruction.executable()) == Some("add") => target,
+                _ => display_executable(instruction.executable(), &source, &target),
+            };
+
+            let register_name = format!("R[{}]", instruction.source_index());
+
+            if conditions.is_empty() {
+                expressions[instruction.source_index()] = expression;
+            } else {
+                // The value kept when the conditions fail.
+                if expressions[instruction.source_index()] != register_name {
+                    rules.push(format!(
+                        "{} = {}",
+                        register_name,
+                        expressions[instruction.source_index()]
+                    ));
+                }
+
+                rules.push(format!(
+                    "if {} then {} = {}",
+                    conditions.join(" and "),
+                    register_name,
+                    expression
+                ));
+                conditions.clear();
+                expressions[instruction.source_index()] = register_name;
+            }
+        }
+
+        for (register, expression) in expressions.iter().enumerate().take(T::N_OUTPUT_REGISTERS) {
+            let register_name = format!("R[{}]", register);
+
+            if *expression != register_name {
+                rules.push(format!("{} = {}", register_name, expression));
+            }
+        }
+
+        rules
+    }
+
+    /// Renders the register dataflow as a Graphviz DOT graph. Instructions are nodes, with an
+    /// edge from the last instruction writing a register to every later instruction reading it.
+    /// Inputs are drawn as boxes and output registers as double circles.
+    pub fn to_dot(&self) -> String {
+        let mut inputs = BTreeSet::new();
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        let mut last_writers: HashMap<usize, usize> = HashMap::new();
+        let feature_names = T::feature_names();
+
+        for (index, instruction) in self.wrapped_instructions().enumerate() {
+            nodes.push(format!(
+                "    i{} [label=\"{}\"];",
+                index,
+                instruction.to_named_assembly(feature_names.as_deref())
+            ));
+
+            let mut read_registers = BTreeSet::from([instruction.source_index()]);
+
+            match instruction.mode() {
+                Mode::Internal => {
+                    read_registers.insert(instruction.target_index());
+                }
+                Mode::Branch => {
+                    read_registers.insert(instruction.target_index());
+
+                    if index + 1 < self.instructions.len() {
+                        edges.push(format!("    i{} -> i{} [style=dashed];", index, index + 1));
+                    }
+                }
+                Mode::External => {
+                    inputs.insert(instruction.target_index());
+                    edges.push(format!(
+                        "    in{} -> i{};",
+                        instruction.target_index(),
+                        index
+                    ));
+                }
+            }
+
+            for register in read_registers {
+                if let Some(writer) = last_writers.get(&register) {
+                    edges.push(format!(
+                        "    i{} -> i{} [label=\"R[{}]\"];",
+                        writer, index, register
+                    ));
+                }
+            }
+
+            if *instruction.mode() != Mode::Branch {
+                last_writers.insert(instruction.source_index(), index);
+            }
+        }
+
+        for register in 0..T::N_OUTPUT_REGISTERS {
+            nodes.push(format!(
+                "    out{} [label=\"R[{}]\", shape=doublecircle];",
+                register, register
+            ));
+
+            if let Some(writer) = last_writers.get(&register) {
+                edges.push(format!("    i{} -> out{};", writer, register));
+            }
+        }
+
+        let input_nodes = inputs.into_iter().map(|input| {
+            format!(
+                "    in{} [label=\"{}\", shape=box];",
+                input,
+                feature_label(input, feature_names.as_deref())
+            )
+        });
+
+        std::iter::once("digraph program {".to_string())
+            .chain(input_nodes)
+            .chain(nodes)
+            .chain(edges)
+            .chain(std::iter::once("}".to_string()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Removes introns: instructions whose results never flow into the output registers.
+    /// Walks the instructions backwards, tracking the registers that are still read. Branches are
+    /// kept only when the instruction they guard is. Kept instructions have their indices
+    /// wrapped as they would be when executed.
+    pub fn simplify(&self) -> Self {
+        let mut live_registers: HashSet<usize> = (0..T::N_OUTPUT_REGISTERS).collect();
+        let mut effective_instructions = vec![];
+        let mut next_is_effective = false;
+        let instructions = self.wrapped_instructions().collect::<Vec<_>>();
+
+        for instruction in instructions.into_iter().rev() {
+            let is_effective = match instruction.mode() {
+                Mode::Branch => next_is_effective,
+                _ => live_registers.contains(&instruction.source_index()),
+            };
+            next_is_effective = is_effective;
+
+            if !is_effective {
+                continue;
+            }
+
+            match instruction.mode() {
+                Mode::Internal => {
+                    live_registers.insert(instruction.target_index());
+                }
+                Mode::Branch => {
+                    live_registers.insert(instruction.source_index());
+                    live_registers.insert(instruction.target_index());
+                }
+                Mode::External => (),
+            }
+
+            effective_instructions.push(instruction);
+        }
+
+        let instructions = effective_instructions.into_iter().rev().collect();
+
+        Program::new(instructions, self.registers.duplicate(), self.fitness)
+    }
 }
 
 impl<T> Display for Program<T>
 where
     T: ExtensionParameters,
 {
+    /// Renders the program as assembly (see `to_assembly`); use `save` for a loadable copy.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let serialized = toml::to_string(&self).unwrap();
-        f.write_str(&serialized)
+        f.write_str(&self.to_assembly())
     }
 }
 
@@ -82,54 +544,177 @@ where
 {
     type GeneratorParameters = ProgramGeneratorParameters;
 
-    fn generate<'a>(parameters: &'a Self::GeneratorParameters) -> Self {
+    fn generate_with_rng<R: Rng + ?Sized>(
+        parameters: &Self::GeneratorParameters,
+        rng: &mut R,
+    ) -> Self {
         let ProgramGeneratorParameters {
             max_instructions,
+            min_instructions,
             instruction_generator_parameters,
+            initial_register_bound,
+            register_clamp,
+            ..
         } = &parameters;
 
-        let registers = Registers::new(instruction_generator_parameters.n_registers);
-        let n_instructions = Uniform::new_inclusive(1, max_instructions).sample(&mut generator());
+        if let Err(error) = parameters.validate() {
+            panic!("{}", error);
+        }
+
+        let n_registers = instruction_generator_parameters.n_registers;
+        assert!(
+            n_registers >= T::N_OUTPUT_REGISTERS,
+            "Programs need at least {} registers to hold their outputs, but only {} were configured.",
+            T::N_OUTPUT_REGISTERS,
+            n_registers
+        );
+
+        let registers = match initial_register_bound {
+            Some(bound) => (0..n_registers)
+                .map(|_| Uniform::new_inclusive(-bound, bound).sample(rng))
+                .collect::<Vec<_>>()
+                .into(),
+            None => Registers::new(n_registers),
+        };
+        let registers = registers
+            .with_clamp(*register_clamp)
+            .with_n_outputs(Some(T::N_OUTPUT_REGISTERS));
+        assert!(min_instructions <= max_instructions);
+
+        let n_instructions = Uniform::new_inclusive(min_instructions, max_instructions).sample(rng);
         let instructions = (0..n_instructions)
             .into_iter()
-            .map(|_| Instruction::generate(instruction_generator_parameters))
+            .map(|_| Instruction::generate_with_rng(instruction_generator_parameters, rng))
             .collect();
 
         Self::new(instructions, registers, None)
     }
+
+    fn validate_parameters(parameters: &Self::GeneratorParameters) -> Result<(), String> {
+        parameters.validate().map_err(|error| error.to_string())
+    }
+
+    fn estimated_memory(parameters: &Self::GeneratorParameters) -> usize {
+        let n_registers = parameters.instruction_generator_parameters.n_registers;
+
+        mem::size_of::<Self>()
+            + parameters.max_instructions * mem::size_of::<Node<Instruction>>()
+            // Current and initial values.
+            + 2 * n_registers * mem::size_of::<R32>()
+    }
+
+    /// Drops the instructions past `max_instructions`.
+    fn enforce_bounds(&mut self, parameters: &Self::GeneratorParameters) {
+        self.instructions.truncate(parameters.max_instructions);
+    }
 }
 
 impl<T> Mutate for Program<T>
 where
     T: ExtensionParameters,
 {
-    fn mutate(&self, params: &Self::GeneratorParameters) -> Self {
+    fn mutate_with_rng<R: Rng + ?Sized>(
+        &self,
+        params: &Self::GeneratorParameters,
+        rng: &mut R,
+    ) -> Self {
         let mut mutated = self.clone();
+        let length = mutated.instructions.len();
+        let roll: f64 = rng.gen();
 
-        // Pick instruction to mutate.
-        let instruction = mutated
-            .instructions
-            .iter_mut()
-            .choose(&mut generator())
-            .unwrap();
+        match params.initial_register_bound {
+            // Macro mutation: grow the program by one instruction.
+            _ if roll < params.insertion_rate && length < params.max_instructions => {
+                let position = Uniform::new_inclusive(0, length).sample(rng);
+                let instruction =
+                    Instruction::generate_with_rng(&params.instruction_generator_parameters, rng);
+                let mut cursor = mutated.instructions.cursor_mut();
 
-        let mutated_instruction = instruction.mutate(&params.instruction_generator_parameters);
-        *instruction = mutated_instruction;
+                // Position 0 inserts after the ghost front, i.e. as the new head.
+                if position > 0 {
+                    cursor.seek(position - 1);
+                }
+                cursor.insert_after(instruction);
+            }
+            // Macro mutation: shrink the program by one instruction.
+            _ if roll < params.insertion_rate + params.deletion_rate
+                && length > params.min_instructions.max(1) =>
+            {
+                let position = Uniform::new(0, length).sample(rng);
+                let mut cursor = mutated.instructions.cursor_mut();
+
+                cursor.seek(position);
+                cursor.remove_current();
+            }
+            // Flip a Coin: Perturb an initial register value instead of an instruction.
+            Some(bound) if rng.gen_bool(0.5) => {
+                let index = Uniform::new(0, mutated.registers.len()).sample(rng);
+                let value = Uniform::new_inclusive(-bound, bound).sample(rng);
+                mutated.registers.set_initial(index, value);
+                mutated.registers.reset();
+            }
+            // Pick instruction to mutate.
+            _ => {
+                match mutated.instructions.iter_mut().choose(rng) {
+                    Some(instruction) => {
+                        let mutated_instruction = instruction
+                            .mutate_with_rng(&params.instruction_generator_parameters, rng);
+                        *instruction = mutated_instruction;
+                    }
+                    // An empty program has nothing to perturb, so it grows instead.
+                    None => mutated.instructions.cursor_mut().insert_after(
+                        Instruction::generate_with_rng(
+                            &params.instruction_generator_parameters,
+                            rng,
+                        ),
+                    ),
+                }
+            }
+        }
 
         // IMPORTANT: Reset fitness to force evaluation.
         mutated.fitness = None;
+        mutated.fitness_variance = None;
 
         mutated
     }
 }
 
+impl<T> Lineage for Program<T>
+where
+    T: ExtensionParameters,
+{
+    fn id(&self) -> Option<u64> {
+        self.id
+    }
+
+    fn parent_ids(&self) -> &[u64] {
+        &self.parent_ids
+    }
+
+    fn set_lineage(&mut self, id: u64, parent_ids: Vec<u64>) {
+        self.id = Some(id);
+        self.parent_ids = parent_ids;
+    }
+}
+
+impl<T> Length for Program<T>
+where
+    T: ExtensionParameters,
+{
+    fn length(&self) -> usize {
+        self.instructions.len()
+    }
+}
+
 impl<T> Breed for Program<T>
 where
     T: ExtensionParameters,
 {
-    fn two_point_crossover(&self, mate: &Self) -> [Self; 2] {
-        let [child_a_instructions, child_b_instructions] =
-            self.instructions.two_point_crossover(&mate.instructions);
+    fn two_point_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R) -> [Self; 2] {
+        let [child_a_instructions, child_b_instructions] = self
+            .instructions
+            .two_point_crossover_with_rng(&mate.instructions, rng);
 
         let program_a = Program::new(child_a_instructions, self.registers.duplicate(), None);
 
@@ -137,14 +722,49 @@ where
 
         [program_a, program_b]
     }
+
+    fn homologous_crossover_with_rng<R: Rng + ?Sized>(
+        &self,
+        mate: &Self,
+        rng: &mut R,
+    ) -> [Self; 2] {
+        let [child_a_instructions, child_b_instructions] = self
+            .instructions
+            .homologous_crossover_with_rng(&mate.instructions, rng);
+
+        let program_a = Program::new(child_a_instructions, self.registers.duplicate(), None);
+
+        let program_b = Program::new(child_b_instructions, self.registers.duplicate(), None);
+
+        [program_a, program_b]
+    }
+
+    fn uniform_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R) -> Self {
+        let instructions = self
+            .instructions
+            .uniform_crossover_with_rng(&mate.instructions, rng);
+
+        Program::new(instructions, self.registers.duplicate(), None)
+    }
+
+    fn genome_hash(&self) -> Option<u64> {
+        Some(self.structural_hash())
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use rand::{distributions::Standard, Rng};
+
     use crate::{
-        core::instruction::InstructionGeneratorParameters,
-        extensions::classification::ClassificationParameters, utils::test::TestInput,
+        core::{characteristics::Fitness, instruction::InstructionGeneratorParameters},
+        extensions::classification::{ClassificationInput, ClassificationParameters},
+        utils::{
+            executables::{add, divide, executable_name, multiply, subtract, UNNAMED_EXECUTABLE},
+            random::generator,
+            test::TestInput,
+        },
     };
 
     use super::*;
@@ -186,4 +806,694 @@ mod tests {
         assert_ne!(program_b, child_a);
         assert_ne!(program_b, child_b);
     }
+
+    #[test]
+    fn given_program_with_intron_when_simplify_then_intron_is_removed_and_fitness_is_unchanged() {
+        let instructions: Instructions = [
+            // Flows into the first output register.
+            Instruction::new(2, 0, Mode::External, add),
+            Instruction::new(0, 2, Mode::Internal, add),
+            // Intron: the scratch register is never read again.
+            Instruction::new(2, 1, Mode::External, multiply),
+            Instruction::new(1, 2, Mode::External, add),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::new(3),
+            None,
+        );
+        let mut simplified = program.simplify();
+
+        let expected: Instructions = [
+            Instruction::new(2, 0, Mode::External, add),
+            Instruction::new(0, 2, Mode::Internal, add),
+            Instruction::new(1, 2, Mode::External, add),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(simplified.instructions, expected);
+
+        let inputs: Vec<TestInput> = [0; 10].map(|_| generator().sample(Standard)).to_vec();
+
+        for input in &inputs {
+            program.exec(input);
+            simplified.exec(input);
+            assert_eq!(program.registers[0..2], simplified.registers[0..2]);
+            program.registers.reset();
+            simplified.registers.reset();
+        }
+
+        let mut parameters = ClassificationParameters::new(inputs);
+        assert_eq!(
+            program.eval_fitness(&mut parameters),
+            simplified.eval_fitness(&mut parameters)
+        );
+    }
+
+    #[test]
+    fn given_out_of_range_indices_when_simplify_then_indices_wrap_as_when_executed() {
+        let instructions: Instructions = [
+            // `R[2] = R[2] + I[0]` once wrapped.
+            Instruction::new(5, 4, Mode::External, add),
+            // `R[0] = R[0] + R[2]`, which raw indices would mistake for an intron.
+            Instruction::new(3, 5, Mode::Internal, add),
+            // Intron: writes `R[2]` after its last read.
+            Instruction::new(8, 1, Mode::External, multiply),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::new(3),
+            None,
+        );
+        let mut simplified = program.simplify();
+
+        let expected: Instructions = [
+            Instruction::new(2, 0, Mode::External, add),
+            Instruction::new(0, 2, Mode::Internal, add),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(simplified.instructions, expected);
+        assert_eq!(
+            program.to_assembly(),
+            "R[2] = R[2] + I[0]\nR[0] = R[0] + R[2]\nR[2] = R[2] * I[1]"
+        );
+
+        for _ in 0..10 {
+            let input: TestInput = generator().sample(Standard);
+            program.exec(&input);
+            simplified.exec(&input);
+            assert_eq!(program.registers[0..2], simplified.registers[0..2]);
+            program.registers.reset();
+            simplified.registers.reset();
+        }
+    }
+
+    #[test]
+    fn given_initial_register_bound_when_generate_then_registers_start_from_evolved_values() {
+        let mut params =
+            ProgramGeneratorParameters::new(10, InstructionGeneratorParameters::new(3, 4));
+        params.initial_register_bound = Some(2.);
+
+        let mut program = Program::<ClassificationParameters<TestInput>>::generate(&params);
+        let initial = program.registers.initial().to_vec();
+
+        assert!(initial.iter().all(|value| (-2f32..=2f32).contains(value)));
+        assert!(initial.iter().any(|value| *value != 0.));
+
+        program.exec(&TestInput::new([1.; 5]));
+        program.registers.reset();
+
+        assert_eq!(program.registers[..], initial[..]);
+        assert_eq!(program.registers.duplicate()[..], initial[..]);
+    }
+
+    #[test]
+    fn given_register_clamp_when_program_would_overflow_then_registers_stay_within_the_clamp() {
+        let mut params =
+            ProgramGeneratorParameters::new(10, InstructionGeneratorParameters::new(3, 4));
+        params.register_clamp = Some((-10., 10.));
+
+        let mut program = Program::<ClassificationParameters<TestInput>>::generate(&params);
+        program.instructions = std::iter::once(Instruction::new(0, 0, Mode::External, add))
+            .chain((0..20).map(|_| Instruction::new(0, 0, Mode::Internal, multiply)))
+            .collect();
+
+        let trace = program.exec_trace(&TestInput::new([1000.; 5]));
+
+        assert!(trace
+            .iter()
+            .flat_map(|registers| registers.iter())
+            .all(|value| (-10f32..=10f32).contains(value)));
+        assert_eq!(trace.last().unwrap()[0], 10.);
+        assert_eq!(program.registers.duplicate().clamp(), Some((-10., 10.)));
+    }
+
+    #[test]
+    fn given_register_clamp_and_wider_initial_bound_when_generate_and_mutate_then_initial_values_are_clamped(
+    ) {
+        let mut params =
+            ProgramGeneratorParameters::new(10, InstructionGeneratorParameters::new(3, 4));
+        params.initial_register_bound = Some(100.);
+        params.register_clamp = Some((-1., 1.));
+
+        let mut program = Program::<ClassificationParameters<TestInput>>::generate(&params);
+        for _ in 0..100 {
+            program = program.mutate(&params);
+        }
+
+        assert!(program
+            .registers
+            .initial()
+            .iter()
+            .chain(program.registers.iter())
+            .all(|value| (-1f32..=1f32).contains(value)));
+    }
+
+    #[test]
+    fn given_unordered_or_nan_register_clamp_when_validate_then_error_is_returned() {
+        let mut params =
+            ProgramGeneratorParameters::new(10, InstructionGeneratorParameters::new(3, 4));
+        pretty_assertions::assert_eq!(params.validate(), Ok(()));
+
+        params.register_clamp = Some((1., -1.));
+        pretty_assertions::assert_eq!(
+            params.validate(),
+            Err(ProgramParameterError::InvalidRegisterClamp(1., -1.))
+        );
+
+        params.register_clamp = Some((f32::NAN, 1.));
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn given_unordered_instruction_bounds_or_no_executables_when_validate_then_error_is_returned() {
+        let mut params =
+            ProgramGeneratorParameters::new(10, InstructionGeneratorParameters::new(3, 4));
+        params.min_instructions = 11;
+        pretty_assertions::assert_eq!(
+            params.validate(),
+            Err(ProgramParameterError::InstructionBoundsOutOfOrder { min: 11, max: 10 })
+        );
+
+        params.min_instructions = 0;
+        pretty_assertions::assert_eq!(
+            params.validate(),
+            Err(ProgramParameterError::NoMinInstructions)
+        );
+
+        params.min_instructions = 10;
+        params
+            .instruction_generator_parameters
+            .available_executables
+            .clear();
+        pretty_assertions::assert_eq!(params.validate(), Err(ProgramParameterError::NoExecutables));
+    }
+
+    #[test]
+    fn given_executable_override_when_generate_then_every_instruction_uses_it() {
+        let mut instruction_parameters = InstructionGeneratorParameters::from::<TestInput>(1);
+        instruction_parameters.available_executables = vec![add];
+        let params = ProgramGeneratorParameters::new(20, instruction_parameters);
+
+        for _ in 0..10 {
+            let program = Program::<ClassificationParameters<TestInput>>::generate(&params);
+            let mutated = program.mutate(&params);
+
+            assert!(program
+                .instructions
+                .iter()
+                .chain(mutated.instructions.iter())
+                .all(|instruction| executable_name(instruction.executable()) == Some("add")));
+        }
+    }
+
+    #[test]
+    fn given_saved_program_without_output_layout_when_deserialized_then_layout_is_restored() {
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            [Instruction::new(0, 1, Mode::Internal, add)]
+                .into_iter()
+                .collect(),
+            Registers::new(3),
+            None,
+        );
+        let mut saved = serde_json::to_value(&program).unwrap();
+        saved["registers"]
+            .as_object_mut()
+            .unwrap()
+            .remove("n_outputs");
+
+        let loaded: Program<ClassificationParameters<TestInput>> =
+            serde_json::from_value(saved).unwrap();
+
+        assert_eq!(
+            loaded.registers.n_outputs(),
+            Some(TestInput::N_ACTION_REGISTERS)
+        );
+    }
+
+    #[test]
+    fn given_custom_executable_when_displayed_or_serialized_then_neither_panics() {
+        fn halve(a: R32, _b: R32) -> R32 {
+            a / 2.
+        }
+
+        let instructions: Instructions = [Instruction::new(0, 1, Mode::Internal, halve)]
+            .into_iter()
+            .collect();
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::new(3),
+            None,
+        );
+
+        let serialized = serde_json::to_string(&program).unwrap();
+
+        assert_eq!(program.to_string(), "R[0] = op(R[0], R[1])");
+        assert!(serialized.contains(UNNAMED_EXECUTABLE));
+        assert!(
+            serde_json::from_str::<Program<ClassificationParameters<TestInput>>>(&serialized)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn given_program_when_to_assembly_then_instructions_are_rendered_line_by_line() {
+        let instructions: Instructions = [
+            Instruction::new(2, 0, Mode::External, add),
+            Instruction::new(0, 2, Mode::Internal, multiply),
+            Instruction::new(1, 2, Mode::Internal, divide),
+        ]
+        .into_iter()
+        .collect();
+
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::new(3),
+            None,
+        );
+
+        assert_eq!(
+            program.to_assembly(),
+            "R[2] = R[2] + I[0]\nR[0] = R[0] * R[2]\nR[1] = R[1] / R[2]"
+        );
+    }
+
+    #[derive(Clone)]
+    struct NamedInput(TestInput);
+
+    impl ValidInput for NamedInput {
+        const N_INPUT_REGISTERS: usize = TestInput::N_INPUT_REGISTERS;
+        const N_ACTION_REGISTERS: usize = TestInput::N_ACTION_REGISTERS;
+
+        fn flat(&self) -> Vec<R32> {
+            self.0.flat()
+        }
+
+        fn feature_names() -> Option<Vec<&'static str>> {
+            Some(vec!["height", "width"])
+        }
+    }
+
+    impl ClassificationInput for NamedInput {
+        fn get_class(&self) -> usize {
+            self.0.get_class()
+        }
+    }
+
+    #[test]
+    fn given_feature_names_when_rendered_then_inputs_are_labeled_by_name() {
+        let instructions: Instructions = [
+            Instruction::new(0, 1, Mode::External, add),
+            Instruction::new(1, 3, Mode::External, multiply),
+        ]
+        .into_iter()
+        .collect();
+
+        let program = Program::<ClassificationParameters<NamedInput>>::new(
+            instructions,
+            Registers::new(2),
+            None,
+        );
+
+        // Features without a name keep their index.
+        assert_eq!(
+            program.to_assembly(),
+            "R[0] = R[0] + width\nR[1] = R[1] * I[3]"
+        );
+
+        let dot = program.to_dot();
+        assert!(dot.contains("in1 [label=\"width\", shape=box];"));
+        assert!(dot.contains("in3 [label=\"I[3]\", shape=box];"));
+    }
+
+    #[test]
+    fn given_register_dependency_when_to_dot_then_graph_contains_dataflow_edge() {
+        let instructions: Instructions = [
+            Instruction::new(2, 0, Mode::External, add),
+            Instruction::new(0, 2, Mode::Internal, multiply),
+        ]
+        .into_iter()
+        .collect();
+
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::new(3),
+            None,
+        );
+        let dot = program.to_dot();
+
+        assert!(dot.starts_with("digraph program {"));
+        assert!(dot.contains("in0 [label=\"I[0]\", shape=box];"));
+        assert!(dot.contains("out0 [label=\"R[0]\", shape=doublecircle];"));
+        assert!(dot.contains("in0 -> i0;"));
+        assert!(dot.contains("i0 -> i1 [label=\"R[2]\"];"));
+        assert!(dot.contains("i1 -> out0;"));
+        assert!(!dot.contains("-> out1"));
+    }
+
+    #[test]
+    fn given_program_with_branches_when_exec_then_only_guarded_instructions_whose_condition_holds_run(
+    ) {
+        let instructions: Instructions = [
+            // R[0] < R[1] holds, so the next instruction runs.
+            Instruction::new(0, 1, Mode::Branch, add),
+            Instruction::new(0, 0, Mode::External, add),
+            // R[1] < R[0] fails, so the next instruction is skipped.
+            Instruction::new(1, 0, Mode::Branch, add),
+            Instruction::new(1, 1, Mode::External, add),
+            // A failing branch also skips the branch it guards, and that branch's instruction.
+            Instruction::new(1, 0, Mode::Branch, add),
+            Instruction::new(0, 1, Mode::Branch, add),
+            Instruction::new(2, 2, Mode::External, add),
+            Instruction::new(2, 3, Mode::External, add),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::from(vec![0., 1., 0.]),
+            None,
+        );
+
+        program.exec(&TestInput::new([1., 2., 3., 4., 0.]));
+
+        pretty_assertions::assert_eq!(
+            program.registers.iter().copied().collect::<Vec<_>>(),
+            vec![1., 1., 4.]
+        );
+    }
+
+    #[test]
+    fn given_branches_are_allowed_when_generate_and_mutate_then_branches_are_produced() {
+        let mut instruction_params = InstructionGeneratorParameters::new(3, 4);
+        instruction_params.allow_branches = true;
+        let params = ProgramGeneratorParameters::new(100, instruction_params);
+
+        let programs = (0..10)
+            .map(|_| Program::<ClassificationParameters<TestInput>>::generate(&params))
+            .collect::<Vec<_>>();
+        let mutated = programs.iter().map(|program| program.mutate(&params));
+
+        let has_branch = |program: &Program<ClassificationParameters<TestInput>>| {
+            program
+                .instructions
+                .iter()
+                .any(|instruction| *instruction.mode() == Mode::Branch)
+        };
+
+        assert!(programs.iter().any(has_branch));
+        assert!(mutated.into_iter().any(|program| has_branch(&program)));
+    }
+
+    #[test]
+    fn given_program_when_confidences_then_distribution_sums_to_one_and_agrees_with_prediction() {
+        let instructions: Instructions = [
+            Instruction::new(0, 0, Mode::External, add),
+            Instruction::new(1, 1, Mode::External, add),
+        ]
+        .into_iter()
+        .collect();
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::new(3),
+            None,
+        );
+
+        for input in [
+            TestInput::new([0.2, 0.7, 0., 0., 0.]),
+            TestInput::new([0.9, 0.1, 0., 0., 0.]),
+        ] {
+            let confidences = program.confidences(&input);
+            let total: f32 = confidences.iter().sum();
+
+            let mut executed = program.clone();
+            executed.exec(&input);
+            let prediction = ClassificationParameters::<TestInput>::argmax(&executed.registers);
+            let most_confident = confidences
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                .map(|(index, _)| index as i32)
+                .unwrap();
+
+            pretty_assertions::assert_eq!(confidences.len(), 2);
+            assert!((total - 1.).abs() < 1e-6);
+            pretty_assertions::assert_eq!(most_confident, prediction);
+        }
+
+        let empty = Program::<ClassificationParameters<TestInput>>::new(
+            Instructions::default(),
+            Registers::new(3),
+            None,
+        );
+        pretty_assertions::assert_eq!(empty.confidences(&TestInput::default()), vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn given_conditional_program_when_to_rules_then_rules_describe_its_predictions() {
+        let instructions: Instructions = [
+            Instruction::new(0, 0, Mode::External, add),
+            Instruction::new(1, 1, Mode::External, add),
+            // An intron, which the rules leave out.
+            Instruction::new(2, 3, Mode::External, multiply),
+            Instruction::new(0, 1, Mode::Branch, add),
+            Instruction::new(1, 2, Mode::External, multiply),
+        ]
+        .into_iter()
+        .collect();
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::new(3),
+            None,
+        );
+
+        pretty_assertions::assert_eq!(
+            program.to_rules(),
+            vec![
+                "R[1] = I[1]".to_string(),
+                "if I[0] < I[1] then R[1] = I[1] * I[2]".to_string(),
+                "R[0] = I[0]".to_string(),
+            ]
+        );
+
+        // I[0] < I[1] and I[1] * I[2] = 0.1 < I[0], so class 0.
+        let below = TestInput::new([0.2, 0.5, 0.2, 0., 0.]);
+        // I[0] >= I[1], so R[1] keeps I[1] = 0.1 < I[0]: class 0.
+        let above = TestInput::new([0.2, 0.1, 5., 0., 0.]);
+        // I[0] < I[1] and I[1] * I[2] = 1 > I[0], so class 1.
+        let boosted = TestInput::new([0.2, 0.5, 2., 0., 0.]);
+
+        let predict = |input: &TestInput| {
+            let mut executed = program.clone();
+            executed.exec(input);
+            ClassificationParameters::<TestInput>::argmax(&executed.registers)
+        };
+
+        pretty_assertions::assert_eq!([&below, &above, &boosted].map(predict), [0, 0, 1]);
+    }
+
+    #[test]
+    fn given_overflowing_instructions_when_exec_then_registers_stay_finite() {
+        let instructions: Instructions = [
+            // Squaring 1e30 overflows to infinity.
+            Instruction::new(0, 0, Mode::Internal, multiply),
+            Instruction::new(1, 1, Mode::Internal, multiply),
+            // Without clamping this would be inf - inf = NaN.
+            Instruction::new(0, 1, Mode::Internal, subtract),
+            Instruction::new(2, 1, Mode::Internal, multiply),
+        ]
+        .into_iter()
+        .collect();
+        let mut program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::from(vec![1e30, 1e30, -1e30]),
+            None,
+        );
+
+        program.exec(&TestInput::default());
+
+        assert!(program.registers.iter().all(|value| value.is_finite()));
+        pretty_assertions::assert_eq!(
+            program.registers.iter().copied().collect::<Vec<_>>(),
+            vec![0., f32::MAX, f32::MIN]
+        );
+    }
+
+    #[test]
+    fn given_min_instructions_when_generate_then_every_program_has_at_least_that_many() {
+        let mut params =
+            ProgramGeneratorParameters::new(10, InstructionGeneratorParameters::new(3, 4));
+        pretty_assertions::assert_eq!(params.min_instructions, 1);
+
+        params.min_instructions = 5;
+
+        for _ in 0..500 {
+            let program = Program::<ClassificationParameters<TestInput>>::generate(&params);
+            assert!((5..=10).contains(&program.instructions.len()));
+        }
+    }
+
+    #[test]
+    fn given_macro_mutation_rates_when_mutate_then_lengths_change_within_bounds() {
+        let mut params =
+            ProgramGeneratorParameters::new(8, InstructionGeneratorParameters::new(3, 4));
+        params.min_instructions = 2;
+        params.insertion_rate = 0.4;
+        params.deletion_rate = 0.4;
+
+        let mut program = Program::<ClassificationParameters<TestInput>>::generate(&params);
+        let (mut n_insertions, mut n_deletions) = (0, 0);
+
+        for _ in 0..1000 {
+            let mutated = program.mutate(&params);
+            let (before, after) = (program.instructions.len(), mutated.instructions.len());
+
+            assert!((2..=8).contains(&after));
+            assert_eq!(after, mutated.instructions.iter().count());
+
+            if after > before {
+                n_insertions += 1;
+            } else if after < before {
+                n_deletions += 1;
+            }
+
+            program = mutated;
+        }
+
+        assert!(n_insertions > 0);
+        assert!(n_deletions > 0);
+    }
+
+    #[test]
+    fn given_empty_program_when_mutate_then_an_instruction_is_inserted() {
+        let params = ProgramGeneratorParameters::new(8, InstructionGeneratorParameters::new(3, 4));
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            Instructions::new(),
+            Registers::new(3),
+            None,
+        );
+
+        for _ in 0..100 {
+            pretty_assertions::assert_eq!(program.mutate(&params).instructions.len(), 1);
+        }
+    }
+
+    #[test]
+    fn given_constant_parents_when_uniform_crossover_then_child_instructions_come_from_either_parent(
+    ) {
+        let instruction_a = Instruction::new(0, 1, Mode::Internal, add);
+        let instruction_b = Instruction::new(2, 3, Mode::External, multiply);
+
+        let parent_a = Program::<ClassificationParameters<TestInput>>::new(
+            (0..10).map(|_| instruction_a.clone()).collect(),
+            Registers::new(4),
+            None,
+        );
+        let parent_b = Program::<ClassificationParameters<TestInput>>::new(
+            (0..6).map(|_| instruction_b.clone()).collect(),
+            Registers::new(4),
+            None,
+        );
+
+        let child = parent_a.uniform_crossover(&parent_b);
+
+        pretty_assertions::assert_eq!(child.instructions.len(), parent_a.instructions.len());
+        assert!(child
+            .instructions
+            .iter()
+            .all(|instruction| *instruction == instruction_a || *instruction == instruction_b));
+        // Past the end of the shorter mate, the tail is inherited from the first parent.
+        assert!(child
+            .instructions
+            .iter()
+            .skip(6)
+            .all(|instruction| *instruction == instruction_a));
+    }
+
+    #[test]
+    fn given_programs_when_homologous_crossover_then_children_keep_their_parents_lengths() {
+        let params = ProgramGeneratorParameters::new(12, InstructionGeneratorParameters::new(3, 4));
+
+        for _ in 0..200 {
+            let parent_a = Program::<ClassificationParameters<TestInput>>::generate(&params);
+            let parent_b = Program::<ClassificationParameters<TestInput>>::generate(&params);
+
+            let [child_a, child_b] = parent_a.homologous_crossover(&parent_b);
+
+            pretty_assertions::assert_eq!(child_a.instructions.len(), parent_a.instructions.len());
+            pretty_assertions::assert_eq!(child_b.instructions.len(), parent_b.instructions.len());
+            pretty_assertions::assert_eq!(
+                child_a.instructions.iter().count(),
+                parent_a.instructions.len()
+            );
+
+            // Each position holds the gene of either parent at that same position.
+            for (index, instruction) in child_a.instructions.iter().enumerate() {
+                assert!(
+                    Some(instruction) == parent_a.instructions.get(index)
+                        || Some(instruction) == parent_b.instructions.get(index)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn given_two_instruction_program_when_exec_trace_then_last_snapshot_matches_exec() {
+        let input = TestInput([1., 2., 3., 4., 0.]);
+        let instructions = [
+            Instruction::new(0, 1, Mode::External, add),
+            Instruction::new(1, 0, Mode::Internal, multiply),
+        ];
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            instructions.into_iter().collect(),
+            Registers::from(vec![1., 2., 3.]),
+            None,
+        );
+
+        let trace = program.exec_trace(&input);
+
+        pretty_assertions::assert_eq!(trace.len(), 2);
+        pretty_assertions::assert_eq!(*trace[0].get(0), 3.);
+        pretty_assertions::assert_eq!(*trace[0].get(1), 2.);
+
+        let mut executed = program.clone();
+        executed.exec(&input);
+        pretty_assertions::assert_eq!(trace[1], executed.registers);
+        // The traced program itself is untouched.
+        pretty_assertions::assert_eq!(
+            program.registers,
+            Registers::from(vec![1., 2., 3.]).with_n_outputs(Some(2))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Programs need at least 2 registers to hold their outputs")]
+    fn given_fewer_registers_than_outputs_when_generate_then_clear_error_is_raised() {
+        let params = ProgramGeneratorParameters::new(10, InstructionGeneratorParameters::new(1, 4));
+
+        Program::<ClassificationParameters<TestInput>>::generate(&params);
+    }
+
+    #[test]
+    fn given_a_known_register_layout_when_program_is_generated_then_accessors_report_it() {
+        let parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(2),
+        );
+        let program = Program::<ClassificationParameters<TestInput>>::generate(&parameters);
+
+        pretty_assertions::assert_eq!(program.n_registers(), TestInput::N_ACTION_REGISTERS + 2);
+        pretty_assertions::assert_eq!(program.n_input_registers(), TestInput::N_INPUT_REGISTERS);
+        pretty_assertions::assert_eq!(program.n_output_registers(), TestInput::N_ACTION_REGISTERS);
+    }
 }
@@ -1,57 +1,278 @@
 use core::slice::Iter;
-use std::{ops::Index, slice::SliceIndex};
+use std::{fmt, ops::Index, slice::SliceIndex};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 pub type R32 = f32;
 
-#[derive(Debug, Clone, Serialize)]
+/// Maps `NaN` to 0 and clamps infinities to the finite range, so a single bad operation can't
+/// poison later comparisons.
+pub fn sanitize(value: R32) -> R32 {
+    if value.is_nan() {
+        0.
+    } else {
+        value.clamp(R32::MIN, R32::MAX)
+    }
+}
+
+/// Two register sets of different lengths were compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthMismatch {
+    pub left: usize,
+    pub right: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cannot compare {} registers with {} registers.",
+            self.left, self.right
+        )
+    }
+}
+
+impl std::error::Error for LengthMismatch {}
+
+/// The output registers were read before `Registers::with_n_outputs` set how many there are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingOutputLayout;
+
+impl fmt::Display for MissingOutputLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The number of output registers is unknown; set it with `Registers::with_n_outputs`."
+        )
+    }
+}
+
+impl std::error::Error for MissingOutputLayout {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Registers {
     data: Vec<R32>,
+    /// Values restored on `reset`.
+    initial: Vec<R32>,
+    /// Inclusive range values are clamped to after every instruction.
+    #[serde(default)]
+    clamp: Option<(R32, R32)>,
+    /// How many leading registers hold outputs; `None` treats every register as one.
+    #[serde(default)]
+    n_outputs: Option<usize>,
 }
 
 impl From<Vec<R32>> for Registers {
     fn from(data: Vec<R32>) -> Self {
-        Registers { data }
+        let initial = data.clone();
+        Registers {
+            data,
+            initial,
+            clamp: None,
+            n_outputs: None,
+        }
+    }
+}
+
+impl From<&[R32]> for Registers {
+    fn from(data: &[R32]) -> Self {
+        data.to_vec().into()
     }
 }
 
 impl Registers {
     pub fn new(n_registers: usize) -> Self {
         let data = vec![0.; n_registers];
+        let initial = data.clone();
 
-        Registers { data }
+        Registers {
+            data,
+            initial,
+            clamp: None,
+            n_outputs: None,
+        }
     }
 
     pub fn reset(&mut self) {
-        let Registers { data } = self;
-        for value in data.as_mut_slice() {
-            *value = 0.
-        }
+        let Registers { data, initial, .. } = self;
+        data.clone_from(initial);
     }
 
+    /// A fresh set of registers holding this set's initial values.
     pub fn duplicate(&self) -> Self {
-        Self::new(self.len())
+        Self::from(self.initial.clone())
+            .with_clamp(self.clamp)
+            .with_n_outputs(self.n_outputs)
+    }
+
+    /// Clamps values (initial ones included) into `clamp` from now on. The bounds must be
+    /// ordered and not `NaN`, as checked by `ProgramGeneratorParameters::validate`.
+    pub fn with_clamp(self, clamp: Option<(R32, R32)>) -> Self {
+        let mut registers = Self { clamp, ..self };
+
+        if let Some((min, max)) = clamp {
+            for value in registers.initial.iter_mut() {
+                *value = value.clamp(min, max);
+            }
+        }
+        registers.apply_clamp();
+
+        registers
+    }
+
+    pub fn clamp(&self) -> Option<(R32, R32)> {
+        self.clamp
+    }
+
+    pub fn with_n_outputs(self, n_outputs: Option<usize>) -> Self {
+        Self { n_outputs, ..self }
+    }
+
+    pub fn n_outputs(&self) -> Option<usize> {
+        self.n_outputs
+    }
+
+    /// Clamps every value into the configured range, if any.
+    pub fn apply_clamp(&mut self) {
+        if let Some((min, max)) = self.clamp {
+            for value in self.data.iter_mut() {
+                *value = value.clamp(min, max);
+            }
+        }
     }
 
     pub fn len(&self) -> usize {
-        let Registers { data } = self;
+        let Registers { data, .. } = self;
         data.len()
     }
 
     pub fn update(&mut self, index: usize, value: R32) {
-        let Registers { data } = self;
+        let Registers { data, .. } = self;
         data[index] = value;
     }
 
     pub fn get(&self, index: usize) -> &R32 {
-        let Registers { data } = self;
+        let Registers { data, .. } = self;
         data.get(index).unwrap()
     }
 
+    /// Like `get`, but `None` instead of a panic when `index` is out of range.
+    pub fn try_get(&self, index: usize) -> Option<&R32> {
+        self.data.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut R32> {
+        self.data.get_mut(index)
+    }
+
+    /// Reads register `index % len`, so every index is valid unless there are no registers.
+    pub fn get_wrapped(&self, index: usize) -> Option<&R32> {
+        let wrapped = index.checked_rem(self.len())?;
+        self.data.get(wrapped)
+    }
+
+    pub fn get_wrapped_mut(&mut self, index: usize) -> Option<&mut R32> {
+        let wrapped = index.checked_rem(self.len())?;
+        self.data.get_mut(wrapped)
+    }
+
+    pub fn initial(&self) -> &[R32] {
+        &self.initial
+    }
+
+    /// Sets the value restored on `reset`, clamped like every other value.
+    pub fn set_initial(&mut self, index: usize, value: R32) {
+        let Registers { initial, clamp, .. } = self;
+        initial[index] = match clamp {
+            Some((min, max)) => value.clamp(*min, *max),
+            None => value,
+        };
+    }
+
     pub fn iter<'a>(&'a self) -> Iter<'a, R32> {
         self.data.iter()
     }
+
+    /// Softmax over the first `n_classes` registers, shifted by their maximum for numerical
+    /// stability. Equal values yield a uniform distribution.
+    pub fn softmax(&self, n_classes: usize) -> Vec<f32> {
+        let logits = &self.data[0..n_classes];
+
+        if logits.iter().all(|logit| *logit == logits[0]) {
+            return vec![1. / n_classes as f32; n_classes];
+        }
+
+        let max_logit = logits.iter().copied().fold(R32::NEG_INFINITY, R32::max);
+        let exponentials = logits
+            .iter()
+            .map(|logit| (logit - max_logit).exp())
+            .collect::<Vec<_>>();
+        let total: f32 = exponentials.iter().sum();
+
+        exponentials
+            .into_iter()
+            .map(|exponential| exponential / total)
+            .collect()
+    }
+
+    /// Every index among the first `n_classes` registers that holds the maximum value.
+    pub fn argmax_all(&self, n_classes: usize) -> Vec<usize> {
+        assert!(
+            n_classes <= self.len(),
+            "Expected at least {} output registers, but only {} registers exist.",
+            n_classes,
+            self.len()
+        );
+
+        let candidates = &self.data[0..n_classes];
+        let max_value = candidates.iter().copied().fold(R32::NAN, R32::max);
+
+        candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value == max_value)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// `argmax_all` over the stored output registers.
+    pub fn argmax_outputs(&self) -> Result<Vec<usize>, MissingOutputLayout> {
+        self.n_outputs
+            .map(|n_outputs| self.argmax_all(n_outputs))
+            .ok_or(MissingOutputLayout)
+    }
+
+    fn paired_differences<'a>(
+        &'a self,
+        other: &'a Registers,
+    ) -> Result<impl Iterator<Item = R32> + 'a, LengthMismatch> {
+        if self.len() != other.len() {
+            return Err(LengthMismatch {
+                left: self.len(),
+                right: other.len(),
+            });
+        }
+
+        Ok(self.iter().zip(other.iter()).map(|(a, b)| a - b))
+    }
+
+    /// Euclidean distance between the current values, e.g. to score programs against a target
+    /// register state.
+    pub fn euclidean_distance(&self, other: &Registers) -> Result<f32, LengthMismatch> {
+        Ok(self
+            .paired_differences(other)?
+            .map(|difference| difference.powi(2))
+            .sum::<f32>()
+            .sqrt())
+    }
+
+    /// Sum of absolute differences between the current values.
+    pub fn manhattan_distance(&self, other: &Registers) -> Result<f32, LengthMismatch> {
+        Ok(self
+            .paired_differences(other)?
+            .map(|difference| difference.abs())
+            .sum())
+    }
 }
 
 impl<Idx> Index<Idx> for Registers
@@ -64,3 +285,96 @@ where
         &self.data[index]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{LengthMismatch, MissingOutputLayout, Registers};
+
+    #[test]
+    fn given_plain_floats_when_converted_to_registers_then_values_round_trip() {
+        let values: Vec<f32> = vec![0.5, -2., 3.25];
+
+        let from_slice = Registers::from(values.as_slice());
+        let from_vec = Registers::from(values.clone());
+
+        pretty_assertions::assert_eq!(from_slice.iter().copied().collect::<Vec<_>>(), values);
+        pretty_assertions::assert_eq!(from_slice.initial(), values.as_slice());
+        pretty_assertions::assert_eq!(from_slice, from_vec);
+    }
+
+    #[test]
+    fn given_logits_when_softmax_then_probabilities_sum_to_one_and_preserve_order() {
+        let registers = Registers::from(vec![0.5, 3., -1., 100.]);
+        let probabilities = registers.softmax(3);
+        let total: f32 = probabilities.iter().sum();
+
+        pretty_assertions::assert_eq!(probabilities.len(), 3);
+        assert!((total - 1.).abs() < 1e-6);
+        assert!(probabilities[1] > probabilities[0] && probabilities[0] > probabilities[2]);
+
+        let large = Registers::from(vec![1e30, 1e30 - 1e24]);
+        assert!(large.softmax(2).iter().all(|p| p.is_finite()));
+    }
+
+    #[test]
+    fn given_two_way_tie_when_argmax_all_then_both_indices_are_returned() {
+        let registers = Registers::from(vec![1., 3., 3., 5.]);
+
+        pretty_assertions::assert_eq!(registers.argmax_all(3), vec![1, 2]);
+        pretty_assertions::assert_eq!(registers.argmax_all(4), vec![3]);
+    }
+
+    #[test]
+    fn given_a_layout_with_three_outputs_when_argmax_outputs_then_only_outputs_are_considered() {
+        // 3 outputs followed by 4 registers holding copies of the inputs.
+        let registers = Registers::from(vec![0.5, 2., -1., 9., 9., 9., 9.]).with_n_outputs(Some(3));
+
+        pretty_assertions::assert_eq!(registers.argmax_outputs(), Ok(vec![1]));
+        pretty_assertions::assert_eq!(registers.duplicate().n_outputs(), Some(3));
+        pretty_assertions::assert_eq!(
+            registers.with_n_outputs(None).argmax_outputs(),
+            Err(MissingOutputLayout)
+        );
+    }
+
+    #[test]
+    fn given_registers_when_serialized_and_deserialized_then_values_and_initial_values_round_trip()
+    {
+        let mut registers = Registers::from(vec![0.5, -1., 2.]);
+        registers.update(0, 3.);
+
+        let serialized = serde_json::to_string(&registers).unwrap();
+        let deserialized: Registers = serde_json::from_str(&serialized).unwrap();
+
+        pretty_assertions::assert_eq!(deserialized, registers);
+        pretty_assertions::assert_eq!(deserialized[..], [3., -1., 2.]);
+        pretty_assertions::assert_eq!(deserialized.initial(), &[0.5, -1., 2.]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected at least 3 output registers, but only 2 registers exist.")]
+    fn given_fewer_registers_than_outputs_when_argmax_all_then_clear_error_is_raised() {
+        Registers::new(2).argmax_all(3);
+    }
+
+    #[test]
+    fn given_two_register_sets_when_distances_are_computed_then_they_match_hand_calculations() {
+        let a = Registers::from(vec![1., 2., 3.]);
+        let b = Registers::from(vec![4., 6., 3.]);
+
+        pretty_assertions::assert_eq!(a.euclidean_distance(&b), Ok(5.));
+        pretty_assertions::assert_eq!(a.manhattan_distance(&b), Ok(7.));
+        pretty_assertions::assert_eq!(a.euclidean_distance(&a), Ok(0.));
+    }
+
+    #[test]
+    fn given_register_sets_of_different_lengths_when_distances_are_computed_then_an_error_is_returned(
+    ) {
+        let a = Registers::from(vec![1., 2., 3.]);
+        let b = Registers::from(vec![1., 2.]);
+        let mismatch = Err(LengthMismatch { left: 3, right: 2 });
+
+        pretty_assertions::assert_eq!(a.euclidean_distance(&b), mismatch);
+        pretty_assertions::assert_eq!(a.manhattan_distance(&b), mismatch);
+    }
+}
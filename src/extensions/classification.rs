@@ -1,83 +1,604 @@
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    fmt,
+    hash::{Hash, Hasher},
+};
+
 use derive_new::new;
 use itertools::Itertools;
+use rand::prelude::SliceRandom;
 use serde::Serialize;
 
-use crate::core::{
-    characteristics::Fitness,
-    inputs::{Inputs, ValidInput},
-    program::Program,
-    registers::Registers,
+use crate::{
+    core::{
+        characteristics::{Fitness, FitnessScore},
+        inputs::{DatasetError, Inputs, ValidInput},
+        instruction::Mode,
+        program::Program,
+        registers::{Registers, R32},
+    },
+    utils::{
+        executables::{executable_name, rust_expression},
+        random::generator,
+    },
 };
 
 use super::core::ExtensionParameters;
 
-#[derive(Clone, Debug, Serialize, new)]
+/// How a prediction is made when several output registers share the maximum value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Default)]
+pub enum TieBreak {
+    /// Ties are ambiguous and count as no prediction (-1).
+    #[default]
+    None,
+    /// The first tied register, in register order.
+    FirstIndex,
+    /// A tied register drawn from the seeded generator.
+    Random,
+}
+
+/// The aggregate of per-input predictions that `eval_fitness` scores a program by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Default)]
+pub enum FitnessMetric {
+    /// Fraction of all inputs labelled correctly.
+    #[default]
+    Accuracy,
+    /// Mean recall over the classes present in the inputs, robust to class imbalance.
+    BalancedAccuracy,
+    /// Mean F1 score over the classes present in the inputs.
+    F1Macro,
+}
+
+/// A cost matrix that isn't `N_ACTION_REGISTERS` by `N_ACTION_REGISTERS`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CostMatrixError {
+    RowCount {
+        expected: usize,
+        found: usize,
+    },
+    ColumnCount {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for CostMatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CostMatrixError::RowCount { expected, found } => write!(
+                f,
+                "The cost matrix has {} rows, expected one per class ({}).",
+                found, expected
+            ),
+            CostMatrixError::ColumnCount {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} of the cost matrix has {} columns, expected one per class ({}).",
+                row, found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CostMatrixError {}
+
+/// Lets a classifier decline to predict when its output is not confident enough.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, new)]
+pub struct Abstention {
+    /// Predictions whose winning register falls below this value are withheld. Ties are always
+    /// withheld.
+    pub threshold: f32,
+    /// Credit for each abstention under `FitnessMetric::Accuracy`, between a wrong (0) and a
+    /// correct (1) prediction.
+    pub reward: f32,
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub struct ClassificationParameters<InputType>
 where
     InputType: ClassificationInput,
 {
     inputs: Inputs<InputType>,
+    /// Fitness penalty per instruction, to favour shorter programs.
+    pub parsimony_coefficient: f32,
+    pub tie_break: TieBreak,
+    pub metric: FitnessMetric,
+    /// `cost_matrix[actual][predicted]` is the cost of each prediction. When set, it replaces
+    /// `metric`: fitness becomes `1 / (1 + mean cost)`, and ambiguous predictions cost the most
+    /// expensive entry of the actual class' row. Set through `with_cost_matrix`, which checks
+    /// its shape.
+    cost_matrix: Option<Vec<Vec<f32>>>,
+    pub abstention: Option<Abstention>,
+}
+
+impl<T> ClassificationParameters<T>
+where
+    T: ClassificationInput,
+{
+    /// Like `try_new`, but panics if a label has no output register.
+    pub fn new(inputs: Inputs<T>) -> Self {
+        match Self::try_new(inputs) {
+            Ok(parameters) => parameters,
+            Err(error) => panic!("Invalid dataset: {}", error),
+        }
+    }
+
+    /// Default settings over `inputs`, failing if any input's class isn't one of the
+    /// `N_ACTION_REGISTERS` classes (see `validate_classes`).
+    pub fn try_new(inputs: Inputs<T>) -> Result<Self, DatasetError> {
+        validate_classes(&inputs)?;
+
+        Ok(ClassificationParameters {
+            inputs,
+            parsimony_coefficient: 0.,
+            tie_break: TieBreak::default(),
+            metric: FitnessMetric::default(),
+            cost_matrix: None,
+            abstention: None,
+        })
+    }
+
+    /// Scores predictions by `cost_matrix` instead of `metric`, failing unless it holds a row
+    /// and a column per class.
+    pub fn with_cost_matrix(self, cost_matrix: Vec<Vec<f32>>) -> Result<Self, CostMatrixError> {
+        let n_classes = T::N_ACTION_REGISTERS;
+
+        if cost_matrix.len() != n_classes {
+            return Err(CostMatrixError::RowCount {
+                expected: n_classes,
+                found: cost_matrix.len(),
+            });
+        }
+
+        if let Some((row, costs)) = cost_matrix
+            .iter()
+            .enumerate()
+            .find(|(_, costs)| costs.len() != n_classes)
+        {
+            return Err(CostMatrixError::ColumnCount {
+                row,
+                expected: n_classes,
+                found: costs.len(),
+            });
+        }
+
+        Ok(ClassificationParameters {
+            cost_matrix: Some(cost_matrix),
+            ..self
+        })
+    }
+
+    pub fn cost_matrix(&self) -> Option<&[Vec<f32>]> {
+        self.cost_matrix.as_deref()
+    }
+
+    /// The predicted class, with ties resolved by `tie_break` (-1 if they remain ambiguous or
+    /// the program abstains).
+    pub fn predict(&self, registers: &Registers) -> i32 {
+        Self::predict_with(registers, self.tie_break, self.abstention)
+    }
+
+    fn predict_with(
+        registers: &Registers,
+        tie_break: TieBreak,
+        abstention: Option<Abstention>,
+    ) -> i32 {
+        let ties = registers
+            .argmax_outputs()
+            .unwrap_or_else(|error| panic!("{}", error));
+
+        let class = match tie_break {
+            _ if ties.len() == 1 => ties.first(),
+            _ if abstention.is_some() => None,
+            TieBreak::None => None,
+            TieBreak::FirstIndex => ties.first(),
+            TieBreak::Random => ties.choose(&mut generator()),
+        };
+
+        let class = class.filter(|&&class| match abstention {
+            Some(Abstention { threshold, .. }) => *registers.get(class) >= threshold,
+            None => true,
+        });
+
+        class.map_or(-1, |class| *class as i32)
+    }
 }
 
 impl<T> ExtensionParameters for ClassificationParameters<T>
 where
     T: ClassificationInput,
 {
+    const N_OUTPUT_REGISTERS: usize = T::N_ACTION_REGISTERS;
+    const N_INPUT_REGISTERS: usize = T::N_INPUT_REGISTERS;
+
+    /// `predict` under the default settings, so ties count as no prediction (-1).
     fn argmax(registers: &Registers) -> i32 {
-        let action_registers = &registers[0..T::N_ACTION_REGISTERS];
-        let max_value = action_registers
-            .into_iter()
-            .copied()
-            .reduce(|a, b| f32::max(a, b))
-            .unwrap();
+        Self::predict_with(registers, TieBreak::default(), None)
+    }
 
-        let mut indices = action_registers
-            .into_iter()
-            .enumerate()
-            .filter(|(_, value)| **value == max_value)
-            .map(|(index, _)| index)
-            .collect_vec();
+    fn feature_names() -> Option<Vec<&'static str>> {
+        T::feature_names()
+    }
+}
 
-        if indices.len() > 1 {
-            -1
-        } else {
-            indices.remove(0) as i32
-        }
+/// Recall of each class present in the evaluated inputs, keyed by class.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PerClassAccuracy(BTreeMap<usize, f32>);
+
+impl PerClassAccuracy {
+    /// The fraction of `class`' inputs labelled correctly, if the class was evaluated at all.
+    pub fn recall(&self, class: usize) -> Option<f32> {
+        self.0.get(&class).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&usize, &f32)> {
+        self.0.iter()
     }
 }
 
+/// The scalar fitness (per `FitnessMetric`) alongside the accuracy breakdown.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DetailedFitness {
+    pub fitness: FitnessScore,
+    pub accuracy: f32,
+    pub per_class_accuracy: PerClassAccuracy,
+}
+
 pub trait ClassificationInput: ValidInput {
     fn get_class(&self) -> usize;
 }
 
-impl<T> Fitness for Program<ClassificationParameters<T>>
+/// Checks that every label maps to one of the `N_ACTION_REGISTERS` classes.
+pub fn validate_classes<T>(inputs: &Inputs<T>) -> Result<(), DatasetError>
 where
     T: ClassificationInput,
 {
-    type FitnessParameters = ClassificationParameters<T>;
+    for (row, input) in inputs.iter().enumerate() {
+        let class = input.get_class();
+        if class >= T::N_ACTION_REGISTERS {
+            return Err(DatasetError::ClassOutOfRange {
+                row,
+                class,
+                n_classes: T::N_ACTION_REGISTERS,
+            });
+        }
+    }
 
-    fn eval_fitness(
-        &mut self,
-        parameters: &mut Self::FitnessParameters,
-    ) -> crate::core::characteristics::FitnessScore {
-        let inputs = &parameters.inputs;
+    Ok(())
+}
+
+impl<T> Program<ClassificationParameters<T>>
+where
+    T: ClassificationInput,
+{
+    /// Runs the program once on `input`, returning the class `parameters` predict (`None` when
+    /// the prediction is ambiguous or withheld).
+    pub fn predict(&self, input: &T, parameters: &ClassificationParameters<T>) -> Option<usize> {
+        let mut program = self.clone();
+        program.exec(input);
+
+        usize::try_from(parameters.predict(&program.registers)).ok()
+    }
+
+    /// The predicted class for every input (-1 when the prediction is ambiguous).
+    pub fn predictions(&self, parameters: &ClassificationParameters<T>) -> Vec<i32> {
+        let mut program = self.clone();
+
+        parameters
+            .inputs
+            .iter()
+            .map(|input| {
+                program.exec(input);
+                let predicted_class = parameters.predict(&program.registers);
+                program.registers.reset();
+                predicted_class
+            })
+            .collect()
+    }
+
+    /// Fraction of each class' inputs that the program labels correctly (0 for absent classes).
+    pub fn per_class_accuracy(&self, parameters: &ClassificationParameters<T>) -> Vec<f32> {
+        let per_class_accuracy = self.eval_detailed(parameters).per_class_accuracy;
+
+        (0..T::N_ACTION_REGISTERS)
+            .map(|class| per_class_accuracy.recall(class).unwrap_or(0.))
+            .collect()
+    }
+
+    /// Evaluates the program like `eval_fitness`, additionally reporting per-class accuracy.
+    /// The program's own fitness is left untouched.
+    pub fn eval_detailed(&self, parameters: &ClassificationParameters<T>) -> DetailedFitness {
+        self.clone().evaluate(parameters)
+    }
 
-        let mut n_correct = 0;
+    /// Scores the program like `eval_fitness`, but over `inputs` instead of `parameters`' own
+    /// inputs. Inputs are consumed one at a time, so they never need to be held in memory
+    /// together. The program's own fitness is left untouched.
+    pub fn eval_fitness_streaming<I>(
+        &self,
+        parameters: &ClassificationParameters<T>,
+        inputs: I,
+    ) -> FitnessScore
+    where
+        I: Iterator<Item = T>,
+    {
+        self.clone().evaluate_over(parameters, inputs).fitness
+    }
+
+    fn evaluate(&mut self, parameters: &ClassificationParameters<T>) -> DetailedFitness {
+        self.evaluate_over(parameters, parameters.inputs.iter())
+    }
+
+    fn evaluate_over<I>(
+        &mut self,
+        parameters: &ClassificationParameters<T>,
+        inputs: impl Iterator<Item = I>,
+    ) -> DetailedFitness
+    where
+        I: Borrow<T>,
+    {
+        let mut n_inputs = 0;
+        let mut n_correct = BTreeMap::<usize, usize>::new();
+        let mut n_total = BTreeMap::<usize, usize>::new();
+        let mut n_predicted = BTreeMap::<usize, usize>::new();
+        let mut total_cost = 0.;
+        let mut n_abstained = 0;
 
         for input in inputs {
+            let input = input.borrow();
+            n_inputs += 1;
             self.exec(input);
 
-            let predicted_class = ClassificationParameters::<T>::argmax(&self.registers);
-            let correct_class = input.get_class() as i32;
+            let predicted_class = parameters.predict(&self.registers);
+            let correct_class = input.get_class();
+
+            if predicted_class >= 0 {
+                *n_predicted.entry(predicted_class as usize).or_default() += 1;
+            } else {
+                n_abstained += 1;
+            }
+
+            if let Some(cost_matrix) = &parameters.cost_matrix {
+                let costs = &cost_matrix[correct_class];
+                total_cost += match predicted_class {
+                    -1 => costs.iter().copied().fold(0., f32::max),
+                    predicted_class => costs[predicted_class as usize],
+                };
+            }
+
+            *n_total.entry(correct_class).or_default() += 1;
+            let class_correct = n_correct.entry(correct_class).or_default();
 
-            if predicted_class == correct_class {
-                n_correct += 1;
+            if predicted_class == correct_class as i32 {
+                *class_correct += 1;
             }
 
             self.registers.reset();
         }
 
-        let fitness = n_correct as f32 / inputs.len() as f32;
+        let accuracy = n_correct.values().sum::<usize>() as f32 / n_inputs as f32;
+
+        let per_class_accuracy = PerClassAccuracy(
+            n_correct
+                .iter()
+                .map(|(&class, &correct)| (class, correct as f32 / n_total[&class] as f32))
+                .collect(),
+        );
+
+        let score = match (&parameters.cost_matrix, parameters.metric) {
+            (Some(_), _) => 1. / (1. + total_cost / n_inputs as f32),
+            (None, FitnessMetric::Accuracy) => match parameters.abstention {
+                Some(Abstention { reward, .. }) => {
+                    accuracy + reward * n_abstained as f32 / n_inputs as f32
+                }
+                None => accuracy,
+            },
+            (None, FitnessMetric::BalancedAccuracy) => {
+                per_class_accuracy
+                    .iter()
+                    .map(|(_, recall)| recall)
+                    .sum::<f32>()
+                    / n_total.len() as f32
+            }
+            (None, FitnessMetric::F1Macro) => {
+                let f1_scores = per_class_accuracy.iter().map(|(class, &recall)| {
+                    let precision = match n_predicted.get(class) {
+                        Some(&predicted) => n_correct[class] as f32 / predicted as f32,
+                        None => 0.,
+                    };
+
+                    if precision + recall == 0. {
+                        0.
+                    } else {
+                        2. * precision * recall / (precision + recall)
+                    }
+                });
+
+                f1_scores.sum::<f32>() / n_total.len() as f32
+            }
+        };
+
+        let penalty = parameters.parsimony_coefficient * self.instructions.len() as f32;
+        let fitness = f32::max(score - penalty, 0.);
+
+        DetailedFitness {
+            fitness,
+            accuracy,
+            per_class_accuracy,
+        }
+    }
+
+    /// Hashes the program's predictions so behaviorally identical programs share a key.
+    pub fn behavior_hash(&self, parameters: &ClassificationParameters<T>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.predictions(parameters).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Emits a standalone `fn fn_name(inputs: &[f32]) -> usize` returning the class `predict`
+    /// returns under default parameters, bit for bit: results are sanitized (and clamped when
+    /// the registers are), a failing branch skips what it guards, and ties yield `usize::MAX`
+    /// as `TieBreak::None` yields no class. Input indices wrap around `inputs`, which must not
+    /// be empty. Executables missing from `NAMED_EXECUTABLES` are emitted as calls to an
+    /// `op(a, b)` the caller must define.
+    pub fn to_rust_fn(&self, fn_name: &str) -> String {
+        // Register indices wrap as in `Instruction::apply`. Wrapping into an empty range keeps
+        // input indices as they are, so they wrap around `inputs` at runtime instead.
+        let instructions = self
+            .instructions
+            .iter()
+            .map(|instruction| instruction.wrapped(self.registers.len(), 0))
+            .collect::<Vec<_>>();
+        let clamp = self.registers.clamp();
+
+        let mut code = format!(
+            concat!(
+                "fn {}(inputs: &[f32]) -> usize {{\n",
+                "    fn sanitize(value: f32) -> f32 {{\n",
+                "        if value.is_nan() {{ 0.0 }} else {{ value.clamp(f32::MIN, f32::MAX) }}\n",
+                "    }}\n",
+            ),
+            fn_name
+        );
+
+        if let Some((min, max)) = clamp {
+            code.push_str(&format!(
+                concat!(
+                    "    fn clamp(r: &mut [f32]) {{\n",
+                    "        for value in r.iter_mut() {{ *value = value.clamp({}, {}); }}\n",
+                    "    }}\n",
+                ),
+                rust_literal(min),
+                rust_literal(max)
+            ));
+        }
+
+        for executable in instructions
+            .iter()
+            .filter(|instruction| *instruction.mode() != Mode::Branch)
+            .map(|instruction| instruction.executable())
+            .unique_by(|executable| *executable as usize)
+        {
+            if let (Some(name), Some(expression)) =
+                (executable_name(executable), rust_expression(executable))
+            {
+                code.push_str(&format!(
+                    concat!(
+                        "    #[allow(unused_variables)]\n",
+                        "    fn {}(a: f32, b: f32) -> f32 {{\n",
+                        "        {}\n",
+                        "    }}\n",
+                    ),
+                    name, expression
+                ));
+            }
+        }
+
+        if instructions
+            .iter()
+            .any(|instruction| *instruction.mode() == Mode::External)
+        {
+            code.push_str("    let input = |index: usize| inputs[index % inputs.len()];\n");
+        }
+
+        code.push_str(&format!(
+            "    let mut r: [f32; {}] = [{}];\n",
+            self.registers.len(),
+            self.registers
+                .initial()
+                .iter()
+                .map(|value| rust_literal(*value))
+                .join(", ")
+        ));
+
+        // A chain of branches guards the next instruction with all of its conditions, checked in
+        // order. Branches at the end guard nothing and are left out.
+        let mut conditions = vec![];
+
+        for instruction in &instructions {
+            let source = format!("r[{}]", instruction.source_index());
+            let target = match instruction.mode() {
+                Mode::Internal | Mode::Branch => format!("r[{}]", instruction.target_index()),
+                Mode::External => format!("input({})", instruction.target_index()),
+            };
+
+            if *instruction.mode() == Mode::Branch {
+                conditions.push(format!("{} < {}", source, target));
+                continue;
+            }
+
+            let indent = match conditions.is_empty() {
+                true => "    ",
+                false => "        ",
+            };
+
+            if !conditions.is_empty() {
+                code.push_str(&format!("    if {} {{\n", conditions.join(" && ")));
+            }
+
+            code.push_str(&format!(
+                "{}{} = sanitize({}({}, {}));\n",
+                indent,
+                source,
+                executable_name(instruction.executable()).unwrap_or("op"),
+                source,
+                target
+            ));
+
+            if clamp.is_some() {
+                code.push_str(&format!("{}clamp(&mut r);\n", indent));
+            }
+
+            if !conditions.is_empty() {
+                code.push_str("    }\n");
+                conditions.clear();
+            }
+        }
+
+        code.push_str(&format!(
+            "    let outputs = &r[..{}];\n",
+            ClassificationParameters::<T>::N_OUTPUT_REGISTERS
+        ));
+        code.push_str(concat!(
+            "    let max = outputs.iter().copied().fold(f32::NAN, f32::max);\n",
+            "    let mut winners = (0..outputs.len()).filter(|&i| outputs[i] == max);\n",
+            "    match (winners.next(), winners.next()) {\n",
+            "        (Some(class), None) => class,\n",
+            "        _ => usize::MAX,\n",
+            "    }\n",
+            "}\n",
+        ));
+
+        code
+    }
+}
+
+/// Renders `value` as a Rust expression evaluating to the same value.
+fn rust_literal(value: R32) -> String {
+    match value {
+        value if value.is_nan() => "f32::NAN".to_string(),
+        R32::INFINITY => "f32::INFINITY".to_string(),
+        R32::NEG_INFINITY => "f32::NEG_INFINITY".to_string(),
+        value => format!("{:?}", value),
+    }
+}
+
+impl<T> Fitness for Program<ClassificationParameters<T>>
+where
+    T: ClassificationInput,
+{
+    type FitnessParameters = ClassificationParameters<T>;
+
+    fn eval_fitness(
+        &mut self,
+        parameters: &mut Self::FitnessParameters,
+    ) -> crate::core::characteristics::FitnessScore {
+        let DetailedFitness { fitness, .. } = self.evaluate(parameters);
 
         self.fitness = Some(fitness);
 
@@ -87,4 +608,556 @@ where
     fn get_fitness(&self) -> Option<crate::core::characteristics::FitnessScore> {
         self.fitness
     }
+
+    fn set_fitness(&mut self, fitness: FitnessScore, _variance: Option<FitnessScore>) {
+        self.fitness = Some(fitness);
+    }
+
+    /// Fitness against program length (negated, so shorter is better).
+    fn objectives(&self) -> Option<Vec<crate::core::characteristics::FitnessScore>> {
+        self.fitness.map(|fitness| {
+            vec![
+                Self::DIRECTION.gain(fitness),
+                -(self.instructions.len() as f32),
+            ]
+        })
+    }
+
+    fn fitness_key(&self) -> Option<u64> {
+        Some(self.structural_hash())
+    }
+
+    /// Hashes the inputs and every setting affecting the score.
+    fn parameters_key(parameters: &Self::FitnessParameters) -> Option<u64> {
+        let ClassificationParameters {
+            inputs,
+            parsimony_coefficient,
+            tie_break,
+            metric,
+            cost_matrix,
+            abstention,
+        } = parameters;
+        let settings = serde_json::to_vec(&(
+            parsimony_coefficient,
+            tie_break,
+            metric,
+            cost_matrix,
+            abstention,
+        ))
+        .ok()?;
+
+        let mut hasher = DefaultHasher::new();
+        settings.hash(&mut hasher);
+        for input in inputs {
+            for value in input.flat() {
+                value.to_bits().hash(&mut hasher);
+            }
+            input.get_class().hash(&mut hasher);
+        }
+
+        Some(hasher.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{distributions::Standard, Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{
+        core::{
+            algorithm::GeneticAlgorithm,
+            characteristics::{Fitness, Generate},
+            fitness_cache::FitnessCache,
+            inputs::{DatasetError, ValidInput},
+            instruction::{Instruction, InstructionGeneratorParameters, Mode},
+            instructions::Instructions,
+            population::Population,
+            program::{Program, ProgramGeneratorParameters},
+            registers::Registers,
+        },
+        utils::{
+            executables::{add, divide, multiply},
+            random::generator,
+            test::{TestInput, TestLgp},
+        },
+    };
+
+    use itertools::Itertools;
+
+    use super::{
+        validate_classes, Abstention, ClassificationParameters, CostMatrixError, FitnessMetric,
+        TieBreak,
+    };
+
+    fn program_from(
+        instructions: Vec<Instruction>,
+    ) -> Program<ClassificationParameters<TestInput>> {
+        Program::new(
+            instructions.into_iter().collect::<Instructions>(),
+            Registers::new(3),
+            None,
+        )
+    }
+
+    #[test]
+    fn given_changed_parameters_when_rank_cached_then_cached_scores_are_dropped() {
+        let inputs = [0; 10].map(|_| generator().sample(Standard)).to_vec();
+        let mut parameters = ClassificationParameters::new(inputs);
+        let program = Program::generate(&ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        ));
+        let clones = || {
+            let mut population = Population::with_capacity(2);
+            population.extend([program.clone(), program.clone()]);
+            population
+        };
+        let mut cache = FitnessCache::new();
+
+        TestLgp::rank_cached(&mut clones(), &mut parameters, &mut cache);
+        TestLgp::rank_cached(&mut clones(), &mut parameters, &mut cache);
+        pretty_assertions::assert_eq!(cache.misses(), 1);
+
+        parameters.parsimony_coefficient = 0.1;
+        TestLgp::rank_cached(&mut clones(), &mut parameters, &mut cache);
+        pretty_assertions::assert_eq!(cache.misses(), 2);
+        pretty_assertions::assert_eq!(cache.hits(), 4);
+    }
+
+    #[test]
+    fn given_programs_with_identical_predictions_when_behavior_hash_then_hashes_are_equal() {
+        let inputs = [0; 10].map(|_| generator().sample(Standard)).to_vec();
+        let parameters = ClassificationParameters::new(inputs);
+
+        // Neither program touches the action registers.
+        let program_a = program_from(vec![Instruction::new(2, 0, Mode::External, add)]);
+        let program_b = program_from(vec![Instruction::new(2, 1, Mode::External, multiply)]);
+        // Writes a positive feature into the first action register.
+        let program_c = program_from(vec![Instruction::new(0, 0, Mode::External, add)]);
+
+        assert_ne!(program_a, program_b);
+
+        pretty_assertions::assert_eq!(
+            program_a.behavior_hash(&parameters),
+            program_b.behavior_hash(&parameters)
+        );
+        assert_ne!(
+            program_a.behavior_hash(&parameters),
+            program_c.behavior_hash(&parameters)
+        );
+    }
+
+    #[test]
+    fn given_parsimony_coefficient_when_rank_then_shorter_program_with_equal_accuracy_wins() {
+        let inputs = [0; 10].map(|_| generator().sample(Standard)).to_vec();
+        let mut parameters = ClassificationParameters::new(inputs);
+        parameters.parsimony_coefficient = 0.01;
+
+        let short_program = program_from(vec![Instruction::new(0, 0, Mode::External, add)]);
+        let long_program = program_from(vec![
+            Instruction::new(0, 0, Mode::External, add),
+            Instruction::new(2, 1, Mode::External, add),
+        ]);
+
+        let mut population = Population::with_capacity(2);
+        population.push(long_program.clone());
+        population.push(short_program.clone());
+
+        TestLgp::rank(&mut population, &mut parameters);
+
+        let [best, worst] = [population.first(), population.last()].map(|p| p.unwrap());
+
+        pretty_assertions::assert_eq!(best.instructions, short_program.instructions);
+        pretty_assertions::assert_eq!(worst.instructions, long_program.instructions);
+        assert!(best.fitness > worst.fitness);
+        assert!((best.fitness.unwrap() - worst.fitness.unwrap() - 0.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn given_identical_instructions_when_initial_registers_differ_then_predictions_differ() {
+        let inputs = [0; 10].map(|_| generator().sample(Standard)).to_vec();
+        let parameters = ClassificationParameters::new(inputs);
+
+        // Only touches the scratch register, so the initial values decide the class.
+        let instructions: Instructions = [Instruction::new(2, 0, Mode::External, add)]
+            .into_iter()
+            .collect();
+
+        let program_a = Program::<ClassificationParameters<TestInput>>::new(
+            instructions.clone(),
+            Registers::from(vec![1., 0., 0.]),
+            None,
+        );
+        let program_b = Program::<ClassificationParameters<TestInput>>::new(
+            instructions,
+            Registers::from(vec![0., 1., 0.]),
+            None,
+        );
+
+        pretty_assertions::assert_eq!(program_a.predictions(&parameters), vec![0; 10]);
+        pretty_assertions::assert_eq!(program_b.predictions(&parameters), vec![1; 10]);
+    }
+
+    /// `to_rust_fn`'s output for the program in the test below, compiled into the tests.
+    mod emitted {
+        include!("../../assets/tests/classify.rs");
+
+        pub fn predict(inputs: &[f32]) -> usize {
+            classify(inputs)
+        }
+    }
+
+    #[test]
+    fn given_program_when_to_rust_fn_then_emitted_function_predicts_like_the_program() {
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            [
+                // Input index 5 wraps to feature 1.
+                Instruction::new(0, 5, Mode::External, add),
+                Instruction::new(0, 2, Mode::Branch, add),
+                // Protected: R[0] is 0 for a zero feature.
+                Instruction::new(1, 0, Mode::Internal, divide),
+                Instruction::new(0, 2, Mode::External, multiply),
+                Instruction::new(1, 0, Mode::Branch, add),
+                Instruction::new(2, 0, Mode::Branch, add),
+                Instruction::new(1, 2, Mode::Internal, add),
+            ]
+            .into_iter()
+            .collect(),
+            Registers::from(vec![0., 0.5, 1.]).with_clamp(Some((-4., 4.))),
+            None,
+        );
+        let parameters = ClassificationParameters::<TestInput>::new(vec![]);
+
+        pretty_assertions::assert_eq!(
+            program.to_rust_fn("classify"),
+            include_str!("../../assets/tests/classify.rs")
+        );
+
+        let edge_cases = [
+            // Dividing by zero leaves R[1] at 0.5.
+            TestInput([0., 0., 0., 0., 0.]),
+            // R[0] and R[1] tie at 1.
+            TestInput([0., 0.5, 2., 0., 0.]),
+            // R[0] and R[1] tie once both are clamped to 4.
+            TestInput([0., 0.1, 100., 0., 0.]),
+            // The first branch fails, the nested ones hold.
+            TestInput([0., 2., 3., 0., 0.]),
+        ];
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let inputs = edge_cases.into_iter().chain((0..100).map(|_| {
+            let input: TestInput = rng.sample(Standard);
+            TestInput(input.0.map(|value| value * 4. - 2.))
+        }));
+
+        for input in inputs {
+            pretty_assertions::assert_eq!(
+                emitted::predict(&input.flat()),
+                program.predict(&input, &parameters).unwrap_or(usize::MAX),
+                "{:?}",
+                input.flat()
+            );
+        }
+    }
+
+    #[test]
+    fn given_tied_registers_when_predict_then_each_tie_break_strategy_is_applied() {
+        let mut parameters = ClassificationParameters::<TestInput>::new(vec![]);
+        let tied = Registers::from(vec![1., 1., 5.]).with_n_outputs(Some(2));
+        let untied = Registers::from(vec![1., 2., 5.]).with_n_outputs(Some(2));
+
+        let mut predict_with = |tie_break| {
+            parameters.tie_break = tie_break;
+            (0..50)
+                .map(|_| parameters.predict(&tied))
+                .unique()
+                .sorted()
+                .collect_vec()
+        };
+
+        pretty_assertions::assert_eq!(predict_with(TieBreak::None), vec![-1]);
+        pretty_assertions::assert_eq!(predict_with(TieBreak::FirstIndex), vec![0]);
+        pretty_assertions::assert_eq!(predict_with(TieBreak::Random), vec![0, 1]);
+
+        for tie_break in [TieBreak::None, TieBreak::FirstIndex, TieBreak::Random] {
+            parameters.tie_break = tie_break;
+            pretty_assertions::assert_eq!(parameters.predict(&untied), 1);
+        }
+    }
+
+    #[test]
+    fn given_evolved_program_when_saved_and_loaded_then_predictions_match(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let inputs: Vec<TestInput> = [0; 20].map(|_| generator().sample(Standard)).to_vec();
+        let mut parameters = ClassificationParameters::new(inputs.clone());
+        let program_parameters = ProgramGeneratorParameters::new(
+            20,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+
+        let mut population = Population::with_capacity(10);
+        for _ in 0..10 {
+            population.push(Program::generate(&program_parameters));
+        }
+        TestLgp::rank(&mut population, &mut parameters);
+        let best = population.first().unwrap();
+
+        let file = tempfile::NamedTempFile::new()?;
+        best.save(file.path())?;
+        let loaded = Program::<ClassificationParameters<TestInput>>::load(file.path())?;
+
+        pretty_assertions::assert_eq!(&loaded, best);
+
+        for input in inputs.iter() {
+            pretty_assertions::assert_eq!(
+                loaded.predict(input, &parameters),
+                best.predict(input, &parameters)
+            );
+        }
+
+        let predictions = best.predictions(&parameters);
+        for (input, prediction) in inputs.iter().zip(predictions) {
+            let expected = usize::try_from(prediction).ok();
+            pretty_assertions::assert_eq!(loaded.predict(input, &parameters), expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn given_class_that_is_never_predicted_when_eval_detailed_then_its_accuracy_is_zero() {
+        // Three inputs of class 0 and one of class 1.
+        let inputs = [0., 0., 0., 1.]
+            .map(|class| TestInput([0., 0., 0., 0., class]))
+            .to_vec();
+        let parameters = ClassificationParameters::new(inputs);
+
+        // Adds the scratch register into the first output, so class 0 always wins.
+        let mut registers = Registers::new(3);
+        registers.set_initial(2, 1.);
+        registers.reset();
+        let program = Program::<ClassificationParameters<TestInput>>::new(
+            [Instruction::new(0, 2, Mode::Internal, add)]
+                .into_iter()
+                .collect(),
+            registers,
+            None,
+        );
+
+        let detailed = program.eval_detailed(&parameters);
+
+        pretty_assertions::assert_eq!(detailed.per_class_accuracy.recall(0), Some(1.));
+        pretty_assertions::assert_eq!(detailed.per_class_accuracy.recall(1), Some(0.));
+        pretty_assertions::assert_eq!(detailed.accuracy, 0.75);
+        pretty_assertions::assert_eq!(detailed.fitness, 0.75);
+        pretty_assertions::assert_eq!(program.per_class_accuracy(&parameters), vec![1., 0.]);
+    }
+
+    #[test]
+    fn given_imbalanced_inputs_when_majority_predictor_is_scored_then_balanced_accuracy_is_half() {
+        // Nine inputs of class 0 and one of class 1.
+        let inputs = [0., 0., 0., 0., 0., 0., 0., 0., 0., 1.]
+            .map(|class| TestInput([0., 0., 0., 0., class]))
+            .to_vec();
+        let mut parameters = ClassificationParameters::new(inputs);
+        pretty_assertions::assert_eq!(parameters.metric, FitnessMetric::Accuracy);
+
+        // Always predicts class 0.
+        let mut registers = Registers::new(3);
+        registers.set_initial(0, 1.);
+        registers.reset();
+        let mut program = Program::<ClassificationParameters<TestInput>>::new(
+            Instructions::new(),
+            registers,
+            None,
+        );
+
+        let accuracy = program.eval_fitness(&mut parameters);
+
+        parameters.metric = FitnessMetric::BalancedAccuracy;
+        let balanced_accuracy = program.eval_fitness(&mut parameters);
+
+        parameters.metric = FitnessMetric::F1Macro;
+        let f1_macro = program.eval_fitness(&mut parameters);
+
+        assert!((accuracy - 0.9).abs() < 1e-6);
+        assert!((balanced_accuracy - 0.5).abs() < 1e-6);
+        // Class 0: precision 0.9, recall 1; class 1: never predicted.
+        assert!((f1_macro - (2. * 0.9 / 1.9) / 2.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn given_steep_off_diagonal_cost_when_rank_then_program_avoiding_that_confusion_wins() {
+        // Five inputs of class 0 and four of class 1.
+        let inputs = [0., 0., 0., 0., 0., 1., 1., 1., 1.]
+            .map(|class| TestInput([0., 0., 0., 0., class]))
+            .to_vec();
+        // Mistaking class 1 for class 0 is ten times worse than the converse.
+        let mut parameters = ClassificationParameters::new(inputs)
+            .with_cost_matrix(vec![vec![0., 1.], vec![10., 0.]])
+            .unwrap();
+
+        let constant_predictor = |class: usize| {
+            let mut registers = Registers::new(3);
+            registers.set_initial(class, 1.);
+            registers.reset();
+            Program::<ClassificationParameters<TestInput>>::new(
+                Instructions::new(),
+                registers,
+                None,
+            )
+        };
+        // More accurate (5/9), but makes the costly confusion.
+        let majority_predictor = constant_predictor(0);
+        // Less accurate (4/9), but only makes the cheap confusion.
+        let cautious_predictor = constant_predictor(1);
+
+        let mut population = Population::with_capacity(2);
+        population.push(majority_predictor);
+        population.push(cautious_predictor);
+
+        TestLgp::rank(&mut population, &mut parameters);
+
+        let best = population.first().unwrap();
+        pretty_assertions::assert_eq!(best.predictions(&parameters)[0], 1);
+        pretty_assertions::assert_eq!(best.fitness, Some(1. / (1. + 5. / 9.)));
+        pretty_assertions::assert_eq!(
+            population.last().unwrap().fitness,
+            Some(1. / (1. + 40. / 9.))
+        );
+    }
+
+    #[test]
+    fn given_misshapen_cost_matrix_when_with_cost_matrix_then_error_is_returned() {
+        let parameters = ClassificationParameters::<TestInput>::new(vec![]);
+
+        pretty_assertions::assert_eq!(
+            parameters
+                .clone()
+                .with_cost_matrix(vec![vec![0., 1.]])
+                .err(),
+            Some(CostMatrixError::RowCount {
+                expected: 2,
+                found: 1
+            })
+        );
+        pretty_assertions::assert_eq!(
+            parameters
+                .clone()
+                .with_cost_matrix(vec![vec![0., 1.], vec![1.]])
+                .err(),
+            Some(CostMatrixError::ColumnCount {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        pretty_assertions::assert_eq!(
+            parameters
+                .with_cost_matrix(vec![vec![0., 1.], vec![1., 0.]])
+                .unwrap()
+                .cost_matrix(),
+            Some(&[vec![0., 1.], vec![1., 0.]][..])
+        );
+    }
+
+    #[test]
+    fn given_extra_registers_when_generate_and_predict_then_scratch_registers_are_used_but_not_read(
+    ) {
+        let params = ProgramGeneratorParameters::new(
+            20,
+            InstructionGeneratorParameters::from::<TestInput>(3),
+        );
+        let programs = (0..50)
+            .map(|_| Program::<ClassificationParameters<TestInput>>::generate(&params))
+            .collect_vec();
+
+        assert!(programs
+            .iter()
+            .all(|program| program.registers.len() == TestInput::N_ACTION_REGISTERS + 3));
+        assert!(programs
+            .iter()
+            .flat_map(|program| program.instructions.iter())
+            .any(|instruction| instruction.source_index() >= TestInput::N_ACTION_REGISTERS));
+
+        // The scratch registers hold the largest values, yet only the outputs decide the class.
+        let parameters = ClassificationParameters::new(vec![TestInput::default()]);
+        let registers = Registers::from(vec![0., 1., 100., 200., 300.]).with_n_outputs(Some(2));
+        pretty_assertions::assert_eq!(parameters.predict(&registers), 1);
+    }
+
+    #[test]
+    fn given_lazily_generated_inputs_when_eval_fitness_streaming_then_score_matches_batch_evaluation(
+    ) {
+        let params = ProgramGeneratorParameters::new(
+            20,
+            InstructionGeneratorParameters::from::<TestInput>(1),
+        );
+        let seed_inputs = |seed: u64| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            (0..200).map(move |index| {
+                let mut input: TestInput = rng.sample(Standard);
+                input.0[4] = (index % 2) as f32;
+                input
+            })
+        };
+
+        let mut parameters = ClassificationParameters::new(seed_inputs(7).collect_vec());
+        let mut program = Program::<ClassificationParameters<TestInput>>::generate(&params);
+
+        let streamed = program.eval_fitness_streaming(&parameters, seed_inputs(7));
+        let batch = program.eval_fitness(&mut parameters);
+
+        pretty_assertions::assert_eq!(streamed, batch);
+    }
+
+    #[test]
+    fn given_rising_abstention_threshold_when_eval_fitness_then_abstentions_replace_wrong_predictions(
+    ) {
+        // Class 0 wins with a register value equal to the first feature; every input is class 1,
+        // so every prediction is wrong.
+        let inputs = [0.2, 0.4, 0.6, 0.8]
+            .map(|feature| TestInput([feature, 0., 0., 0., 1.]))
+            .to_vec();
+        let mut program = program_from(vec![Instruction::new(0, 0, Mode::External, add)]);
+
+        let evaluate = |program: &mut Program<ClassificationParameters<TestInput>>,
+                        threshold: f32| {
+            let mut parameters = ClassificationParameters::new(inputs.clone());
+            parameters.abstention = Some(Abstention::new(threshold, 0.5));
+
+            let n_abstained = program
+                .predictions(&parameters)
+                .into_iter()
+                .filter(|&class| class == -1)
+                .count();
+            (n_abstained, program.eval_fitness(&mut parameters))
+        };
+
+        pretty_assertions::assert_eq!(evaluate(&mut program, 0.), (0, 0.));
+        pretty_assertions::assert_eq!(evaluate(&mut program, 0.5), (2, 0.25));
+        pretty_assertions::assert_eq!(evaluate(&mut program, 1.), (4, 0.5));
+    }
+
+    #[test]
+    fn given_a_label_without_an_action_register_when_classes_are_validated_then_the_row_is_reported(
+    ) {
+        let mut inputs: Vec<TestInput> = vec![generator().sample(Standard); 3];
+        for input in inputs.iter_mut() {
+            input.0[4] = 0.;
+        }
+        pretty_assertions::assert_eq!(validate_classes(&inputs), Ok(()));
+
+        inputs[2].0[4] = TestInput::N_ACTION_REGISTERS as f32;
+        let error = DatasetError::ClassOutOfRange {
+            row: 2,
+            class: TestInput::N_ACTION_REGISTERS,
+            n_classes: TestInput::N_ACTION_REGISTERS,
+        };
+        pretty_assertions::assert_eq!(validate_classes(&inputs), Err(error.clone()));
+        pretty_assertions::assert_eq!(
+            ClassificationParameters::try_new(inputs).unwrap_err(),
+            error
+        );
+    }
 }
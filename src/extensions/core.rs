@@ -1,5 +1,15 @@
 use crate::core::registers::Registers;
 
 pub trait ExtensionParameters {
+    /// Number of leading registers read as the program's output.
+    const N_OUTPUT_REGISTERS: usize;
+    /// Number of input features instructions may read in `Mode::External`.
+    const N_INPUT_REGISTERS: usize;
+
     fn argmax(registers: &Registers) -> i32;
+
+    /// See `ValidInput::feature_names`.
+    fn feature_names() -> Option<Vec<&'static str>> {
+        None
+    }
 }
@@ -1,3 +1,5 @@
 pub mod classification;
 pub mod core;
+pub mod multi_label;
+pub mod regression;
 pub mod reinforcement_learning;
@@ -0,0 +1,242 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet},
+    hash::{Hash, Hasher},
+};
+
+use derive_new::new;
+use serde::Serialize;
+
+use crate::core::{
+    characteristics::{Fitness, FitnessScore},
+    inputs::{Inputs, ValidInput},
+    program::Program,
+    registers::{Registers, R32},
+};
+
+use super::core::ExtensionParameters;
+
+/// How predicted label sets are scored against the true ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Default)]
+pub enum MultiLabelMetric {
+    /// Fraction of individual labels, over all inputs, predicted correctly.
+    #[default]
+    Hamming,
+    /// Fraction of inputs whose whole label set is predicted exactly.
+    Subset,
+}
+
+/// Inputs that may belong to several classes at once. Each of the `N_ACTION_REGISTERS` output
+/// registers decides one label.
+pub trait MultiLabelInput: ValidInput {
+    fn get_classes(&self) -> Vec<usize>;
+}
+
+#[derive(Clone, Debug, Serialize, new)]
+pub struct MultiLabelParameters<InputType>
+where
+    InputType: MultiLabelInput,
+{
+    inputs: Inputs<InputType>,
+    #[new(default)]
+    pub metric: MultiLabelMetric,
+}
+
+impl<T> MultiLabelParameters<T>
+where
+    T: MultiLabelInput,
+{
+    /// A label is predicted when the sigmoid of its output register exceeds 0.5.
+    pub fn predict(registers: &Registers) -> BTreeSet<usize> {
+        (0..T::N_ACTION_REGISTERS)
+            .filter(|label| sigmoid(*registers.get(*label)) > 0.5)
+            .collect()
+    }
+}
+
+impl<T> ExtensionParameters for MultiLabelParameters<T>
+where
+    T: MultiLabelInput,
+{
+    const N_OUTPUT_REGISTERS: usize = T::N_ACTION_REGISTERS;
+    const N_INPUT_REGISTERS: usize = T::N_INPUT_REGISTERS;
+
+    fn argmax(registers: &Registers) -> i32 {
+        let mut indices = registers
+            .argmax_outputs()
+            .unwrap_or_else(|error| panic!("{}", error));
+
+        if indices.len() > 1 {
+            -1
+        } else {
+            indices.remove(0) as i32
+        }
+    }
+
+    fn feature_names() -> Option<Vec<&'static str>> {
+        T::feature_names()
+    }
+}
+
+fn sigmoid(value: R32) -> R32 {
+    1. / (1. + (-value).exp())
+}
+
+impl<T> Program<MultiLabelParameters<T>>
+where
+    T: MultiLabelInput,
+{
+    /// Runs the program once on `input`, returning the predicted labels.
+    pub fn predict_labels(&self, input: &T) -> BTreeSet<usize> {
+        let mut program = self.clone();
+        program.exec(input);
+
+        MultiLabelParameters::<T>::predict(&program.registers)
+    }
+}
+
+impl<T> Fitness for Program<MultiLabelParameters<T>>
+where
+    T: MultiLabelInput,
+{
+    type FitnessParameters = MultiLabelParameters<T>;
+
+    fn eval_fitness(&mut self, parameters: &mut Self::FitnessParameters) -> FitnessScore {
+        let inputs = &parameters.inputs;
+        let n_labels = T::N_ACTION_REGISTERS;
+
+        let mut score = 0.;
+
+        for input in inputs {
+            self.exec(input);
+
+            let predicted_labels = MultiLabelParameters::<T>::predict(&self.registers);
+            let true_labels = input.get_classes().into_iter().collect::<BTreeSet<_>>();
+            let n_wrong = predicted_labels.symmetric_difference(&true_labels).count();
+
+            score += match parameters.metric {
+                MultiLabelMetric::Hamming => 1. - n_wrong as f32 / n_labels as f32,
+                MultiLabelMetric::Subset if n_wrong == 0 => 1.,
+                MultiLabelMetric::Subset => 0.,
+            };
+
+            self.registers.reset();
+        }
+
+        let fitness = score / inputs.len() as f32;
+
+        self.fitness = Some(fitness);
+
+        fitness
+    }
+
+    fn get_fitness(&self) -> Option<FitnessScore> {
+        self.fitness
+    }
+
+    fn set_fitness(&mut self, fitness: FitnessScore, _variance: Option<FitnessScore>) {
+        self.fitness = Some(fitness);
+    }
+
+    fn fitness_key(&self) -> Option<u64> {
+        Some(self.structural_hash())
+    }
+
+    /// Hashes the inputs and the metric.
+    fn parameters_key(parameters: &Self::FitnessParameters) -> Option<u64> {
+        let MultiLabelParameters { inputs, metric } = parameters;
+
+        let mut hasher = DefaultHasher::new();
+        metric.hash(&mut hasher);
+        for input in inputs {
+            for value in input.flat() {
+                value.to_bits().hash(&mut hasher);
+            }
+            input.get_classes().hash(&mut hasher);
+        }
+
+        Some(hasher.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use crate::{
+        core::{
+            characteristics::Fitness,
+            inputs::ValidInput,
+            instruction::{Instruction, Mode},
+            program::Program,
+            registers::{Registers, R32},
+        },
+        utils::executables::add,
+    };
+
+    use super::{MultiLabelInput, MultiLabelMetric, MultiLabelParameters};
+
+    /// Carries label `i` exactly when feature `i` is positive.
+    #[derive(Clone, Debug, Serialize)]
+    struct SignInput([R32; 2]);
+
+    impl ValidInput for SignInput {
+        const N_INPUT_REGISTERS: usize = 2;
+        const N_ACTION_REGISTERS: usize = 2;
+
+        fn flat(&self) -> Vec<R32> {
+            self.0.to_vec()
+        }
+    }
+
+    impl MultiLabelInput for SignInput {
+        fn get_classes(&self) -> Vec<usize> {
+            (0..2).filter(|label| self.0[*label] > 0.).collect()
+        }
+    }
+
+    #[test]
+    fn given_program_matching_every_label_when_eval_fitness_then_it_scores_one() {
+        let inputs = vec![
+            SignInput([1., 1.]),
+            SignInput([1., -1.]),
+            SignInput([-1., 1.]),
+            SignInput([-1., -1.]),
+        ];
+        let mut parameters = MultiLabelParameters::new(inputs);
+
+        // Copies each feature into its output register.
+        let mut matching = Program::<MultiLabelParameters<SignInput>>::new(
+            [
+                Instruction::new(0, 0, Mode::External, add),
+                Instruction::new(1, 1, Mode::External, add),
+            ]
+            .into_iter()
+            .collect(),
+            Registers::new(2),
+            None,
+        );
+        // Only the first label is ever right.
+        let mut partial = Program::<MultiLabelParameters<SignInput>>::new(
+            [Instruction::new(0, 0, Mode::External, add)]
+                .into_iter()
+                .collect(),
+            Registers::new(2),
+            None,
+        );
+
+        pretty_assertions::assert_eq!(matching.eval_fitness(&mut parameters), 1.);
+        pretty_assertions::assert_eq!(partial.eval_fitness(&mut parameters), 0.75);
+
+        parameters.metric = MultiLabelMetric::Subset;
+        pretty_assertions::assert_eq!(matching.eval_fitness(&mut parameters), 1.);
+        pretty_assertions::assert_eq!(partial.eval_fitness(&mut parameters), 0.5);
+
+        pretty_assertions::assert_eq!(
+            matching
+                .predict_labels(&SignInput([1., -1.]))
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+    }
+}
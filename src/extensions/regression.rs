@@ -0,0 +1,189 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use derive_new::new;
+use serde::Serialize;
+
+use crate::core::{
+    characteristics::{Fitness, FitnessDirection, FitnessScore},
+    inputs::{Inputs, ValidInput},
+    program::Program,
+    registers::{Registers, R32},
+};
+
+use super::core::ExtensionParameters;
+
+/// Inputs paired with a real-valued target, which programs predict in their first register.
+pub trait RegressionInput: ValidInput {
+    fn get_target(&self) -> R32;
+}
+
+#[derive(Clone, Debug, Serialize, new)]
+pub struct RegressionParameters<InputType>
+where
+    InputType: RegressionInput,
+{
+    inputs: Inputs<InputType>,
+}
+
+impl<T> ExtensionParameters for RegressionParameters<T>
+where
+    T: RegressionInput,
+{
+    const N_OUTPUT_REGISTERS: usize = 1;
+    const N_INPUT_REGISTERS: usize = T::N_INPUT_REGISTERS;
+
+    /// A single output, so it is always the one picked.
+    fn argmax(_registers: &Registers) -> i32 {
+        0
+    }
+
+    fn feature_names() -> Option<Vec<&'static str>> {
+        T::feature_names()
+    }
+}
+
+impl<T> Program<RegressionParameters<T>>
+where
+    T: RegressionInput,
+{
+    /// Runs the program once on `input`, returning the value left in `R[0]`.
+    pub fn predict_value(&self, input: &T) -> R32 {
+        let mut program = self.clone();
+        program.exec(input);
+
+        *program.registers.get(0)
+    }
+}
+
+impl<T> Fitness for Program<RegressionParameters<T>>
+where
+    T: RegressionInput,
+{
+    type FitnessParameters = RegressionParameters<T>;
+
+    const DIRECTION: FitnessDirection = FitnessDirection::Minimize;
+
+    /// Mean absolute error of `R[0]` against the targets.
+    fn eval_fitness(&mut self, parameters: &mut Self::FitnessParameters) -> FitnessScore {
+        let inputs = &parameters.inputs;
+
+        let mut total_error = 0.;
+
+        for input in inputs {
+            self.exec(input);
+            total_error += (self.registers.get(0) - input.get_target()).abs();
+            self.registers.reset();
+        }
+
+        let fitness = total_error / inputs.len() as f32;
+
+        self.fitness = Some(fitness);
+
+        fitness
+    }
+
+    fn get_fitness(&self) -> Option<FitnessScore> {
+        self.fitness
+    }
+
+    fn set_fitness(&mut self, fitness: FitnessScore, _variance: Option<FitnessScore>) {
+        self.fitness = Some(fitness);
+    }
+
+    fn fitness_key(&self) -> Option<u64> {
+        Some(self.structural_hash())
+    }
+
+    /// Hashes the inputs and their targets.
+    fn parameters_key(parameters: &Self::FitnessParameters) -> Option<u64> {
+        let RegressionParameters { inputs } = parameters;
+
+        let mut hasher = DefaultHasher::new();
+        for input in inputs {
+            for value in input.flat() {
+                value.to_bits().hash(&mut hasher);
+            }
+            input.get_target().to_bits().hash(&mut hasher);
+        }
+
+        Some(hasher.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use crate::{
+        core::{
+            characteristics::Fitness,
+            inputs::ValidInput,
+            instruction::{Instruction, Mode},
+            program::Program,
+            registers::{Registers, R32},
+        },
+        utils::executables::{add, multiply},
+    };
+
+    use super::{RegressionInput, RegressionParameters};
+
+    /// `x` with the target `2x`.
+    #[derive(Clone, Debug, Serialize)]
+    struct DoublingInput(R32);
+
+    impl ValidInput for DoublingInput {
+        const N_INPUT_REGISTERS: usize = 1;
+        const N_ACTION_REGISTERS: usize = 1;
+
+        fn flat(&self) -> Vec<R32> {
+            vec![self.0]
+        }
+    }
+
+    impl RegressionInput for DoublingInput {
+        fn get_target(&self) -> R32 {
+            2. * self.0
+        }
+    }
+
+    #[test]
+    fn given_programs_when_eval_fitness_then_mean_absolute_error_is_scored() {
+        let mut parameters = RegressionParameters::new(vec![DoublingInput(1.), DoublingInput(-2.)]);
+
+        // R[0] = x + x.
+        let mut exact = Program::<RegressionParameters<DoublingInput>>::new(
+            [
+                Instruction::new(0, 0, Mode::External, add),
+                Instruction::new(0, 0, Mode::External, add),
+            ]
+            .into_iter()
+            .collect(),
+            Registers::new(1),
+            None,
+        );
+        // R[0] = x.
+        let mut identity = Program::<RegressionParameters<DoublingInput>>::new(
+            [Instruction::new(0, 0, Mode::External, add)]
+                .into_iter()
+                .collect(),
+            Registers::new(1),
+            None,
+        );
+        // R[0] = 0 * x.
+        let mut zero = Program::<RegressionParameters<DoublingInput>>::new(
+            [Instruction::new(0, 0, Mode::External, multiply)]
+                .into_iter()
+                .collect(),
+            Registers::new(1),
+            None,
+        );
+
+        pretty_assertions::assert_eq!(exact.eval_fitness(&mut parameters), 0.);
+        pretty_assertions::assert_eq!(identity.eval_fitness(&mut parameters), 1.5);
+        pretty_assertions::assert_eq!(zero.eval_fitness(&mut parameters), 3.);
+        pretty_assertions::assert_eq!(exact.predict_value(&DoublingInput(4.)), 8.);
+    }
+}
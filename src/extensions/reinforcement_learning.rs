@@ -1,15 +1,15 @@
+use std::{error::Error, fmt, rc::Rc};
+
 use derivative::Derivative;
 use derive_new::new;
-use itertools::Itertools;
-use ordered_float::OrderedFloat;
-use rand::prelude::SliceRandom;
+use rand::{distributions::Uniform, prelude::SliceRandom, Rng};
 use serde::Serialize;
 
 use crate::{
     core::{
-        characteristics::Fitness,
+        characteristics::{Breed, Fitness, Generate, Length, Lineage, Mutate},
         inputs::ValidInput,
-        program::Program,
+        program::{Program, ProgramGeneratorParameters},
         registers::{Registers, R32},
     },
     utils::random::generator,
@@ -17,18 +17,149 @@ use crate::{
 
 use super::core::ExtensionParameters;
 
-#[derive(Debug, Serialize, Derivative, new)]
-#[derivative(PartialEq, Eq, PartialOrd, Ord, Clone)]
+/// How the scores of an individual's runs are combined into its fitness.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum RunAggregate {
+    #[default]
+    Median,
+    Mean,
+}
+
+impl RunAggregate {
+    /// Combines the scores of every run, returning the fitness and the variance of the scores.
+    fn apply(&self, mut scores: Vec<R32>) -> (R32, R32) {
+        let n_runs = scores.len();
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mean = scores.iter().sum::<R32>() / n_runs as R32;
+        let variance = scores
+            .iter()
+            .map(|score| (score - mean).powi(2))
+            .sum::<R32>()
+            / n_runs as R32;
+
+        let fitness = match self {
+            RunAggregate::Median => scores.remove(n_runs / 2),
+            RunAggregate::Mean => mean,
+        };
+
+        (fitness, variance)
+    }
+}
+
+/// How an action is picked when several output registers share the maximum value.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum ActionTieBreak {
+    /// A tied action drawn from `generator()`. Evaluation then depends on how many draws other
+    /// code has made, so runs are hard to reproduce.
+    #[default]
+    Random,
+    /// The tied action with the lowest index, for reproducible benchmarking.
+    LowestIndex,
+}
+
+/// A run count that would leave an individual without scores to aggregate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunCountError {
+    NoRuns,
+    NoSurrogateRuns,
+}
+
+impl fmt::Display for RunCountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunCountError::NoRuns => write!(f, "The number of runs must be greater than 0."),
+            RunCountError::NoSurrogateRuns => {
+                write!(f, "The number of surrogate runs must be greater than 0.")
+            }
+        }
+    }
+}
+
+impl Error for RunCountError {}
+
+/// Maps each step's outcome to the reward accumulated in its place.
+pub type RewardShaper = Rc<dyn Fn(&StateRewardPair) -> R32>;
+
+#[derive(Serialize, Derivative, new)]
+#[derivative(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct ReinforcementLearningParameters<T>
 where
     T: ReinforcementLearningInput,
 {
     pub n_runs: usize,
     pub max_episode_length: usize,
+    /// Number of runs used for the surrogate (stage-one) evaluation.
+    #[new(default)]
+    pub surrogate_n_runs: Option<usize>,
+    #[new(default)]
+    pub aggregate: RunAggregate,
+    /// Discount applied per step, so a run scores `sum(reward * gamma^step)`. Defaults to 1
+    /// (undiscounted).
+    #[new(value = "1.")]
+    #[derivative(PartialEq = "ignore", PartialOrd = "ignore", Ord = "ignore")]
+    pub gamma: R32,
+    /// Replaces the environment's reward with a shaped one; `None` keeps the raw reward.
+    #[new(default)]
+    #[serde(skip)]
+    #[derivative(
+        Debug = "ignore",
+        PartialEq = "ignore",
+        PartialOrd = "ignore",
+        Ord = "ignore"
+    )]
+    pub reward_shaper: Option<RewardShaper>,
+    #[new(default)]
+    pub tie_break: ActionTieBreak,
     #[derivative(PartialEq = "ignore", PartialOrd = "ignore", Ord = "ignore")]
     pub environment: T,
 }
 
+impl<T> ReinforcementLearningParameters<T>
+where
+    T: ReinforcementLearningInput,
+{
+    pub fn with_reward_shaper(self, shaper: impl Fn(&StateRewardPair) -> R32 + 'static) -> Self {
+        Self {
+            reward_shaper: Some(Rc::new(shaper)),
+            ..self
+        }
+    }
+
+    /// Rejects run counts `RunAggregate::apply` has no scores for.
+    pub fn validate(&self) -> Result<(), RunCountError> {
+        if self.n_runs == 0 {
+            Err(RunCountError::NoRuns)
+        } else if self.surrogate_n_runs == Some(0) {
+            Err(RunCountError::NoSurrogateRuns)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The action picked from the output registers, with ties resolved by `tie_break`.
+    pub fn pick_action(&self, registers: &Registers) -> usize {
+        let ties = registers
+            .argmax_outputs()
+            .unwrap_or_else(|error| panic!("{}", error));
+
+        let action = match self.tie_break {
+            ActionTieBreak::Random => ties.choose(&mut generator()),
+            ActionTieBreak::LowestIndex => ties.iter().min(),
+        };
+
+        *action.unwrap()
+    }
+
+    /// The reward accumulated for a step, after shaping.
+    fn reward(&self, state_reward: &StateRewardPair) -> R32 {
+        match &self.reward_shaper {
+            Some(shaper) => shaper(state_reward),
+            None => state_reward.get_value(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Clone, Copy)]
 pub enum Reward {
     Continue(R32),
@@ -69,50 +200,46 @@ impl<T> ExtensionParameters for ReinforcementLearningParameters<T>
 where
     T: ReinforcementLearningInput,
 {
+    const N_OUTPUT_REGISTERS: usize = T::N_ACTION_REGISTERS;
+    const N_INPUT_REGISTERS: usize = T::N_INPUT_REGISTERS;
+
     fn argmax(registers: &Registers) -> i32 {
-        let action_registers = &registers[0..T::N_ACTION_REGISTERS];
-        let max_value = action_registers
-            .into_iter()
-            .copied()
-            .reduce(|a, b| f32::max(a, b))
-            .unwrap();
-
-        let indices = action_registers
-            .into_iter()
-            .enumerate()
-            .filter(|(_, value)| **value == max_value)
-            .map(|(index, _)| index)
-            .collect_vec();
+        let indices = registers
+            .argmax_outputs()
+            .unwrap_or_else(|error| panic!("{}", error));
 
         indices.choose(&mut generator()).map(|v| *v as i32).unwrap()
     }
+
+    fn feature_names() -> Option<Vec<&'static str>> {
+        T::feature_names()
+    }
 }
 
-impl<T> Fitness for Program<ReinforcementLearningParameters<T>>
+impl<T> Program<ReinforcementLearningParameters<T>>
 where
     T: ReinforcementLearningInput,
 {
-    type FitnessParameters = ReinforcementLearningParameters<T>;
-
-    fn eval_fitness(
+    fn eval_runs(
         &mut self,
-        parameters: &mut Self::FitnessParameters,
+        parameters: &mut ReinforcementLearningParameters<T>,
+        n_runs: usize,
     ) -> crate::core::characteristics::FitnessScore {
         let mut scores = vec![];
 
         parameters.environment.init();
 
-        for _ in 0..parameters.n_runs {
+        for _ in 0..n_runs {
             let mut score = 0.;
 
-            for _ in 0..parameters.max_episode_length {
+            for step in 0..parameters.max_episode_length {
                 // Run program.
                 self.exec(&parameters.environment);
                 // Eval
-                let picked_action = ReinforcementLearningParameters::<T>::argmax(&self.registers);
-                let state_reward = parameters.environment.act(picked_action as usize);
+                let picked_action = parameters.pick_action(&self.registers);
+                let state_reward = parameters.environment.act(picked_action);
 
-                score += state_reward.get_value();
+                score += parameters.reward(&state_reward) * parameters.gamma.powi(step as i32);
 
                 if state_reward.is_terminal() {
                     break;
@@ -123,22 +250,96 @@ where
             parameters.environment.reset();
         }
 
-        scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
         parameters.environment.finish();
 
-        let median = scores.remove(parameters.n_runs / 2);
+        let (fitness, variance) = parameters.aggregate.apply(scores);
+
+        self.fitness = Some(fitness);
+        self.fitness_variance = Some(variance);
+
+        fitness
+    }
+
+    /// Replays a single episode of `parameters.environment`, at most `max_episode_length` steps
+    /// long and picking actions as `eval_fitness` does, returning the state each action was
+    /// picked in, the action and the raw reward. The episode starts with `init`; resetting and
+    /// closing the environment is left to the caller. The program's fitness is not touched.
+    pub fn rollout(
+        &self,
+        parameters: &mut ReinforcementLearningParameters<T>,
+    ) -> Vec<(Vec<R32>, usize, R32)> {
+        let mut program = self.clone();
+        let mut trajectory = vec![];
+
+        parameters.environment.init();
+
+        for _ in 0..parameters.max_episode_length {
+            program.exec(&parameters.environment);
+
+            let state = parameters.environment.get_state();
+            let picked_action = parameters.pick_action(&program.registers);
+            let state_reward = parameters.environment.act(picked_action);
+
+            trajectory.push((state, picked_action, state_reward.get_value()));
+
+            if state_reward.is_terminal() {
+                break;
+            }
+        }
+
+        trajectory
+    }
+}
+
+impl<T> Fitness for Program<ReinforcementLearningParameters<T>>
+where
+    T: ReinforcementLearningInput,
+{
+    type FitnessParameters = ReinforcementLearningParameters<T>;
+
+    fn eval_fitness(
+        &mut self,
+        parameters: &mut Self::FitnessParameters,
+    ) -> crate::core::characteristics::FitnessScore {
+        let n_runs = parameters.n_runs;
+        self.eval_runs(parameters, n_runs)
+    }
+
+    fn eval_surrogate_fitness(
+        &mut self,
+        parameters: &mut Self::FitnessParameters,
+    ) -> crate::core::characteristics::FitnessScore {
+        let n_runs = parameters.surrogate_n_runs.unwrap_or(parameters.n_runs);
+        self.eval_runs(parameters, n_runs)
+    }
 
-        self.fitness = Some(median);
+    fn has_surrogate_fitness(parameters: &Self::FitnessParameters) -> bool {
+        parameters
+            .surrogate_n_runs
+            .is_some_and(|n_runs| n_runs < parameters.n_runs)
+    }
 
-        median
+    fn validate_fitness_parameters(parameters: &Self::FitnessParameters) -> Result<(), String> {
+        parameters.validate().map_err(|error| error.to_string())
     }
 
     fn get_fitness(&self) -> Option<crate::core::characteristics::FitnessScore> {
         self.fitness
     }
+
+    /// Variance of the per-run scores behind the last evaluation, distinguishing a reliably
+    /// mediocre policy from a lucky one.
+    fn get_fitness_variance(&self) -> Option<R32> {
+        self.fitness_variance
+    }
+
+    fn set_fitness(&mut self, fitness: R32, variance: Option<R32>) {
+        self.fitness = Some(fitness);
+        self.fitness_variance = variance;
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct QTable {
     table: Vec<Vec<R32>>,
     /// Step size parameter.
@@ -147,14 +348,30 @@ pub struct QTable {
     gamma: R32,
 }
 
+/// A program paired with a Q-table whose `alpha` and `gamma` evolve alongside its instructions.
+/// The program's winning register is the state the Q-table picks an action for; the table is
+/// learned during evaluation and compared (like fitness) through the program alone.
+#[derive(Clone, Debug, Serialize, Derivative)]
+#[derivative(PartialEq(bound = ""), Eq(bound = ""), PartialOrd(bound = ""))]
+#[serde(bound = "")]
 pub struct QProgram<T>
 where
     T: ReinforcementLearningInput,
 {
     program: Program<ReinforcementLearningParameters<T>>,
+    #[derivative(PartialEq = "ignore", PartialOrd = "ignore")]
     q_table: QTable,
 }
 
+#[derive(Clone, Debug, Serialize, new)]
+pub struct QProgramGeneratorParameters {
+    pub program_parameters: ProgramGeneratorParameters,
+    pub n_actions: usize,
+    /// Largest change a single mutation applies to `alpha` or `gamma`.
+    #[new(value = "0.1")]
+    pub step_size: R32,
+}
+
 impl QTable {
     pub fn new(n_actions: usize, n_registers: usize, alpha: R32, gamma: R32) -> Self {
         let table = vec![vec![0.; n_actions]; n_registers];
@@ -165,33 +382,54 @@ impl QTable {
         }
     }
 
+    pub fn alpha(&self) -> R32 {
+        self.alpha
+    }
+
+    pub fn gamma(&self) -> R32 {
+        self.gamma
+    }
+
+    /// An untrained table of the same shape. Learned Q-values describe a single program's
+    /// behaviour, so offspring start from scratch.
+    fn untrained(&self, alpha: R32, gamma: R32) -> Self {
+        let n_actions = self.table.first().map_or(0, |actions| actions.len());
+        QTable::new(n_actions, self.table.len(), alpha, gamma)
+    }
+
+    /// The action with the highest Q-value in the register's row, the lowest such action on
+    /// ties.
     pub fn action_argmax(&self, register_number: usize) -> usize {
-        let QTable { table, .. } = &self;
-        let mut best_action = -1 as i32;
-        let mut best_q_value = 0f32;
-        let available_actions = table
+        let available_actions = self
+            .table
             .get(register_number)
             .expect("Register number to be less than length of QTable.");
 
-        for (action, q_value) in available_actions.into_iter().enumerate() {
-            if *q_value > best_q_value {
-                best_q_value = *q_value;
-                best_action = action as i32;
-            }
-        }
-
-        best_action as usize
+        available_actions
+            .iter()
+            .enumerate()
+            .fold((0, R32::NEG_INFINITY), |best, (action, q_value)| {
+                match *q_value > best.1 {
+                    true => (action, *q_value),
+                    false => best,
+                }
+            })
+            .0
     }
 
+    /// Moves `Q(current_register, current_action)` towards the reward plus the discounted best
+    /// Q-value of `next_register`, or towards the reward alone when the episode ended.
     pub fn update(
         &mut self,
         current_register: usize,
         current_action: usize,
         current_reward: R32,
-        next_register: usize,
+        next_register: Option<usize>,
     ) {
         let current_q_value = self.table[current_register][current_action];
-        let next_q_value = self.action_argmax(next_register) as f32;
+        let next_q_value = next_register.map_or(0., |register| {
+            self.table[register][self.action_argmax(register)]
+        });
 
         let new_q_value = current_q_value
             + self.alpha * (current_reward + self.gamma * next_q_value - current_q_value);
@@ -200,44 +438,633 @@ impl QTable {
     }
 }
 
+impl<T> QProgram<T>
+where
+    T: ReinforcementLearningInput,
+{
+    pub fn q_table(&self) -> &QTable {
+        &self.q_table
+    }
+
+    fn offspring(
+        &self,
+        program: Program<ReinforcementLearningParameters<T>>,
+        alpha: R32,
+        gamma: R32,
+    ) -> Self {
+        QProgram {
+            program,
+            q_table: self.q_table.untrained(alpha, gamma),
+        }
+    }
+}
+
+impl<T> Generate for QProgram<T>
+where
+    T: ReinforcementLearningInput,
+{
+    type GeneratorParameters = QProgramGeneratorParameters;
+
+    fn generate_with_rng<R: Rng + ?Sized>(
+        parameters: &Self::GeneratorParameters,
+        rng: &mut R,
+    ) -> Self {
+        let program = Program::generate_with_rng(&parameters.program_parameters, rng);
+        let unit = Uniform::new_inclusive(0., 1.);
+        let q_table = QTable::new(
+            parameters.n_actions,
+            program.registers.len(),
+            rng.sample(unit),
+            rng.sample(unit),
+        );
+
+        QProgram { program, q_table }
+    }
+
+    fn validate_parameters(parameters: &Self::GeneratorParameters) -> Result<(), String> {
+        Program::<ReinforcementLearningParameters<T>>::validate_parameters(
+            &parameters.program_parameters,
+        )
+    }
+
+    fn enforce_bounds(&mut self, parameters: &Self::GeneratorParameters) {
+        self.program.enforce_bounds(&parameters.program_parameters);
+    }
+}
+
+impl<T> Mutate for QProgram<T>
+where
+    T: ReinforcementLearningInput,
+{
+    /// Flip a coin: mutate the instructions, or nudge `alpha` and `gamma` within `[0, 1]`.
+    fn mutate_with_rng<R: Rng + ?Sized>(
+        &self,
+        parameters: &Self::GeneratorParameters,
+        rng: &mut R,
+    ) -> Self {
+        let QTable { alpha, gamma, .. } = self.q_table;
+
+        if rng.gen_bool(0.5) {
+            let program = self
+                .program
+                .mutate_with_rng(&parameters.program_parameters, rng);
+            self.offspring(program, alpha, gamma)
+        } else {
+            let step = Uniform::new_inclusive(-parameters.step_size, parameters.step_size);
+            let mut nudge = |value: R32| (value + rng.sample(step)).clamp(0., 1.);
+            self.offspring(self.program.clone(), nudge(alpha), nudge(gamma))
+        }
+    }
+}
+
+impl<T> Breed for QProgram<T>
+where
+    T: ReinforcementLearningInput,
+{
+    /// Each child takes `alpha` from one parent and `gamma` from the other.
+    fn two_point_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R) -> [Self; 2] {
+        let [program_a, program_b] = self
+            .program
+            .two_point_crossover_with_rng(&mate.program, rng);
+
+        [
+            self.offspring(program_a, self.q_table.alpha, mate.q_table.gamma),
+            self.offspring(program_b, mate.q_table.alpha, self.q_table.gamma),
+        ]
+    }
+
+    fn uniform_crossover_with_rng<R: Rng + ?Sized>(&self, mate: &Self, rng: &mut R) -> Self {
+        let program = self.program.uniform_crossover_with_rng(&mate.program, rng);
+        let parents = [&self.q_table, &mate.q_table];
+        let alpha = parents.choose(rng).unwrap().alpha;
+        let gamma = parents.choose(rng).unwrap().gamma;
+
+        self.offspring(program, alpha, gamma)
+    }
+
+    fn homologous_crossover_with_rng<R: Rng + ?Sized>(
+        &self,
+        mate: &Self,
+        rng: &mut R,
+    ) -> [Self; 2] {
+        let [program_a, program_b] = self
+            .program
+            .homologous_crossover_with_rng(&mate.program, rng);
+
+        [
+            self.offspring(program_a, self.q_table.alpha, mate.q_table.gamma),
+            self.offspring(program_b, mate.q_table.alpha, self.q_table.gamma),
+        ]
+    }
+
+    fn genome_hash(&self) -> Option<u64> {
+        self.program.genome_hash()
+    }
+}
+
 impl<T> Fitness for QProgram<T>
 where
     T: ReinforcementLearningInput,
 {
     type FitnessParameters = ReinforcementLearningParameters<T>;
 
+    /// Runs the episodes like `Program`'s evaluation, except that actions come from the
+    /// Q-table row of the winning register, and the table learns from every step.
     fn eval_fitness(
         &mut self,
         parameters: &mut Self::FitnessParameters,
     ) -> crate::core::characteristics::FitnessScore {
+        let mut scores = vec![];
+
         parameters.environment.init();
-        for _run in 0..parameters.n_runs {
-            let mut score = 0f32;
-            for _step in 0..parameters.max_episode_length {
+
+        for _ in 0..parameters.n_runs {
+            let mut score = 0.;
+            let mut previous: Option<(usize, usize, R32)> = None;
+
+            for step in 0..parameters.max_episode_length {
                 self.program.exec(&parameters.environment);
 
-                let selected_register = self
-                    .program
-                    .registers
-                    .iter()
-                    .map(|v| OrderedFloat(*v))
-                    .position_max()
-                    .expect("Registers length to be greater than 0.");
+                let registers = &self.program.registers;
+                let register = registers.argmax_all(registers.len())[0];
 
-                let state_reward_pair = parameters.environment.act(selected_register);
+                if let Some((previous_register, previous_action, reward)) = previous {
+                    self.q_table
+                        .update(previous_register, previous_action, reward, Some(register));
+                }
+
+                let action = self.q_table.action_argmax(register);
+                let state_reward = parameters.environment.act(action);
+                let reward = parameters.reward(&state_reward);
 
-                score += state_reward_pair.get_value();
+                score += reward * parameters.gamma.powi(step as i32);
 
-                if state_reward_pair.is_terminal() {
+                if state_reward.is_terminal() {
+                    self.q_table.update(register, action, reward, None);
+                    previous = None;
                     break;
                 }
+
+                previous = Some((register, action, reward));
+            }
+
+            if let Some((register, action, reward)) = previous {
+                self.q_table.update(register, action, reward, None);
             }
+
+            scores.push(score);
+            parameters.environment.reset();
         }
 
-        todo!()
+        parameters.environment.finish();
+
+        let (fitness, variance) = parameters.aggregate.apply(scores);
+        self.program.set_fitness(fitness, Some(variance));
+
+        fitness
     }
 
     fn get_fitness(&self) -> Option<crate::core::characteristics::FitnessScore> {
-        todo!()
+        self.program.fitness
+    }
+
+    fn validate_fitness_parameters(parameters: &Self::FitnessParameters) -> Result<(), String> {
+        parameters.validate().map_err(|error| error.to_string())
+    }
+
+    fn get_fitness_variance(&self) -> Option<R32> {
+        self.program.fitness_variance
+    }
+
+    fn set_fitness(&mut self, fitness: R32, variance: Option<R32>) {
+        self.program.set_fitness(fitness, variance);
+    }
+}
+
+impl<T> Length for QProgram<T>
+where
+    T: ReinforcementLearningInput,
+{
+    fn length(&self) -> usize {
+        self.program.length()
+    }
+}
+
+impl<T> Lineage for QProgram<T>
+where
+    T: ReinforcementLearningInput,
+{
+    fn id(&self) -> Option<u64> {
+        self.program.id()
+    }
+
+    fn parent_ids(&self) -> &[u64] {
+        self.program.parent_ids()
+    }
+
+    fn set_lineage(&mut self, id: u64, parent_ids: Vec<u64>) {
+        self.program.set_lineage(id, parent_ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use crate::core::{
+        algorithm::{EventHooks, GeneticAlgorithm, HyperParameterError, HyperParameters},
+        characteristics::{Breed, Fitness, Generate, Mutate},
+        inputs::ValidInput,
+        instruction::InstructionGeneratorParameters,
+        instructions::Instructions,
+        program::{Program, ProgramGeneratorParameters},
+        registers::{Registers, R32},
+    };
+
+    use super::{
+        ActionTieBreak, QProgram, QProgramGeneratorParameters, QTable, ReinforcementLearningInput,
+        ReinforcementLearningParameters, Reward, RunAggregate, RunCountError, StateRewardPair,
+    };
+
+    /// Ends every run after one step, paying out the next scripted reward.
+    #[derive(Debug, Clone, Serialize)]
+    struct ScriptedEnvironment {
+        rewards: Vec<R32>,
+        run: usize,
+    }
+
+    impl ValidInput for ScriptedEnvironment {
+        const N_INPUT_REGISTERS: usize = 1;
+        const N_ACTION_REGISTERS: usize = 2;
+
+        fn flat(&self) -> Vec<R32> {
+            vec![0.]
+        }
+    }
+
+    impl ReinforcementLearningInput for ScriptedEnvironment {
+        fn init(&mut self) {
+            self.run = 0;
+        }
+
+        fn act(&mut self, _action: usize) -> StateRewardPair {
+            StateRewardPair {
+                state: self.get_state(),
+                reward: Reward::Terminal(self.rewards[self.run]),
+            }
+        }
+
+        fn reset(&mut self) {
+            self.run += 1;
+        }
+
+        fn get_state(&self) -> Vec<R32> {
+            self.flat()
+        }
+
+        fn finish(&mut self) {}
+    }
+
+    /// Pays a constant reward every step, never terminating early.
+    #[derive(Debug, Clone, Serialize)]
+    struct ConstantRewardEnvironment;
+
+    impl ValidInput for ConstantRewardEnvironment {
+        const N_INPUT_REGISTERS: usize = 1;
+        const N_ACTION_REGISTERS: usize = 2;
+
+        fn flat(&self) -> Vec<R32> {
+            vec![0.]
+        }
+    }
+
+    impl ReinforcementLearningInput for ConstantRewardEnvironment {
+        fn init(&mut self) {}
+
+        fn act(&mut self, _action: usize) -> StateRewardPair {
+            StateRewardPair {
+                state: self.get_state(),
+                reward: Reward::Continue(1.),
+            }
+        }
+
+        fn reset(&mut self) {}
+
+        fn get_state(&self) -> Vec<R32> {
+            self.flat()
+        }
+
+        fn finish(&mut self) {}
+    }
+
+    /// Counts the steps taken in a run, paying the index of the picked action as the reward.
+    #[derive(Debug, Clone, Default, Serialize)]
+    struct CountingEnvironment {
+        step: usize,
+    }
+
+    impl ValidInput for CountingEnvironment {
+        const N_INPUT_REGISTERS: usize = 1;
+        const N_ACTION_REGISTERS: usize = 2;
+
+        fn flat(&self) -> Vec<R32> {
+            vec![self.step as R32]
+        }
+    }
+
+    impl ReinforcementLearningInput for CountingEnvironment {
+        fn init(&mut self) {
+            self.step = 0;
+        }
+
+        fn act(&mut self, action: usize) -> StateRewardPair {
+            self.step += 1;
+
+            StateRewardPair {
+                state: self.get_state(),
+                reward: Reward::Continue(action as R32),
+            }
+        }
+
+        fn reset(&mut self) {
+            self.step = 0;
+        }
+
+        fn get_state(&self) -> Vec<R32> {
+            self.flat()
+        }
+
+        fn finish(&mut self) {}
+    }
+
+    #[test]
+    fn given_scripted_rewards_when_eval_fitness_then_mean_and_variance_are_reported() {
+        let environment = ScriptedEnvironment {
+            rewards: vec![1., 2., 3., 10.],
+            run: 0,
+        };
+        let mut parameters = ReinforcementLearningParameters::new(4, 5, environment);
+        let mut program = Program::<ReinforcementLearningParameters<ScriptedEnvironment>>::new(
+            Instructions::new(),
+            Registers::new(2),
+            None,
+        );
+        pretty_assertions::assert_eq!(program.get_fitness_variance(), None);
+
+        pretty_assertions::assert_eq!(program.eval_fitness(&mut parameters), 3.);
+
+        parameters.aggregate = RunAggregate::Mean;
+        pretty_assertions::assert_eq!(program.eval_fitness(&mut parameters), 4.);
+        pretty_assertions::assert_eq!(program.get_fitness_variance(), Some(12.5));
+    }
+
+    #[test]
+    fn given_surrogate_runs_when_has_surrogate_fitness_then_only_fewer_runs_count() {
+        type RlProgram = Program<ReinforcementLearningParameters<ConstantRewardEnvironment>>;
+        let mut parameters = ReinforcementLearningParameters::new(4, 5, ConstantRewardEnvironment);
+
+        assert!(!RlProgram::has_surrogate_fitness(&parameters));
+
+        parameters.surrogate_n_runs = Some(4);
+        assert!(!RlProgram::has_surrogate_fitness(&parameters));
+
+        parameters.surrogate_n_runs = Some(1);
+        assert!(RlProgram::has_surrogate_fitness(&parameters));
+    }
+
+    #[test]
+    fn given_zero_runs_when_execute_then_the_parameters_are_rejected() {
+        let build = |n_runs, surrogate_n_runs| {
+            let mut parameters =
+                ReinforcementLearningParameters::new(n_runs, 5, ConstantRewardEnvironment);
+            parameters.surrogate_n_runs = surrogate_n_runs;
+
+            HyperParameters::<QProgram<ConstantRewardEnvironment>>::builder()
+                .with_population_size(10)
+                .with_max_generations(1)
+                .with_fitness_parameters(parameters)
+                .with_program_parameters(QProgramGeneratorParameters::new(
+                    ProgramGeneratorParameters::new(
+                        10,
+                        InstructionGeneratorParameters::from::<ConstantRewardEnvironment>(1),
+                    ),
+                    ConstantRewardEnvironment::N_ACTION_REGISTERS,
+                ))
+                .build()
+        };
+
+        pretty_assertions::assert_eq!(
+            build(0, None).unwrap_err(),
+            HyperParameterError::InvalidFitnessParameters(RunCountError::NoRuns.to_string())
+        );
+        pretty_assertions::assert_eq!(
+            build(2, Some(0)).unwrap_err(),
+            HyperParameterError::InvalidFitnessParameters(
+                RunCountError::NoSurrogateRuns.to_string()
+            )
+        );
+        assert!(build(2, Some(1)).is_ok());
+    }
+
+    #[test]
+    fn given_reward_shaper_that_doubles_rewards_when_eval_fitness_then_score_doubles() {
+        let environment = ScriptedEnvironment {
+            rewards: vec![1., 2., 3.],
+            run: 0,
+        };
+        let mut parameters = ReinforcementLearningParameters::new(3, 5, environment);
+        let mut program = Program::<ReinforcementLearningParameters<ScriptedEnvironment>>::new(
+            Instructions::new(),
+            Registers::new(2),
+            None,
+        );
+
+        let score = program.eval_fitness(&mut parameters);
+
+        let mut parameters =
+            parameters.with_reward_shaper(|state_reward| 2. * state_reward.get_value());
+        let shaped_score = program.eval_fitness(&mut parameters);
+
+        pretty_assertions::assert_eq!(score, 2.);
+        pretty_assertions::assert_eq!(shaped_score, 2. * score);
+    }
+
+    #[test]
+    fn given_gamma_when_eval_fitness_then_score_is_the_discounted_geometric_series() {
+        let n_steps = 10;
+        let mut parameters =
+            ReinforcementLearningParameters::new(1, n_steps, ConstantRewardEnvironment);
+        let mut program =
+            Program::<ReinforcementLearningParameters<ConstantRewardEnvironment>>::new(
+                Instructions::new(),
+                Registers::new(2),
+                None,
+            );
+
+        pretty_assertions::assert_eq!(parameters.gamma, 1.);
+        pretty_assertions::assert_eq!(program.eval_fitness(&mut parameters), n_steps as R32);
+
+        let gamma: R32 = 0.9;
+        parameters.gamma = gamma;
+        let discounted_score = program.eval_fitness(&mut parameters);
+
+        let geometric_series = (1. - gamma.powi(n_steps as i32)) / (1. - gamma);
+        assert!((discounted_score - geometric_series).abs() < 1e-5);
+    }
+
+    #[test]
+    fn given_program_when_rollout_then_trajectory_stops_at_max_steps_or_termination() {
+        let program = Program::<ReinforcementLearningParameters<ConstantRewardEnvironment>>::new(
+            Instructions::new(),
+            Registers::new(2),
+            None,
+        );
+        let mut parameters = ReinforcementLearningParameters::new(1, 7, ConstantRewardEnvironment);
+        let trajectory = program.rollout(&mut parameters);
+
+        pretty_assertions::assert_eq!(trajectory.len(), 7);
+        for (state, action, reward) in trajectory {
+            pretty_assertions::assert_eq!(state, vec![0.]);
+            assert!(action < ConstantRewardEnvironment::N_ACTION_REGISTERS);
+            pretty_assertions::assert_eq!(reward, 1.);
+        }
+        pretty_assertions::assert_eq!(program.fitness, None);
+
+        let program = Program::<ReinforcementLearningParameters<ScriptedEnvironment>>::new(
+            Instructions::new(),
+            Registers::new(2),
+            None,
+        );
+        let environment = ScriptedEnvironment {
+            rewards: vec![5.],
+            run: 0,
+        };
+        let mut parameters = ReinforcementLearningParameters::new(1, 7, environment);
+        let trajectory = program.rollout(&mut parameters);
+
+        pretty_assertions::assert_eq!(trajectory.len(), 1);
+        pretty_assertions::assert_eq!(trajectory[0].2, 5.);
+    }
+
+    #[test]
+    fn given_deterministic_tie_break_when_rollout_then_rewards_add_up_to_eval_fitness() {
+        let program_parameters = ProgramGeneratorParameters::new(
+            10,
+            InstructionGeneratorParameters::from::<CountingEnvironment>(1),
+        );
+        let mut parameters =
+            ReinforcementLearningParameters::new(1, 7, CountingEnvironment::default());
+        parameters.tie_break = ActionTieBreak::LowestIndex;
+
+        for _ in 0..20 {
+            let mut program = Program::generate(&program_parameters);
+
+            let trajectory = program.rollout(&mut parameters);
+            let return_: R32 = trajectory.iter().map(|(_, _, reward)| reward).sum();
+
+            pretty_assertions::assert_eq!(program.eval_fitness(&mut parameters), return_);
+        }
+    }
+
+    #[test]
+    fn given_q_programs_when_repeatedly_mutated_and_bred_then_alpha_and_gamma_stay_within_unit_interval(
+    ) {
+        let parameters = QProgramGeneratorParameters::new(
+            ProgramGeneratorParameters::new(
+                10,
+                InstructionGeneratorParameters::from::<ScriptedEnvironment>(1),
+            ),
+            2,
+        );
+        let in_unit_interval = |program: &QProgram<ScriptedEnvironment>| {
+            let q_table = program.q_table();
+            (0. ..=1.).contains(&q_table.alpha()) && (0. ..=1.).contains(&q_table.gamma())
+        };
+
+        let mut program = QProgram::<ScriptedEnvironment>::generate(&parameters);
+        let mate = QProgram::generate(&parameters);
+
+        for _ in 0..1000 {
+            program = program.mutate(&parameters);
+            assert!(in_unit_interval(&program));
+
+            let [child_a, child_b] = program.two_point_crossover(&mate);
+            assert!(in_unit_interval(&child_a) && in_unit_interval(&child_b));
+            assert!(in_unit_interval(&program.uniform_crossover(&mate)));
+        }
+    }
+
+    #[test]
+    fn given_lowest_index_tie_break_when_actions_are_tied_then_the_lowest_action_is_always_picked()
+    {
+        let environment = ScriptedEnvironment {
+            rewards: vec![],
+            run: 0,
+        };
+        let mut parameters = ReinforcementLearningParameters::new(1, 1, environment);
+        parameters.tie_break = ActionTieBreak::LowestIndex;
+
+        let mut registers = Registers::new(3).with_n_outputs(Some(2));
+        registers.update(0, 2.);
+        registers.update(1, 2.);
+
+        for _ in 0..20 {
+            pretty_assertions::assert_eq!(parameters.pick_action(&registers), 0);
+        }
+    }
+
+    #[test]
+    fn given_untrained_q_table_when_updated_then_action_argmax_follows_the_learned_values() {
+        let mut q_table = QTable::new(3, 2, 0.5, 0.5);
+        pretty_assertions::assert_eq!(q_table.action_argmax(0), 0);
+
+        q_table.update(1, 2, 4., None);
+        pretty_assertions::assert_eq!(q_table.action_argmax(1), 2);
+
+        q_table.update(0, 1, 1., Some(1));
+        pretty_assertions::assert_eq!(q_table.table[0][1], 0.5 * (1. + 0.5 * 2.));
+        pretty_assertions::assert_eq!(q_table.action_argmax(0), 1);
+    }
+
+    struct QProgramLgp;
+
+    impl GeneticAlgorithm for QProgramLgp {
+        type O = QProgram<ConstantRewardEnvironment>;
+    }
+
+    #[test]
+    fn given_q_programs_when_execute_then_every_ranked_individual_is_evaluated(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let n_steps = 5;
+        let mut hyper_params = HyperParameters::builder()
+            .with_population_size(10)
+            .with_max_generations(3)
+            .with_fitness_parameters(ReinforcementLearningParameters::new(
+                2,
+                n_steps,
+                ConstantRewardEnvironment,
+            ))
+            .with_program_parameters(QProgramGeneratorParameters::new(
+                ProgramGeneratorParameters::new(
+                    10,
+                    InstructionGeneratorParameters::from::<ConstantRewardEnvironment>(1),
+                ),
+                ConstantRewardEnvironment::N_ACTION_REGISTERS,
+            ))
+            .build()?;
+
+        let mut scores = vec![];
+        let population = QProgramLgp::execute(
+            &mut hyper_params,
+            EventHooks::default().with_after_rank(&mut |population| {
+                scores.extend(population.iter().map(|program| program.get_fitness()));
+                Ok(())
+            }),
+        )?;
+
+        pretty_assertions::assert_eq!(population.len(), 10);
+        pretty_assertions::assert_eq!(scores, vec![Some(n_steps as R32); 3 * 10]);
+
+        Ok(())
     }
 }
@@ -1,4 +1,9 @@
-use crate::core::registers::R32;
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+use crate::core::{
+    int_registers::{int_add, int_divide, int_multiply, int_subtract, IntOp, I32},
+    registers::R32,
+};
 
 pub type Op = fn(a: R32, b: R32) -> R32;
 
@@ -6,6 +11,171 @@ pub type Executables = &'static [Op];
 
 pub const DEFAULT_EXECUTABLES: Executables = &[add, subtract, multiply, divide];
 
+/// The default executables plus nonlinear primitives. Unary executables apply to the source
+/// register (`a`) and ignore the target operand (`b`).
+pub const DEFAULT_EXECUTABLES_EXTENDED: Executables =
+    &[add, subtract, multiply, divide, sin, cos, ln, exp, sqrt];
+
+/// Executables writing a fixed constant, from which generation can seed registers with constants.
+///
+/// Executables share the binary `(a, b)` signature so any of them fits any instruction. A
+/// constant load is nullary: it ignores both operands, leaving the instruction's indices unused
+/// apart from selecting the destination register.
+pub const LOAD_CONST_EXECUTABLES: Executables =
+    &[load_zero, load_one, load_minus_one, load_two, load_half];
+
+/// The default executables plus `copy` and the constant loads, broadening the function set for
+/// regression.
+pub const DEFAULT_EXECUTABLES_WITH_CONSTANTS: Executables = &[
+    add,
+    subtract,
+    multiply,
+    divide,
+    copy,
+    load_zero,
+    load_one,
+    load_minus_one,
+    load_two,
+    load_half,
+];
+
+/// The `int_registers` executables over `Registers`' floats, so `Program` can evolve integer
+/// programs: operands are truncated to `I32` and the wrapped integer result written back.
+/// Results are exact while they lie within ±2^24, the integers `R32` represents exactly.
+pub const INTEGER_EXECUTABLES: Executables = &[
+    integer_add,
+    integer_subtract,
+    integer_multiply,
+    integer_divide,
+];
+
+/// Built-in executables under stable names, used to (de)serialize instructions.
+pub const NAMED_EXECUTABLES: &[(&str, Op)] = &[
+    ("add", add),
+    ("subtract", subtract),
+    ("multiply", multiply),
+    ("divide", divide),
+    ("sin", sin),
+    ("cos", cos),
+    ("ln", ln),
+    ("exp", exp),
+    ("sqrt", sqrt),
+    ("copy", copy),
+    ("load_zero", load_zero),
+    ("load_one", load_one),
+    ("load_minus_one", load_minus_one),
+    ("load_two", load_two),
+    ("load_half", load_half),
+    ("integer_add", integer_add),
+    ("integer_subtract", integer_subtract),
+    ("integer_multiply", integer_multiply),
+    ("integer_divide", integer_divide),
+];
+
+/// Inputs to `exp` are clamped to this bound so results stay finite.
+pub const EXP_INPUT_BOUND: R32 = 80.;
+
+/// Renders an executable applied to the operands `a` and `b`, e.g. `a + b`.
+pub fn display_executable(executable: Op, a: &str, b: &str) -> String {
+    match executable_name(executable) {
+        Some("add") => format!("{} + {}", a, b),
+        Some("subtract") => format!("{} - {}", a, b),
+        Some("multiply") => format!("{} * {}", a, b),
+        Some("divide") => format!("{} / {}", a, b),
+        Some(name @ ("sin" | "cos" | "ln" | "exp" | "sqrt")) => format!("{}({})", name, a),
+        Some("copy") => b.to_string(),
+        Some("load_zero" | "load_one" | "load_minus_one" | "load_two" | "load_half") => {
+            format!("{:?}", executable(0., 0.))
+        }
+        Some(
+            name @ ("integer_add" | "integer_subtract" | "integer_multiply" | "integer_divide"),
+        ) => format!("{}({}, {})", name, a, b),
+        _ => format!("op({}, {})", a, b),
+    }
+}
+
+/// A standalone Rust expression over the `f32` operands `a` and `b` computing exactly what
+/// `executable` does, e.g. `if b == 0.0 { a } else { a / b }` for `divide`. `None` for
+/// executables missing from `NAMED_EXECUTABLES`.
+pub fn rust_expression(executable: Op) -> Option<String> {
+    let on_integers = |method: &str| format!("(a as i32).{}(b as i32) as f32", method);
+
+    let expression = match executable_name(executable)? {
+        "add" => "a + b".to_string(),
+        "subtract" => "a - b".to_string(),
+        "multiply" => "a * b".to_string(),
+        "divide" => "if b == 0.0 { a } else { a / b }".to_string(),
+        name @ ("sin" | "cos") => format!("if a.is_finite() {{ a.{}() }} else {{ 0.0 }}", name),
+        "ln" => "if a == 0.0 { 0.0 } else { a.abs().ln() }".to_string(),
+        "exp" => format!("a.min({:?}).exp()", EXP_INPUT_BOUND),
+        "sqrt" => "a.abs().sqrt()".to_string(),
+        "copy" => "b".to_string(),
+        "load_zero" | "load_one" | "load_minus_one" | "load_two" | "load_half" => {
+            format!("{:?}", executable(0., 0.))
+        }
+        "integer_add" => on_integers("wrapping_add"),
+        "integer_subtract" => on_integers("wrapping_sub"),
+        "integer_multiply" => on_integers("wrapping_mul"),
+        "integer_divide" => {
+            "(if b as i32 == 0 { a as i32 } else { (a as i32).wrapping_div(b as i32) }) as f32"
+                .to_string()
+        }
+        _ => return None,
+    };
+
+    Some(expression)
+}
+
+pub fn executable_name(executable: Op) -> Option<&'static str> {
+    NAMED_EXECUTABLES
+        .iter()
+        .find(|(_, op)| *op as usize == executable as usize)
+        .map(|(name, _)| *name)
+}
+
+pub fn executable_from_name(name: &str) -> Option<Op> {
+    NAMED_EXECUTABLES
+        .iter()
+        .find(|(op_name, _)| *op_name == name)
+        .map(|(_, op)| *op)
+}
+
+/// Stands in for executables missing from `NAMED_EXECUTABLES`, so programs using custom
+/// executables still serialize (and display). Deserializing it fails.
+pub const UNNAMED_EXECUTABLE: &str = "unnamed";
+
+/// Serializes an executable by name, writing `UNNAMED_EXECUTABLE` for executables missing from
+/// `NAMED_EXECUTABLES`.
+pub fn serialize_executable<S>(executable: &Op, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(executable_name(*executable).unwrap_or(UNNAMED_EXECUTABLE))
+}
+
+pub fn serialize_executables<S>(executables: &[Op], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let names = executables
+        .iter()
+        .map(|executable| executable_name(*executable).unwrap_or(UNNAMED_EXECUTABLE));
+
+    serializer.collect_seq(names)
+}
+
+pub fn deserialize_executable<'de, D>(deserializer: D) -> Result<Op, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+
+    executable_from_name(&name).ok_or_else(|| match name.as_str() {
+        UNNAMED_EXECUTABLE => de::Error::custom("custom executables cannot be deserialized"),
+        _ => de::Error::custom(format!("unknown executable `{}`", name)),
+    })
+}
+
 pub fn add(a: R32, b: R32) -> R32 {
     a + b
 }
@@ -18,6 +188,204 @@ pub fn multiply(a: R32, b: R32) -> R32 {
     a * b
 }
 
-pub fn divide(a: R32, _b: R32) -> R32 {
-    a / 2f32
+/// Protected division: `a / b`, or `a` unchanged when `b` is 0.
+pub fn divide(a: R32, b: R32) -> R32 {
+    if b == 0. {
+        a
+    } else {
+        a / b
+    }
+}
+
+/// Sine of `a`; non-finite inputs yield 0.
+pub fn sin(a: R32, _b: R32) -> R32 {
+    if a.is_finite() {
+        a.sin()
+    } else {
+        0.
+    }
+}
+
+/// Cosine of `a`; non-finite inputs yield 0.
+pub fn cos(a: R32, _b: R32) -> R32 {
+    if a.is_finite() {
+        a.cos()
+    } else {
+        0.
+    }
+}
+
+/// Protected natural logarithm: `ln(|a|)`, or 0 when `a` is 0.
+pub fn ln(a: R32, _b: R32) -> R32 {
+    if a == 0. {
+        0.
+    } else {
+        a.abs().ln()
+    }
+}
+
+/// `e^a`, with `a` clamped to `EXP_INPUT_BOUND` to avoid overflowing to infinity.
+pub fn exp(a: R32, _b: R32) -> R32 {
+    a.min(EXP_INPUT_BOUND).exp()
+}
+
+/// Protected square root: `sqrt(|a|)`.
+pub fn sqrt(a: R32, _b: R32) -> R32 {
+    a.abs().sqrt()
+}
+
+/// Copies the operand `b` into the destination, leaving its previous value `a` behind.
+pub fn copy(_a: R32, b: R32) -> R32 {
+    b
+}
+
+pub fn load_zero(_a: R32, _b: R32) -> R32 {
+    0.
+}
+
+pub fn load_one(_a: R32, _b: R32) -> R32 {
+    1.
+}
+
+pub fn load_minus_one(_a: R32, _b: R32) -> R32 {
+    -1.
+}
+
+pub fn load_two(_a: R32, _b: R32) -> R32 {
+    2.
+}
+
+pub fn load_half(_a: R32, _b: R32) -> R32 {
+    0.5
+}
+
+fn on_integers(executable: IntOp, a: R32, b: R32) -> R32 {
+    executable(a as I32, b as I32) as R32
+}
+
+pub fn integer_add(a: R32, b: R32) -> R32 {
+    on_integers(int_add, a, b)
+}
+
+pub fn integer_subtract(a: R32, b: R32) -> R32 {
+    on_integers(int_subtract, a, b)
+}
+
+pub fn integer_multiply(a: R32, b: R32) -> R32 {
+    on_integers(int_multiply, a, b)
+}
+
+/// Protected integer division truncating towards zero; see `int_registers::int_divide`.
+pub fn integer_divide(a: R32, b: R32) -> R32 {
+    on_integers(int_divide, a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDGE_CASES: [R32; 9] = [
+        0.,
+        -0.,
+        1.,
+        -1.,
+        1e-30,
+        1e30,
+        -1e30,
+        R32::INFINITY,
+        R32::NEG_INFINITY,
+    ];
+
+    #[test]
+    fn given_domain_edge_cases_when_extended_executables_are_applied_then_no_nan_is_produced() {
+        for executable in [sin, cos, ln, exp, sqrt] {
+            for a in EDGE_CASES {
+                let value = executable(a, 0.);
+                assert!(
+                    !value.is_nan(),
+                    "{} produced NaN for {}",
+                    display_executable(executable, "a", "b"),
+                    a
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn given_protected_executables_when_inputs_are_out_of_domain_then_fallbacks_are_used() {
+        pretty_assertions::assert_eq!(ln(0., 0.), 0.);
+        pretty_assertions::assert_eq!(ln(-1., 0.), 0.);
+        pretty_assertions::assert_eq!(sqrt(-4., 0.), 2.);
+        pretty_assertions::assert_eq!(sin(R32::INFINITY, 0.), 0.);
+        pretty_assertions::assert_eq!(cos(R32::NEG_INFINITY, 0.), 0.);
+        assert!(exp(1e30, 0.).is_finite());
+        pretty_assertions::assert_eq!(exp(1e30, 0.), EXP_INPUT_BOUND.exp());
+    }
+
+    #[test]
+    fn given_named_executables_when_rust_expression_then_each_one_is_rendered() {
+        for (name, executable) in NAMED_EXECUTABLES {
+            assert!(
+                rust_expression(*executable).is_some(),
+                "{} is not rendered",
+                name
+            );
+        }
+
+        pretty_assertions::assert_eq!(
+            rust_expression(divide).as_deref(),
+            Some("if b == 0.0 { a } else { a / b }")
+        );
+        pretty_assertions::assert_eq!(rust_expression(load_half).as_deref(), Some("0.5"));
+    }
+
+    #[test]
+    fn given_zero_denominator_when_divide_then_numerator_is_returned() {
+        pretty_assertions::assert_eq!(divide(3., 0.), 3.);
+        pretty_assertions::assert_eq!(divide(-3., -0.), -3.);
+        pretty_assertions::assert_eq!(divide(3., 2.), 1.5);
+    }
+
+    #[test]
+    fn given_copy_when_applied_then_operand_is_returned_unchanged() {
+        for (a, b) in [(1., 2.), (-3., 0.5), (0., -0.)] {
+            pretty_assertions::assert_eq!(copy(a, b), b);
+        }
+        pretty_assertions::assert_eq!(display_executable(copy, "R[0]", "I[1]"), "I[1]");
+        pretty_assertions::assert_eq!(executable_from_name("copy").map(|op| op(1., 2.)), Some(2.));
+    }
+
+    #[test]
+    fn given_constant_loads_when_applied_then_operands_are_ignored() {
+        let expected = [0., 1., -1., 2., 0.5];
+
+        for (load, value) in LOAD_CONST_EXECUTABLES.iter().zip(expected) {
+            for (a, b) in [(0., 0.), (5., -7.), (R32::INFINITY, R32::NAN)] {
+                pretty_assertions::assert_eq!(load(a, b), value);
+            }
+
+            let name = executable_name(*load).unwrap();
+            pretty_assertions::assert_eq!(
+                executable_from_name(name).unwrap() as usize,
+                *load as usize
+            );
+        }
+
+        pretty_assertions::assert_eq!(display_executable(load_half, "a", "b"), "0.5");
+        pretty_assertions::assert_eq!(display_executable(load_minus_one, "a", "b"), "-1.0");
+    }
+
+    #[test]
+    fn given_fractional_operands_when_integer_executables_are_applied_then_operands_are_truncated()
+    {
+        pretty_assertions::assert_eq!(integer_add(2.9, -1.5), 1.);
+        pretty_assertions::assert_eq!(integer_subtract(2.9, -1.5), 3.);
+        pretty_assertions::assert_eq!(integer_multiply(2.9, -1.5), -2.);
+        pretty_assertions::assert_eq!(integer_divide(7., 2.), 3.);
+        pretty_assertions::assert_eq!(integer_divide(7., 0.5), 7.);
+        pretty_assertions::assert_eq!(
+            display_executable(integer_multiply, "R[0]", "I[0]"),
+            "integer_multiply(R[0], I[0])"
+        );
+    }
 }
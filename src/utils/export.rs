@@ -0,0 +1,129 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    algorithm::GenerationStats, characteristics::FitnessScore, population::ComplexityBenchmark,
+};
+
+pub type FitnessBenchmark = ComplexityBenchmark<Option<FitnessScore>>;
+
+/// A single line of a benchmark export.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    pub generation: usize,
+    #[serde(flatten)]
+    pub benchmark: FitnessBenchmark,
+}
+
+impl From<&GenerationStats> for FitnessBenchmark {
+    fn from(stats: &GenerationStats) -> Self {
+        ComplexityBenchmark {
+            best: Some(stats.best),
+            median: Some(stats.median),
+            worst: Some(stats.worst),
+        }
+    }
+}
+
+impl From<&GenerationStats> for BenchmarkRecord {
+    fn from(stats: &GenerationStats) -> Self {
+        BenchmarkRecord {
+            generation: stats.generation,
+            benchmark: stats.into(),
+        }
+    }
+}
+
+/// A buffered writer over a fresh JSON Lines file at `path`, e.g. `HyperParameters::snapshot_path`.
+pub fn create_jsonl(path: impl AsRef<Path>) -> Result<BufWriter<File>, Box<dyn Error>> {
+    Ok(BufWriter::new(File::create(path)?))
+}
+
+/// Writes `value` as a single JSON Lines entry.
+pub fn write_jsonl_line<W, S>(writer: &mut W, value: &S) -> Result<(), Box<dyn Error>>
+where
+    W: Write,
+    S: Serialize,
+{
+    writeln!(writer, "{}", serde_json::to_string(value)?)?;
+    Ok(())
+}
+
+/// Writes one `{"generation":n,"best":..,"median":..,"worst":..}` object per line. Collect the
+/// records from an `after_generation` hook, e.g. `records.push(stats.into())`.
+pub fn write_benchmarks_jsonl<W>(
+    writer: &mut W,
+    records: &[BenchmarkRecord],
+) -> Result<(), Box<dyn Error>>
+where
+    W: Write,
+{
+    for record in records {
+        write_jsonl_line(writer, record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use crate::{
+        core::algorithm::{EventHooks, GenerationStats, GeneticAlgorithm, HyperParameters},
+        utils::test::TestOrganismLgp,
+    };
+
+    use super::{create_jsonl, write_benchmarks_jsonl, BenchmarkRecord};
+
+    #[test]
+    fn given_benchmarks_collected_after_each_generation_when_exported_then_every_generation_parses_back(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut hyper_params = HyperParameters::builder()
+            .with_population_size(10)
+            .with_max_generations(4)
+            .with_fitness_parameters(())
+            .with_program_parameters(())
+            .build()?;
+
+        let mut benchmarks: Vec<BenchmarkRecord> = vec![];
+        let mut collect = |stats: &GenerationStats| {
+            benchmarks.push(stats.into());
+            Ok(())
+        };
+        TestOrganismLgp::execute(
+            &mut hyper_params,
+            EventHooks::default().with_after_generation(&mut collect),
+        )?;
+
+        // Exporting a later slice keeps the recorded generation numbers.
+        let export = tempfile::NamedTempFile::new()?;
+        write_benchmarks_jsonl(&mut create_jsonl(export.path())?, &benchmarks[1..])?;
+
+        let records = BufReader::new(File::open(export.path())?)
+            .lines()
+            .map(|line| Ok(serde_json::from_str::<BenchmarkRecord>(&line?)?))
+            .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+        pretty_assertions::assert_eq!(records, benchmarks[1..].to_vec());
+        pretty_assertions::assert_eq!(
+            records
+                .iter()
+                .map(|record| record.generation)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        Ok(())
+    }
+}
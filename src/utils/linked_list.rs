@@ -1,6 +1,44 @@
 use std::{fmt, marker::PhantomData, mem, ptr::NonNull};
 
-use serde::{ser::SerializeSeq, Serialize};
+use serde::{
+    de::{SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize,
+};
+
+/// Why `CursorMut::swap` refused to swap. Both lists are left untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapError {
+    /// One of the lists has no nodes.
+    EmptyList,
+    /// A slice's start is not before its end.
+    EmptySlice { start: usize, end: usize },
+    /// A slice ends past the end of its list.
+    EndOutOfBounds { end: usize, len: usize },
+    /// The node before a slice boundary could not be reached.
+    MissingPredecessor,
+}
+
+impl fmt::Display for SwapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapError::EmptyList => write!(f, "Cannot swap slices of an empty list."),
+            SwapError::EmptySlice { start, end } => {
+                write!(f, "The slice [{}, {}) is empty.", start, end)
+            }
+            SwapError::EndOutOfBounds { end, len } => write!(
+                f,
+                "The slice ends at {}, past the end of a list of {} nodes.",
+                end, len
+            ),
+            SwapError::MissingPredecessor => {
+                write!(f, "The node before a slice boundary could not be reached.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SwapError {}
 
 pub struct LinkedList<T> {
     pub head: Option<Pointer<T>>,
@@ -12,10 +50,12 @@ pub struct LinkedList<T> {
 pub struct Node<T> {
     data: T,
     next: Option<Pointer<T>>,
+    prev: Option<Pointer<T>>,
 }
 
 pub struct Iter<'a, T> {
     pub next: Option<Pointer<T>>,
+    pub prev: Option<Pointer<T>>,
     pub length: usize,
     _marker: PhantomData<&'a T>,
 }
@@ -53,7 +93,7 @@ impl<'a, T> CursorMut<'a, T> {
             self.index = self.index.map(|idx| idx + 1);
 
             // We've reached the end, loop to ghost front
-            if self.index > Some(self.list.length) {
+            if self.current.is_none() {
                 self.reset()
             }
         } else {
@@ -70,12 +110,41 @@ impl<'a, T> CursorMut<'a, T> {
         return self.current;
     }
 
+    pub fn prev(&mut self) -> Option<Pointer<T>> {
+        // We're somewhere in the "middle"
+        if let Some(node) = self.current {
+            self.current = unsafe { (*node.as_ptr()).prev };
+            self.index = self.index.and_then(|idx| idx.checked_sub(1));
+
+            // We've moved past the head, loop to ghost front
+            if self.current.is_none() {
+                self.reset()
+            }
+        } else {
+            // we're at the front, go to tail
+            self.current = self.list.tail;
+            match self.current {
+                Some(_) => self.index = Some(self.list.len() - 1),
+                // Do nothing if tail is empty
+                None => return None,
+            }
+        }
+
+        self.current
+    }
+
     // We loop using the modulo operator to determine the "desired" index.
-    // TODO: Benchmark to determine performance impact of decision.
+    // Targets behind the cursor are reached by walking backwards.
     pub fn seek(&mut self, idx: usize) {
         let true_idx = idx % self.list.len();
+        let backwards = matches!(self.index, Some(index) if index > true_idx);
+
         while self.index != Some(true_idx) {
-            self.next();
+            if backwards {
+                self.prev();
+            } else {
+                self.next();
+            }
         }
     }
 
@@ -97,9 +166,10 @@ impl<'a, T> CursorMut<'a, T> {
         // We're somewhere between the head and the tail
         if let Some(current) = self.current {
             let n_nodes_used = self.index.unwrap() + 1;
+            let new_head = unsafe { (*current.as_ptr()).next };
             let new_linked_list = LinkedList {
-                head: unsafe { (*current.as_ptr()).next },
-                tail: self.list.tail,
+                head: new_head,
+                tail: new_head.and(self.list.tail),
                 length: self.list.length - n_nodes_used,
             };
 
@@ -108,6 +178,7 @@ impl<'a, T> CursorMut<'a, T> {
                 // Before: a -> b -> c -> d -> e (c -> current)
                 // After: a -> b > c && d -> e
                 (*current.as_ptr()).remove_next();
+                Node::link(None, new_head);
                 assert_eq!((*current.as_ptr()).next, None);
                 self.list.length = n_nodes_used;
                 // We become the new tail.
@@ -121,6 +192,103 @@ impl<'a, T> CursorMut<'a, T> {
         }
     }
 
+    /// Splits off the current node and everything after it, leaving the nodes before it in the
+    /// list. The cursor moves onto the new tail, or the ghost front when nothing is left. At the
+    /// ghost front, the whole list is split off.
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        if let (Some(current), Some(index)) = (self.current, self.index) {
+            let previous = unsafe { (*current.as_ptr()).prev };
+            let new_linked_list = LinkedList {
+                head: Some(current),
+                tail: self.list.tail,
+                length: self.list.length - index,
+            };
+
+            unsafe {
+                // Break the list
+                // Before: a -> b -> c -> d -> e (c -> current)
+                // After: a -> b && c -> d -> e
+                if let Some(previous) = previous {
+                    (*previous.as_ptr()).remove_next();
+                }
+                (*current.as_ptr()).prev = None;
+            }
+
+            self.list.length = index;
+            self.list.tail = previous;
+            if previous.is_none() {
+                self.list.head = None;
+                self.reset();
+            } else {
+                self.current = previous;
+                self.index = Some(index - 1);
+            }
+
+            new_linked_list
+        } else {
+            // We're at the spot before the head
+            mem::replace(self.list, LinkedList::new())
+        }
+    }
+
+    /// Splices a new node directly after the current node. When positioned at the ghost front,
+    /// the node becomes the new head. The cursor does not move.
+    pub fn insert_after(&mut self, data: T) {
+        let new_node = Node::new_dyn(data).as_ptr();
+
+        unsafe {
+            match self.current {
+                Some(current) => {
+                    let next = (*current.as_ptr()).next;
+                    Node::link(Some(current), Some(new_node));
+                    Node::link(Some(new_node), next);
+                }
+                None => {
+                    Node::link(Some(new_node), self.list.head);
+                    self.list.head = Some(new_node);
+                }
+            }
+
+            // We're the last node, so we become the new tail.
+            if (*new_node.as_ptr()).next.is_none() {
+                self.list.tail = Some(new_node);
+            }
+        }
+
+        self.list.length += 1;
+    }
+
+    /// Unlinks and frees the current node, returning its data. The cursor advances to the node
+    /// that followed it (keeping the same index), or to the ghost front if the tail was removed.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let current = self.current?;
+
+        unsafe {
+            let removed_node = Box::from_raw(current.as_ptr());
+            let previous = removed_node.prev;
+            let next = removed_node.next;
+
+            Node::link(previous, next);
+
+            if previous.is_none() {
+                self.list.head = next;
+            }
+
+            if next.is_none() {
+                self.list.tail = previous;
+            }
+
+            self.list.length -= 1;
+
+            match next {
+                Some(_) => self.current = next,
+                None => self.reset(),
+            }
+
+            Some(removed_node.data)
+        }
+    }
+
     fn reset(&mut self) {
         self.current = None;
         self.index = None
@@ -185,21 +353,32 @@ impl<'a, T> CursorMut<'a, T> {
         other_start_idx: usize,
         end_idx: Option<usize>,
         other_end_idx: Option<usize>,
-    ) -> Option<()> {
+    ) -> Result<(), SwapError> {
         if self.list.len() == 0 || other.list.len() == 0 {
-            return None;
+            return Err(SwapError::EmptyList);
         }
 
-        if start_idx >= end_idx.unwrap_or(self.list.len())
-            || other_start_idx >= other_end_idx.unwrap_or(other.list.len())
-        {
-            return None;
-        }
+        let slices = [
+            (
+                start_idx,
+                end_idx.unwrap_or(self.list.len()),
+                self.list.len(),
+            ),
+            (
+                other_start_idx,
+                other_end_idx.unwrap_or(other.list.len()),
+                other.list.len(),
+            ),
+        ];
+
+        for (start, end, len) in slices {
+            if start >= end {
+                return Err(SwapError::EmptySlice { start, end });
+            }
 
-        if other_end_idx.unwrap_or(other.list.len()) > other.list.len()
-            || end_idx.unwrap_or(self.list.len()) > self.list.len()
-        {
-            return None;
+            if end > len {
+                return Err(SwapError::EndOutOfBounds { end, len });
+            }
         }
 
         // MRE:
@@ -241,39 +420,46 @@ impl<'a, T> CursorMut<'a, T> {
         let self_end = self.next();
         let other_end = other.next();
 
+        // Everything past the heads must have a predecessor to relink.
+        if (start_idx > 0 && before_start.is_none())
+            || (other_start_idx > 0 && before_other_start.is_none())
+            || before_end.is_none()
+            || before_other_end.is_none()
+        {
+            return Err(SwapError::MissingPredecessor);
+        }
+
         // Swaps starts
         {
             if start_idx == 0 {
                 // point self head to other
                 self.list.head = other_start
-            } else {
-                unsafe {
-                    (*before_start?.as_ptr()).point_to(other_start);
-                }
             }
 
             if other_start_idx == 0 {
                 other.list.head = self_start;
-            } else {
-                unsafe {
-                    (*before_other_start?.as_ptr()).point_to(self_start);
-                }
+            }
+
+            unsafe {
+                Node::link(before_start, other_start);
+                Node::link(before_other_start, self_start);
             }
         }
 
         // Swap ends
         {
-            if end_idx == Some(self.list.len()) {
-                self.list.tail = other_end
+            // If a slice ran to the end of its list, the other slice's last node becomes the tail.
+            if self_end.is_none() {
+                self.list.tail = before_other_end
             }
 
-            if other_end_idx == Some(other.list.len()) {
-                other.list.tail = self_end
+            if other_end.is_none() {
+                other.list.tail = before_end
             }
 
             unsafe {
-                (*before_end?.as_ptr()).point_to(other_end);
-                (*before_other_end?.as_ptr()).point_to(self_end);
+                Node::link(before_end, other_end);
+                Node::link(before_other_end, self_end);
             }
         }
 
@@ -289,7 +475,11 @@ impl<'a, T> CursorMut<'a, T> {
 
         // TODO: Write a test to verify head, tail and length.
 
-        Some(())
+        // Indices are stale once the lists have been re-stitched.
+        self.reset();
+        other.reset();
+
+        Ok(())
     }
 }
 
@@ -301,7 +491,11 @@ trait NodeAccess {}
 
 impl<T> Node<T> {
     fn new(data: T) -> Self {
-        Node { data, next: None }
+        Node {
+            data,
+            next: None,
+            prev: None,
+        }
     }
 
     fn new_dyn(data: T) -> Box<Node<T>> {
@@ -323,6 +517,17 @@ impl<T> Node<T> {
         self.point_to(None)
     }
 
+    /// Points `previous` forward to `next` and `next` back to `previous`.
+    unsafe fn link(previous: Option<Pointer<T>>, next: Option<Pointer<T>>) {
+        if let Some(previous) = previous {
+            (*previous.as_ptr()).next = next;
+        }
+
+        if let Some(next) = next {
+            (*next.as_ptr()).prev = previous;
+        }
+    }
+
     pub fn next(&self) -> Option<&Node<T>> {
         unsafe { self.next.map(|node| node.as_ref()) }
     }
@@ -334,6 +539,14 @@ impl<T> Node<T> {
     pub fn next_ptr(&mut self) -> Option<Pointer<T>> {
         self.next
     }
+
+    pub fn prev(&self) -> Option<&Node<T>> {
+        unsafe { self.prev.map(|node| node.as_ref()) }
+    }
+
+    pub fn prev_ptr(&mut self) -> Option<Pointer<T>> {
+        self.prev
+    }
 }
 
 impl<T> LinkedList<T> {
@@ -355,41 +568,73 @@ impl<T> LinkedList<T> {
         self.len() == 0
     }
 
+    /// Drops every node past the first `len`. Does nothing if the list is not longer.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len() {
+            return;
+        }
+
+        if len == 0 {
+            self.clear();
+            return;
+        }
+
+        let mut cursor = self.cursor_mut();
+        cursor.seek(len - 1);
+        cursor.split_after();
+    }
+
     pub fn append(&mut self, data: T) {
         unsafe {
             let node = Node::new_dyn(data);
             let some_leaked_node = node.as_ptr();
-            match self.head {
+            match self.tail {
                 None => {
                     self.head = Some(some_leaked_node);
                 }
-                Some(head_ptr) => {
-                    match self.tail {
-                        None => {
-                            (*head_ptr.as_ptr()).point_to(Some(some_leaked_node));
-                        }
-                        Some(tail_ptr) => {
-                            // Debug: Double free -- be careful
-                            (*tail_ptr.as_ptr()).point_to(Some(some_leaked_node));
-                        }
-                    }
-
-                    self.tail = Some(some_leaked_node);
+                Some(tail_ptr) => {
+                    // Debug: Double free -- be careful
+                    Node::link(Some(tail_ptr), Some(some_leaked_node));
                 }
             }
 
+            self.tail = Some(some_leaked_node);
             self.length += 1;
         }
     }
 
+    /// Splices `other` onto the tail in O(1), taking over its nodes without reallocating them.
+    pub fn append_list(&mut self, mut other: LinkedList<T>) {
+        // Detach the nodes from `other` so its `Drop` has nothing left to free.
+        let head = other.head.take();
+        let tail = other.tail.take();
+        let length = mem::take(&mut other.length);
+
+        if head.is_none() {
+            return;
+        }
+
+        unsafe {
+            Node::link(self.tail, head);
+        }
+
+        if self.head.is_none() {
+            self.head = head;
+        }
+
+        self.tail = tail;
+        self.length += length;
+    }
+
     pub fn dequeue(&mut self) -> Option<Box<Node<T>>> {
         self.head.map(|node| unsafe {
             let contained_node = Box::from_raw(node.as_ptr());
 
             self.head = contained_node.next;
 
-            if self.head.is_none() {
-                self.tail = None
+            match self.head {
+                Some(head) => (*head.as_ptr()).prev = None,
+                None => self.tail = None,
             }
 
             self.length -= 1;
@@ -409,6 +654,7 @@ impl<T> LinkedList<T> {
     pub fn iter(&self) -> Iter<T> {
         Iter {
             next: self.head,
+            prev: self.tail,
             length: self.length,
             _marker: PhantomData,
         }
@@ -437,6 +683,38 @@ impl<T> LinkedList<T> {
     pub fn len(&self) -> usize {
         self.length
     }
+
+    /// Iterates the elements in `[start, end)` (clamped to the list) without modifying it, e.g.
+    /// to inspect a crossover window before committing to it. This is O(end).
+    pub fn range(&self, start: usize, end: usize) -> Iter<'_, T> {
+        let end = end.min(self.len());
+        let start = start.min(end);
+        let advance =
+            |node: Option<Pointer<T>>| node.and_then(|node| unsafe { (*node.as_ptr()).next });
+
+        let first = (0..start).fold(self.head, |node, _| advance(node));
+        let last = match end - start {
+            0 => None,
+            length => (1..length).fold(first, |node, _| advance(node)),
+        };
+
+        Iter {
+            next: first,
+            prev: last,
+            length: end - start,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the element at `index`, walking from the head. This is O(n).
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.iter().nth(index)
+    }
+
+    /// Returns the element at `index` mutably, walking from the head. This is O(n).
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.iter_mut().nth(index)
+    }
 }
 
 // Reference Iterator
@@ -444,7 +722,12 @@ impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.length == 0 {
+            return None;
+        }
+
         self.next.map(|node| unsafe {
+            self.length -= 1;
             self.next = (*node.as_ptr()).next;
             &(*node.as_ptr()).data
         })
@@ -455,6 +738,20 @@ impl<'a, T> Iterator for Iter<'a, T> {
     }
 }
 
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.length == 0 {
+            return None;
+        }
+
+        self.prev.map(|node| unsafe {
+            self.length -= 1;
+            self.prev = (*node.as_ptr()).prev;
+            &(*node.as_ptr()).data
+        })
+    }
+}
+
 impl<'a, T> ExactSizeIterator for Iter<'a, T> {
     fn len(&self) -> usize {
         self.length
@@ -628,9 +925,45 @@ where
     }
 }
 
+struct LinkedListVisitor<E>(PhantomData<E>);
+
+impl<'de, E> Visitor<'de> for LinkedListVisitor<E>
+where
+    E: Deserialize<'de>,
+{
+    type Value = LinkedList<E>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut list = LinkedList::new();
+        while let Some(element) = seq.next_element()? {
+            list.append(element);
+        }
+        Ok(list)
+    }
+}
+
+impl<'de, E> Deserialize<'de> for LinkedList<E>
+where
+    E: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(LinkedListVisitor(PhantomData))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{LinkedList, Node};
+    use super::{LinkedList, Node, SwapError};
 
     #[test]
     fn given_lists_when_swap_single_element_then_lists_are_mutated() {
@@ -644,12 +977,68 @@ mod tests {
         let mut c1 = l1.cursor_mut();
         let mut c2 = l2.cursor_mut();
 
-        c1.swap(&mut c2, 2, 3, Some(3), Some(4));
+        c1.swap(&mut c2, 2, 3, Some(3), Some(4)).unwrap();
 
         itertools::assert_equal(l1, [1, 2, 9, 4, 5]);
         itertools::assert_equal(l2, [6, 7, 8, 3, 10]);
     }
 
+    #[test]
+    fn given_two_lists_when_appended_then_nodes_are_spliced_onto_the_tail() {
+        let mut front = LinkedList::new();
+        let mut back = LinkedList::new();
+        front.extend([1, 2, 3]);
+        back.extend([4, 5]);
+
+        front.append_list(back);
+
+        assert_eq!(front.len(), 5);
+        itertools::assert_equal(front.iter().copied(), [1, 2, 3, 4, 5]);
+        itertools::assert_equal(front.iter().rev().copied(), [5, 4, 3, 2, 1]);
+        assert_eq!(front.tail().map(|node| node.data), Some(5));
+
+        front.append_list(LinkedList::new());
+        assert_eq!(front.len(), 5);
+
+        let mut empty = LinkedList::new();
+        empty.append_list(front);
+        itertools::assert_equal(empty.iter().copied(), [1, 2, 3, 4, 5]);
+        empty.append(6);
+        assert_eq!(empty.len(), 6);
+    }
+
+    #[test]
+    fn given_a_window_when_range_then_only_its_elements_are_yielded() {
+        let mut list = LinkedList::new();
+        list.extend([10, 20, 30, 40]);
+
+        itertools::assert_equal(list.range(1, 3).copied(), [20, 30]);
+        itertools::assert_equal(list.range(1, 3).rev().copied(), [30, 20]);
+        itertools::assert_equal(list.range(2, 10).copied(), [30, 40]);
+        assert_eq!(list.range(3, 3).next(), None);
+        assert_eq!(list.range(5, 1).len(), 0);
+        itertools::assert_equal(list.iter().copied(), [10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn given_a_list_when_truncated_then_only_the_leading_elements_remain() {
+        let mut linked_list = LinkedList::new();
+        linked_list.extend([1, 2, 3, 4, 5]);
+
+        linked_list.truncate(10);
+        assert_eq!(linked_list.len(), 5);
+
+        linked_list.truncate(3);
+        assert_eq!(linked_list.len(), 3);
+        assert_eq!(linked_list.tail().map(|node| node.data), Some(3));
+        linked_list.append(6);
+        itertools::assert_equal(linked_list.iter().copied(), [1, 2, 3, 6]);
+
+        linked_list.truncate(0);
+        assert!(linked_list.is_empty());
+        assert!(linked_list.head().is_none());
+    }
+
     #[test]
     fn given_a_list_of_elems_when_extended_then_linked_list_is_fill_with_elements() {
         let elements = [1, 2, 3, 4, 5];
@@ -767,6 +1156,70 @@ mod tests {
         assert_eq!(split_cursor.current(), Some(&mut 2));
     }
 
+    #[test]
+    fn given_linked_lists_when_split_before_is_called_then_the_current_node_starts_the_new_list() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3, 4, 5]);
+
+        let mut cursor = list.cursor_mut();
+        cursor.seek(2);
+
+        assert_eq!(cursor.current(), Some(&mut 3));
+
+        let split_list = cursor.split_before();
+
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.next();
+        assert_eq!(cursor.current(), None);
+
+        itertools::assert_equal(list.iter().copied(), [1, 2]);
+        itertools::assert_equal(list.iter().rev().copied(), [2, 1]);
+        itertools::assert_equal(split_list.iter().copied(), [3, 4, 5]);
+        itertools::assert_equal(split_list.iter().rev().copied(), [5, 4, 3]);
+        assert_eq!((list.len(), split_list.len()), (2, 3));
+    }
+
+    #[test]
+    fn given_cursor_at_head_or_ghost_front_when_split_before_is_called_then_the_whole_list_is_split_off(
+    ) {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+
+        let mut cursor = list.cursor_mut();
+        cursor.next();
+
+        assert_eq!(cursor.current(), Some(&mut 1));
+
+        let mut split_list = cursor.split_before();
+
+        assert_eq!(cursor.current(), None);
+        assert!(list.is_empty());
+        assert!(list.head().is_none() && list.tail().is_none());
+        itertools::assert_equal(split_list.iter().copied(), [1, 2, 3]);
+
+        let mut ghost_cursor = split_list.cursor_mut();
+        let whole_list = ghost_cursor.split_before();
+
+        assert!(split_list.is_empty());
+        itertools::assert_equal(whole_list.iter().copied(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn given_cursor_when_seek_before_zero_then_cursor_is_at_the_ghost_front() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+
+        let mut cursor = list.cursor_mut();
+        cursor.seek(2);
+        cursor.seek_before(0);
+
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.index, None);
+
+        cursor.next();
+        assert_eq!(cursor.current(), Some(&mut 1));
+    }
+
     #[test]
     fn given_linked_list_cursor_when_seek_then_element_at_index_is_reached() {
         let elems = [1, 2, 3, 4, 5];
@@ -781,6 +1234,45 @@ mod tests {
         assert_eq!(cursor.current(), Some(&mut 1));
     }
 
+    #[test]
+    fn given_invalid_slices_when_swap_then_the_violated_invariant_is_reported_and_lists_are_untouched(
+    ) {
+        let mut l1 = LinkedList::new();
+        let mut l2 = LinkedList::new();
+        l1.extend([1, 2, 3, 4, 5]);
+        l2.extend([6, 7, 8]);
+
+        let mut c1 = l1.cursor_mut();
+        let mut c2 = l2.cursor_mut();
+
+        assert_eq!(
+            c1.swap(&mut c2, 3, 0, Some(3), Some(2)),
+            Err(SwapError::EmptySlice { start: 3, end: 3 })
+        );
+        assert_eq!(
+            c1.swap(&mut c2, 0, 2, Some(2), Some(1)),
+            Err(SwapError::EmptySlice { start: 2, end: 1 })
+        );
+        assert_eq!(
+            c1.swap(&mut c2, 0, 0, Some(6), Some(1)),
+            Err(SwapError::EndOutOfBounds { end: 6, len: 5 })
+        );
+        assert_eq!(
+            c1.swap(&mut c2, 0, 0, Some(1), Some(4)),
+            Err(SwapError::EndOutOfBounds { end: 4, len: 3 })
+        );
+
+        itertools::assert_equal(l1.iter().copied(), [1, 2, 3, 4, 5]);
+        itertools::assert_equal(l2.iter().copied(), [6, 7, 8]);
+
+        let mut empty = LinkedList::new();
+        let mut c1 = l1.cursor_mut();
+        assert_eq!(
+            c1.swap(&mut empty.cursor_mut(), 0, 0, None, None),
+            Err(SwapError::EmptyList)
+        );
+    }
+
     #[test]
     fn given_linked_list_cursors_when_swap_with_no_end_then_pointers_are_swapped() {
         let e1 = [1, 2, 3, 4, 5];
@@ -793,7 +1285,7 @@ mod tests {
         let mut c1 = l1.cursor_mut();
         let mut c2 = l2.cursor_mut();
 
-        c1.swap(&mut c2, 2, 2, None, None);
+        c1.swap(&mut c2, 2, 2, None, None).unwrap();
 
         let e12 = [1, 2, 8, 9, 10];
         let e21 = [6, 7, 3, 4, 5];
@@ -814,7 +1306,7 @@ mod tests {
         let mut c1 = l1.cursor_mut();
         let mut c2 = l2.cursor_mut();
 
-        c1.swap(&mut c2, 2, 2, Some(4), Some(4));
+        c1.swap(&mut c2, 2, 2, Some(4), Some(4)).unwrap();
 
         let e12 = [1, 2, 8, 9, 5];
         let e21 = [6, 7, 3, 4, 10];
@@ -835,7 +1327,7 @@ mod tests {
         let mut c1 = l1.cursor_mut();
         let mut c2 = l2.cursor_mut();
 
-        c1.swap(&mut c2, 0, 0, Some(2), Some(2));
+        c1.swap(&mut c2, 0, 0, Some(2), Some(2)).unwrap();
 
         let e12 = [6, 7, 3, 4, 5];
         let e21 = [1, 2, 8, 9, 10];
@@ -843,4 +1335,219 @@ mod tests {
         itertools::assert_equal(l1, e12);
         itertools::assert_equal(l2, e21);
     }
+
+    #[test]
+    fn given_cursor_at_head_when_insert_after_then_node_follows_head() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+
+        let mut cursor = list.cursor_mut();
+        cursor.next();
+        cursor.insert_after(10);
+
+        assert_eq!(cursor.index, Some(0));
+        assert_eq!(cursor.current(), Some(&mut 1));
+
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.tail().map(|node| node.data), Some(3));
+        itertools::assert_equal(list, [1, 10, 2, 3]);
+    }
+
+    #[test]
+    fn given_cursor_in_middle_when_insert_after_then_node_is_spliced() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+
+        let mut cursor = list.cursor_mut();
+        cursor.seek(1);
+        cursor.insert_after(10);
+        cursor.next();
+
+        assert_eq!(cursor.index, Some(2));
+        assert_eq!(cursor.current(), Some(&mut 10));
+
+        assert_eq!(list.len(), 4);
+        itertools::assert_equal(list, [1, 2, 10, 3]);
+    }
+
+    #[test]
+    fn given_cursor_at_tail_when_insert_after_then_tail_is_updated() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+
+        let mut cursor = list.cursor_mut();
+        cursor.seek(2);
+        cursor.insert_after(10);
+
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.tail().map(|node| node.data), Some(10));
+
+        list.append(11);
+        itertools::assert_equal(list, [1, 2, 3, 10, 11]);
+    }
+
+    #[test]
+    fn given_cursor_at_ghost_front_when_insert_after_then_node_becomes_head() {
+        let mut list = LinkedList::new();
+        list.extend([1]);
+
+        let mut cursor = list.cursor_mut();
+        cursor.insert_after(10);
+
+        assert_eq!(cursor.index, None);
+        assert_eq!(cursor.current(), None);
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.head().map(|node| node.data), Some(10));
+        assert_eq!(list.tail().map(|node| node.data), Some(1));
+
+        let mut empty_list = LinkedList::new();
+        let mut empty_cursor = empty_list.cursor_mut();
+        empty_cursor.insert_after(1);
+
+        assert_eq!(empty_list.len(), 1);
+        assert_eq!(empty_list.head().map(|node| node.data), Some(1));
+        assert_eq!(empty_list.tail().map(|node| node.data), Some(1));
+    }
+
+    #[test]
+    fn given_cursor_when_remove_current_then_node_is_unlinked_and_cursor_advances() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3, 4]);
+
+        let mut cursor = list.cursor_mut();
+
+        assert_eq!(cursor.remove_current(), None);
+
+        // Head
+        cursor.next();
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.index, Some(0));
+        assert_eq!(cursor.current(), Some(&mut 2));
+
+        // Middle
+        cursor.next();
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.index, Some(1));
+        assert_eq!(cursor.current(), Some(&mut 4));
+
+        // Tail
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.index, None);
+        assert_eq!(cursor.current(), None);
+
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.head().map(|node| node.data), Some(2));
+        assert_eq!(list.tail().map(|node| node.data), Some(2));
+
+        let mut cursor = list.cursor_mut();
+        cursor.next();
+        assert_eq!(cursor.remove_current(), Some(2));
+
+        assert!(list.is_empty());
+        assert!(list.head().is_none());
+        assert!(list.tail().is_none());
+    }
+
+    #[test]
+    fn given_linked_list_cursor_when_prev_is_called_then_nodes_are_cycled_backwards() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+
+        let mut cursor = list.cursor_mut();
+
+        cursor.prev();
+        assert_eq!(cursor.index, Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.prev();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.prev();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.prev();
+        assert_eq!(cursor.index, None);
+        assert_eq!(cursor.current(), None);
+
+        cursor.seek(2);
+        cursor.seek(0);
+        assert_eq!(cursor.index, Some(0));
+        assert_eq!(cursor.current(), Some(&mut 1));
+    }
+
+    #[test]
+    fn given_linked_list_when_iterated_from_both_ends_then_elements_are_yielded_once() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3, 4, 5]);
+
+        itertools::assert_equal(list.iter().rev(), &[5, 4, 3, 2, 1]);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn given_mutated_lists_when_iterated_backwards_then_prev_pointers_are_consistent() {
+        let mut l1 = LinkedList::new();
+        let mut l2 = LinkedList::new();
+        l1.extend([1, 2, 3, 4, 5]);
+        l2.extend([6, 7, 8, 9, 10]);
+
+        let mut c1 = l1.cursor_mut();
+        let mut c2 = l2.cursor_mut();
+        c1.swap(&mut c2, 0, 2, Some(2), None).unwrap();
+
+        c1.seek(1);
+        c1.insert_after(11);
+        c1.seek(3);
+        c1.remove_current();
+
+        itertools::assert_equal(l1.iter(), &[8, 9, 11, 3, 4, 5]);
+        itertools::assert_equal(l1.iter().rev(), &[5, 4, 3, 11, 9, 8]);
+        itertools::assert_equal(l2.iter().rev(), &[2, 1, 7, 6]);
+
+        l2.append(12);
+        assert_eq!(l2.dequeue().map(|node| node.data), Some(6));
+        itertools::assert_equal(l2.iter().rev(), &[12, 2, 1, 7]);
+    }
+
+    #[test]
+    fn given_serialized_list_when_deserialized_then_lists_are_equal() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3, 4, 5]);
+
+        let serialized = serde_json::to_string(&list).unwrap();
+        assert_eq!(serialized, "[1,2,3,4,5]");
+
+        let deserialized: LinkedList<i32> = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized, list);
+        assert_eq!(deserialized.len(), 5);
+        assert_eq!(deserialized.tail().map(|node| node.data), Some(5));
+    }
+
+    #[test]
+    fn given_linked_list_when_get_is_called_then_element_at_index_is_returned() {
+        let mut list = LinkedList::new();
+        list.extend([1, 2, 3]);
+
+        assert_eq!(list.get(0), Some(&1));
+        assert_eq!(list.get(2), Some(&3));
+        assert_eq!(list.get(3), None);
+
+        if let Some(element) = list.get_mut(1) {
+            *element = 10;
+        }
+
+        assert_eq!(list.get_mut(3), None);
+        itertools::assert_equal(list, [1, 10, 3]);
+
+        let empty_list = LinkedList::<i32>::new();
+        assert_eq!(empty_list.get(0), None);
+    }
 }
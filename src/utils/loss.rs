@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::registers::R32;
+
+/// Mean of `(predicted - actual)^2` over `(predicted, actual)` observations; 0 when there are
+/// none.
+pub fn mean_squared_error(observations: &[(R32, R32)]) -> f32 {
+    mean(observations, |predicted, actual| {
+        (predicted - actual).powi(2)
+    })
+}
+
+/// Coefficient of determination, `1 - SS_res / SS_tot`, over `(predicted, actual)` observations:
+/// 1 for a perfect fit, 0 for always predicting the mean of the actuals and negative for worse.
+/// With a constant target `SS_tot` is 0, so a perfect fit scores 1 and anything else 0.
+pub fn r_squared(observations: &[(R32, R32)]) -> f32 {
+    let mean_actual = mean(observations, |_, actual| actual);
+    let residual_sum_of_squares: f32 = observations
+        .iter()
+        .map(|(predicted, actual)| (actual - predicted).powi(2))
+        .sum();
+    let total_sum_of_squares: f32 = observations
+        .iter()
+        .map(|(_, actual)| (actual - mean_actual).powi(2))
+        .sum();
+
+    if total_sum_of_squares == 0. {
+        return if residual_sum_of_squares == 0. {
+            1.
+        } else {
+            0.
+        };
+    }
+
+    1. - residual_sum_of_squares / total_sum_of_squares
+}
+
+/// Quadratic for errors up to `delta` and linear beyond, so a few outliers cannot dominate the
+/// loss the way they do under `mean_squared_error`. Lower is better: score programs with it
+/// under `FitnessDirection::Minimize`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HuberLoss {
+    pub delta: f32,
+}
+
+impl HuberLoss {
+    pub fn new(delta: f32) -> Self {
+        assert!(
+            delta > 0.,
+            "Huber loss needs a positive delta, got {}.",
+            delta
+        );
+        HuberLoss { delta }
+    }
+
+    /// Mean Huber loss over `(predicted, actual)` observations; 0 when there are none.
+    pub fn loss(&self, observations: &[(R32, R32)]) -> f32 {
+        let HuberLoss { delta } = *self;
+
+        mean(observations, |predicted, actual| {
+            let error = (predicted - actual).abs();
+            if error <= delta {
+                0.5 * error.powi(2)
+            } else {
+                delta * (error - 0.5 * delta)
+            }
+        })
+    }
+}
+
+fn mean(observations: &[(R32, R32)], f: impl Fn(R32, R32) -> f32) -> f32 {
+    if observations.is_empty() {
+        return 0.;
+    }
+
+    observations
+        .iter()
+        .map(|(predicted, actual)| f(*predicted, *actual))
+        .sum::<f32>()
+        / observations.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mean_squared_error, r_squared, HuberLoss};
+
+    #[test]
+    fn given_small_errors_when_huber_loss_is_computed_then_it_is_half_the_squared_error() {
+        let observations = [(1., 1.5), (2., 1.5)];
+
+        pretty_assertions::assert_eq!(HuberLoss::new(1.).loss(&observations), 0.125);
+        pretty_assertions::assert_eq!(mean_squared_error(&observations), 0.25);
+    }
+
+    #[test]
+    fn given_one_large_outlier_when_losses_are_compared_then_huber_is_less_affected() {
+        let clean = vec![(1., 1.5), (2., 1.5), (3., 3.5), (4., 3.5)];
+        let mut with_outlier = clean.clone();
+        with_outlier.push((5., 105.));
+
+        let huber = HuberLoss::new(1.);
+        let mse_growth = mean_squared_error(&with_outlier) / mean_squared_error(&clean);
+        let huber_growth = huber.loss(&with_outlier) / huber.loss(&clean);
+
+        pretty_assertions::assert_eq!(huber.loss(&with_outlier), 20.);
+        assert!(huber_growth < mse_growth / 10.);
+    }
+
+    #[test]
+    fn given_perfect_and_mean_predictors_when_r_squared_is_computed_then_they_score_one_and_zero() {
+        let actuals = [1., 2., 4., 9.];
+        let perfect = actuals.map(|actual| (actual, actual));
+        let mean_predictor = actuals.map(|actual| (4., actual));
+
+        pretty_assertions::assert_eq!(r_squared(&perfect), 1.);
+        assert!(r_squared(&mean_predictor).abs() < 1e-6);
+    }
+
+    #[test]
+    fn given_a_constant_target_when_r_squared_is_computed_then_only_a_perfect_fit_scores_one() {
+        pretty_assertions::assert_eq!(r_squared(&[(3., 3.), (3., 3.)]), 1.);
+        pretty_assertions::assert_eq!(r_squared(&[(2., 3.), (3., 3.)]), 0.);
+    }
+}
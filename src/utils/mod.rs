@@ -1,5 +1,7 @@
 pub mod executables;
+pub mod export;
 pub mod linked_list;
+pub mod loss;
 pub mod plots;
 pub mod random;
 pub mod test;
@@ -5,7 +5,10 @@ use plotters::{
     style::{colors, IntoFont, WHITE},
 };
 
-use crate::core::{characteristics::Fitness, population::Population};
+use crate::core::{
+    characteristics::Fitness,
+    population::{ComplexityBenchmark, Population},
+};
 
 pub fn plot_population_benchmarks<T>(
     populations: Vec<Population<T>>,
@@ -29,29 +32,25 @@ where
 
     chart.configure_mesh().draw()?;
 
-    let benchmarks: Vec<[f32; 3]> = populations
-        .into_iter()
-        .map(|population| {
-            let best = population.first();
-            let median = population.middle();
-            let worst = population.last();
-
-            let benchmark =
-                [best, median, worst].map(|quantile| quantile.unwrap().get_fitness().unwrap());
-
-            benchmark
+    // Populations without any evaluated individual leave a gap in the chart.
+    let benchmarks: Vec<(usize, [f32; 3])> = populations
+        .iter()
+        .enumerate()
+        .filter_map(|(index, population)| {
+            let ComplexityBenchmark {
+                best,
+                median,
+                worst,
+            } = population.get_benchmark_individuals();
+
+            Some((index, [best?, median?, worst?]))
         })
         .collect();
 
     chart
-        .draw_series(
-            benchmarks
-                .iter()
-                .enumerate()
-                .map(|(index, [best, median, worst])| {
-                    ErrorBar::new_vertical(index, *worst, *median, *best, colors::BLUE, 10)
-                }),
-        )
+        .draw_series(benchmarks.iter().map(|(index, [best, median, worst])| {
+            ErrorBar::new_vertical(*index, *worst, *median, *best, colors::BLUE, 10)
+        }))
         .unwrap();
 
     root.present()?;
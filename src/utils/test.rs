@@ -1,12 +1,19 @@
 // For testing purposes only (binary classification).
 
+use derivative::Derivative;
 use derive_new::new;
-use rand::{distributions::Standard, prelude::Distribution};
+use rand::{distributions::Standard, prelude::Distribution, Rng};
 use serde::{Deserialize, Serialize};
 use strum::EnumCount;
 
 use crate::{
-    core::{algorithm::GeneticAlgorithm, inputs::ValidInput, program::Program, registers::R32},
+    core::{
+        algorithm::GeneticAlgorithm,
+        characteristics::{Breed, Fitness, FitnessScore, Generate, Length, Lineage, Mutate},
+        inputs::ValidInput,
+        program::Program,
+        registers::R32,
+    },
     extensions::classification::{ClassificationInput, ClassificationParameters},
 };
 
@@ -51,3 +58,112 @@ impl Distribution<TestInput> for Standard {
         TestInput(data)
     }
 }
+
+/// An organism with scripted fitness scores, used to exercise the algorithm without running
+/// programs.
+#[derive(Clone, Debug, Serialize, Derivative, new)]
+#[derivative(PartialEq, Eq, PartialOrd)]
+pub struct TestOrganism {
+    #[derivative(PartialOrd = "ignore")]
+    pub surrogate_fitness: FitnessScore,
+    #[derivative(PartialOrd = "ignore")]
+    pub full_fitness: FitnessScore,
+    #[new(default)]
+    pub fitness: Option<FitnessScore>,
+    #[new(default)]
+    #[derivative(PartialEq = "ignore", PartialOrd = "ignore")]
+    pub n_full_evaluations: usize,
+}
+
+impl Fitness for TestOrganism {
+    type FitnessParameters = ();
+
+    fn eval_fitness(&mut self, _parameters: &mut Self::FitnessParameters) -> FitnessScore {
+        self.n_full_evaluations += 1;
+        self.fitness = Some(self.full_fitness);
+        self.full_fitness
+    }
+
+    fn eval_surrogate_fitness(
+        &mut self,
+        _parameters: &mut Self::FitnessParameters,
+    ) -> FitnessScore {
+        self.fitness = Some(self.surrogate_fitness);
+        self.surrogate_fitness
+    }
+
+    fn has_surrogate_fitness(_parameters: &Self::FitnessParameters) -> bool {
+        true
+    }
+
+    fn get_fitness(&self) -> Option<FitnessScore> {
+        self.fitness
+    }
+
+    fn set_fitness(&mut self, fitness: FitnessScore, _variance: Option<FitnessScore>) {
+        self.fitness = Some(fitness);
+    }
+
+    fn fitness_key(&self) -> Option<u64> {
+        Some(self.full_fitness.to_bits() as u64)
+    }
+}
+
+impl Generate for TestOrganism {
+    type GeneratorParameters = ();
+
+    fn generate_with_rng<R: Rng + ?Sized>(
+        _parameters: &Self::GeneratorParameters,
+        _rng: &mut R,
+    ) -> Self {
+        TestOrganism::new(0., 0.)
+    }
+}
+
+impl Mutate for TestOrganism {
+    fn mutate_with_rng<R: Rng + ?Sized>(
+        &self,
+        _parameters: &Self::GeneratorParameters,
+        _rng: &mut R,
+    ) -> Self {
+        TestOrganism::new(self.surrogate_fitness, self.full_fitness)
+    }
+}
+
+impl Length for TestOrganism {
+    fn length(&self) -> usize {
+        1
+    }
+}
+
+impl Lineage for TestOrganism {}
+
+impl Breed for TestOrganism {
+    fn two_point_crossover_with_rng<R: Rng + ?Sized>(
+        &self,
+        mate: &Self,
+        _rng: &mut R,
+    ) -> [Self; 2] {
+        [
+            TestOrganism::new(self.surrogate_fitness, self.full_fitness),
+            TestOrganism::new(mate.surrogate_fitness, mate.full_fitness),
+        ]
+    }
+
+    fn homologous_crossover_with_rng<R: Rng + ?Sized>(
+        &self,
+        mate: &Self,
+        rng: &mut R,
+    ) -> [Self; 2] {
+        self.two_point_crossover_with_rng(mate, rng)
+    }
+
+    fn uniform_crossover_with_rng<R: Rng + ?Sized>(&self, _mate: &Self, _rng: &mut R) -> Self {
+        TestOrganism::new(self.surrogate_fitness, self.full_fitness)
+    }
+}
+
+pub struct TestOrganismLgp;
+impl GeneticAlgorithm for TestOrganismLgp {
+    type O = TestOrganism;
+}
@@ -0,0 +1,189 @@
+use std::io::Write;
+
+use assert_cmd::Command;
+use tempfile::NamedTempFile;
+
+fn write_dataset() -> Result<NamedTempFile, Box<dyn std::error::Error>> {
+    let dataset = NamedTempFile::new()?;
+    for row in 0..20 {
+        let class = row % 2;
+        writeln!(&dataset, "{},{},{}", row, class as f32 * 10., class)?;
+    }
+
+    Ok(dataset)
+}
+
+fn write_config(body: &str) -> Result<NamedTempFile, Box<dyn std::error::Error>> {
+    let config = NamedTempFile::new()?;
+    write!(&config, "{}", body)?;
+
+    Ok(config)
+}
+
+#[test]
+fn given_a_classification_config_when_the_binary_runs_then_the_best_program_is_printed(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dataset = write_dataset()?;
+    let config = write_config(&format!(
+        r#"
+dataset = "{}"
+extension = "classification"
+n_features = 2
+n_classes = 2
+max_instructions = 10
+
+[hyper_parameters]
+population_size = 10
+max_generations = 3
+"#,
+        dataset.path().display()
+    ))?;
+
+    let output = Command::cargo_bin("lgp")?
+        .arg(config.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8(output)?;
+    assert!(stdout.contains("R["));
+    assert!(stdout.lines().any(|line| line.starts_with("fitness: ")));
+
+    Ok(())
+}
+
+#[test]
+fn given_a_regression_config_when_the_binary_runs_then_the_best_program_and_its_error_are_printed(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dataset = NamedTempFile::new()?;
+    for row in 0..20 {
+        writeln!(&dataset, "{},{}", row, row as f32 * 2.)?;
+    }
+    let config = write_config(&format!(
+        r#"
+dataset = "{}"
+extension = "regression"
+n_features = 1
+max_instructions = 10
+
+[hyper_parameters]
+population_size = 10
+max_generations = 3
+"#,
+        dataset.path().display()
+    ))?;
+
+    let output = Command::cargo_bin("lgp")?
+        .arg(config.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8(output)?;
+    assert!(stdout.contains("R["));
+    assert!(stdout
+        .lines()
+        .any(|line| line.starts_with("mean absolute error: ")));
+
+    Ok(())
+}
+
+#[test]
+fn given_a_mismatched_feature_count_when_the_binary_runs_then_it_fails(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dataset = write_dataset()?;
+    let config = write_config(&format!(
+        r#"
+dataset = "{}"
+extension = "classification"
+n_features = 3
+n_classes = 2
+"#,
+        dataset.path().display()
+    ))?;
+
+    let output = Command::cargo_bin("lgp")?
+        .arg(config.path())
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+
+    let stderr = String::from_utf8(output)?;
+    assert!(stderr.contains("Row 0 has 2 features, expected 3."));
+
+    Ok(())
+}
+
+#[test]
+fn given_hyper_parameters_beyond_the_basics_when_the_binary_runs_then_they_are_accepted(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dataset = write_dataset()?;
+    let config = write_config(&format!(
+        r#"
+dataset = "{}"
+extension = "classification"
+n_features = 2
+n_classes = 2
+max_instructions = 10
+
+[hyper_parameters]
+population_size = 10
+max_generations = 2
+crossover = "Uniform"
+dedup_population = true
+replacement = {{ HillClimb = {{ epsilon = 0.0 }} }}
+"#,
+        dataset.path().display()
+    ))?;
+
+    Command::cargo_bin("lgp")?
+        .arg(config.path())
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn given_an_invalid_or_unknown_hyper_parameter_when_the_binary_runs_then_it_is_reported(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dataset = write_dataset()?;
+
+    for (hyper_parameter, message) in [
+        ("gap = 2.0", "The gap must be within [0, 1], got 2."),
+        ("population = 10", "unknown field `population`"),
+    ] {
+        let config = write_config(&format!(
+            r#"
+dataset = "{}"
+extension = "classification"
+n_features = 2
+n_classes = 2
+
+[hyper_parameters]
+{}
+"#,
+            dataset.path().display(),
+            hyper_parameter
+        ))?;
+
+        let output = Command::cargo_bin("lgp")?
+            .arg(config.path())
+            .assert()
+            .failure()
+            .get_output()
+            .stderr
+            .clone();
+
+        let stderr = String::from_utf8(output)?;
+        assert!(stderr.contains(message), "{}", stderr);
+    }
+
+    Ok(())
+}